@@ -6,7 +6,7 @@ use syn::{
     parse_quote,
     punctuated::Punctuated,
     spanned::Spanned,
-    Attribute, Field, Ident, LitStr, Token,
+    Attribute, Field, Ident, LitStr, Path, Token,
 };
 
 use crate::parse::{Content, EventKind, RumaEventInput};
@@ -45,15 +45,16 @@ impl From<RumaEventInput> for RumaEvent {
 
         let mut fields = match kind {
             EventKind::Event => {
-                populate_event_fields(content_name.clone(), input.fields.unwrap_or_else(Vec::new))
+                populate_event_fields(content_name.clone(), input.fields.unwrap_or_default())
             }
-            EventKind::RoomEvent => populate_room_event_fields(
+            EventKind::RoomEvent => {
+                populate_room_event_fields(content_name.clone(), input.fields.unwrap_or_default())
+            }
+            EventKind::StateEvent => populate_state_fields(
                 content_name.clone(),
-                input.fields.unwrap_or_else(Vec::new),
+                input.state_key_type.unwrap_or_else(|| parse_quote!(String)),
+                input.fields.unwrap_or_default(),
             ),
-            EventKind::StateEvent => {
-                populate_state_fields(content_name.clone(), input.fields.unwrap_or_else(Vec::new))
-            }
         };
 
         fields.sort_unstable_by_key(|field| field.ident.clone().unwrap());
@@ -172,7 +173,7 @@ impl ToTokens for RumaEvent {
 
                     /// A key that determines which piece of room state the event represents.
                     fn state_key(&self) -> &str {
-                        &self.state_key
+                        self.state_key.as_ref()
                     }
                 }
             }
@@ -305,7 +306,11 @@ fn populate_room_event_fields(content_name: Ident, fields: Vec<Field>) -> Vec<Fi
 }
 
 /// Fills in the event's struct definition with fields common to all state events.
-fn populate_state_fields(content_name: Ident, fields: Vec<Field>) -> Vec<Field> {
+fn populate_state_fields(
+    content_name: Ident,
+    state_key_type: Path,
+    fields: Vec<Field>,
+) -> Vec<Field> {
     let mut fields = populate_room_event_fields(content_name.clone(), fields);
 
     let punctuated_fields: Punctuated<ParsableNamedField, Token![,]> = parse_quote! {
@@ -314,7 +319,7 @@ fn populate_state_fields(content_name: Ident, fields: Vec<Field>) -> Vec<Field>
         pub prev_content: Option<#content_name>,
 
         /// A key that determines which piece of room state the event represents.
-        pub state_key: String,
+        pub state_key: #state_key_type,
     };
 
     fields.extend(punctuated_fields.into_iter().map(|p| p.field));
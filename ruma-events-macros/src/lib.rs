@@ -23,7 +23,6 @@
     clippy::unicode_not_nfc,
     clippy::use_self,
     clippy::used_underscore_binding,
-    clippy::wrong_pub_self_convention,
     clippy::wrong_self_convention
 )]
 // Since we support Rust 1.36.0, we can't apply this suggestion yet
@@ -119,6 +118,27 @@ mod parse;
 /// # }
 /// ```
 ///
+/// The `state_key` of a state event is a `String` unless the state key has a more specific type,
+/// which is designated with `state_key_type`:
+///
+/// ```ignore
+/// # pub mod example {
+/// # use ruma_events_macros::ruma_event;
+/// ruma_event! {
+///     /// Defines the relationship of a child room to a space-room.
+///     SpaceChildEvent {
+///         kind: StateEvent,
+///         event_type: "m.space.child",
+///         state_key_type: ruma_identifiers::RoomId,
+///         content: {
+///             /// The servers to join the child room through.
+///             pub via: Option<Vec<String>>,
+///         }
+///     }
+/// }
+/// # }
+/// ```
+///
 /// If `content` and `content_type_alias` are both supplied, the second one listed will overwrite
 /// the first.
 ///
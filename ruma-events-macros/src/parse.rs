@@ -4,7 +4,7 @@ use syn::{
     braced,
     parse::{self, Parse, ParseStream},
     token::Colon,
-    Attribute, Expr, ExprLit, Field, FieldValue, Ident, Lit, LitStr, Member, Token, TypePath,
+    Attribute, Expr, ExprLit, Field, FieldValue, Ident, Lit, LitStr, Member, Path, Token, TypePath,
 };
 
 /// The entire `ruma_event!` macro structure directly as it appears in the source code..
@@ -24,6 +24,10 @@ pub struct RumaEventInput {
     /// converting from snake_case to CamelCase).
     pub event_type: LitStr,
 
+    /// The type of the `state_key` field of a state event, determined by the `state_key_type`
+    /// field. Defaults to `String`.
+    pub state_key_type: Option<Path>,
+
     /// Additional named struct fields in the top level event struct.
     pub fields: Option<Vec<Field>>,
 
@@ -40,6 +44,7 @@ impl Parse for RumaEventInput {
 
         let mut kind = None;
         let mut event_type = None;
+        let mut state_key_type = None;
         let mut fields = None;
         let mut content = None;
 
@@ -72,7 +77,7 @@ impl Parse for RumaEventInput {
                 RumaEventField::Value(field_value) => {
                     let ident = match field_value.member {
                         Member::Named(ident) => ident,
-                        Member::Unnamed(_) => panic!("fields with expression values in `ruma_event!` must be named `kind` or `event_type`, ."),
+                        Member::Unnamed(_) => panic!("fields with expression values in `ruma_event!` must be named `kind`, `event_type` or `state_key_type`."),
                     };
 
                     if ident == "kind" {
@@ -102,6 +107,13 @@ impl Parse for RumaEventInput {
                                 "value of field `event_type` is required to be a string literal by `ruma_event!`"
                             ),
                         })
+                    } else if ident == "state_key_type" {
+                        state_key_type = Some(match field_value.expr {
+                            Expr::Path(expr_path) => expr_path.path,
+                            _ => panic!(
+                                "value of field `state_key_type` is required to be a type path by `ruma_event!`"
+                            ),
+                        })
                     } else {
                         panic!("unexpected field-value pair with field name `{}`", ident);
                     }
@@ -113,6 +125,8 @@ impl Parse for RumaEventInput {
             panic!("field `kind` is required by `ruma_event!`");
         } else if event_type.is_none() {
             panic!("field `event_type` is required by `ruma_event!`");
+        } else if state_key_type.is_some() && kind != Some(EventKind::StateEvent) {
+            panic!("field `state_key_type` is only allowed for state events by `ruma_event!`");
         } else if content.is_none() {
             panic!(
                 "one field named `content` or `content_type_alias` is required by `ruma_event!`"
@@ -124,6 +138,7 @@ impl Parse for RumaEventInput {
             name,
             kind: kind.unwrap(),
             event_type: event_type.unwrap(),
+            state_key_type,
             fields,
             content: content.unwrap(),
         })
@@ -169,7 +184,7 @@ enum RumaEventField {
 
     /// A standard named struct field.
     ///
-    /// Used for `kind` and `event_type`.
+    /// Used for `kind`, `event_type` and `state_key_type`.
     Value(FieldValue),
 }
 
@@ -211,6 +226,7 @@ impl Parse for RumaEventField {
 /// The value of a field is a block with a type alias in it.
 ///
 /// Used for `content_type_alias`.
+#[allow(dead_code)]
 struct FieldBlock {
     /// Outer attributes on the field, such as a docstring.
     pub attrs: Vec<Attribute>,
@@ -228,6 +244,7 @@ struct FieldBlock {
 /// The value of a field is a block with named struct fields in it.
 ///
 /// Used for `content`.
+#[allow(dead_code)]
 struct FieldInlineStruct {
     /// Outer attributes on the field, such as a docstring.
     pub attrs: Vec<Attribute>,
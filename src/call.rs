@@ -2,12 +2,25 @@
 //!
 //! This module also contains types shared by events in its child namespaces.
 
-use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
 
 pub mod answer;
 pub mod candidates;
+pub mod group;
 pub mod hangup;
 pub mod invite;
+pub mod lifecycle;
+pub mod member;
+pub mod negotiate;
+pub mod reject;
+#[cfg(feature = "sdp")]
+pub mod sdp;
+pub mod select_answer;
 
 /// A VoIP session description.
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
@@ -38,3 +51,253 @@ impl_enum! {
         Offer => "offer",
     }
 }
+
+/// The version of the VoIP specification a call event adheres to.
+///
+/// Version 0 is serialized as the integer `0`, later versions as strings.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum VoipVersion {
+    /// The initial version of the VoIP specification.
+    V0,
+
+    /// Version 1, which adds support for calls between multiple devices.
+    V1,
+
+    /// Any version that is not part of the specification.
+    Custom(String),
+}
+
+impl Display for VoipVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let version_str = match *self {
+            VoipVersion::V0 => "0",
+            VoipVersion::V1 => "1",
+            VoipVersion::Custom(ref version) => version,
+        };
+
+        write!(f, "{}", version_str)
+    }
+}
+
+impl<T> From<T> for VoipVersion
+where
+    T: Into<String> + AsRef<str>,
+{
+    fn from(s: T) -> VoipVersion {
+        match s.as_ref() {
+            "0" => VoipVersion::V0,
+            "1" => VoipVersion::V1,
+            _ => VoipVersion::Custom(s.into()),
+        }
+    }
+}
+
+impl From<VoipVersion> for String {
+    fn from(version: VoipVersion) -> String {
+        version.to_string()
+    }
+}
+
+impl Serialize for VoipVersion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            VoipVersion::V0 => serializer.serialize_u64(0),
+            version => serializer.serialize_str(&version.to_string()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for VoipVersion {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct VoipVersionVisitor;
+
+        impl<'de> Visitor<'de> for VoipVersionVisitor {
+            type Value = VoipVersion;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("a VoIP version as an integer or a string")
+            }
+
+            fn visit_u64<E: de::Error>(self, version: u64) -> Result<VoipVersion, E> {
+                Ok(VoipVersion::from(version.to_string()))
+            }
+
+            fn visit_str<E: de::Error>(self, version: &str) -> Result<VoipVersion, E> {
+                Ok(VoipVersion::from(version))
+            }
+        }
+
+        deserializer.deserialize_any(VoipVersionVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use matches::assert_matches;
+    use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
+
+    use super::{
+        group::{CallIntent, GroupCallEventContent, GroupCallType},
+        hangup::Reason,
+        member::FeedPurpose,
+        VoipVersion,
+    };
+    use crate::{
+        collections::all::{RoomEvent, StateEvent},
+        stripped::AnyStrippedStateEvent,
+        EventJson,
+    };
+
+    #[test]
+    fn voip_version_serde() {
+        assert_eq!(to_json_value(VoipVersion::V0).unwrap(), json!(0));
+        assert_eq!(to_json_value(VoipVersion::V1).unwrap(), json!("1"));
+        assert_eq!(
+            from_json_value::<VoipVersion>(json!(0)).unwrap(),
+            VoipVersion::V0
+        );
+        assert_eq!(
+            from_json_value::<VoipVersion>(json!("1")).unwrap(),
+            VoipVersion::V1
+        );
+        assert_eq!(
+            from_json_value::<VoipVersion>(json!("org.example.v2")).unwrap(),
+            VoipVersion::Custom("org.example.v2".to_string())
+        );
+        assert!(from_json_value::<VoipVersion>(json!(1.5)).is_err());
+    }
+
+    #[test]
+    fn v1_events() {
+        let select_answer = json!({
+            "content": {
+                "call_id": "12345",
+                "party_id": "caller_device",
+                "selected_party_id": "callee_device",
+                "version": "1"
+            },
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!roomid:room.com",
+            "sender": "@carl:example.com",
+            "type": "m.call.select_answer"
+        });
+
+        assert_matches!(
+            from_json_value::<EventJson<RoomEvent>>(select_answer)
+                .unwrap()
+                .deserialize()
+                .unwrap(),
+            RoomEvent::CallSelectAnswer(event)
+                if event.content.selected_party_id == "callee_device"
+                    && event.content.version == VoipVersion::V1
+        );
+
+        let hangup = json!({
+            "content": {
+                "call_id": "12345",
+                "party_id": "callee_device",
+                "reason": "user_hangup",
+                "version": "1"
+            },
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!roomid:room.com",
+            "sender": "@carl:example.com",
+            "type": "m.call.hangup"
+        });
+
+        assert_matches!(
+            from_json_value::<EventJson<RoomEvent>>(hangup)
+                .unwrap()
+                .deserialize()
+                .unwrap(),
+            RoomEvent::CallHangup(event)
+                if event.content.party_id.as_deref() == Some("callee_device")
+                    && event.content.reason == Some(Reason::UserHangup)
+        );
+    }
+
+    #[test]
+    fn group_call_events() {
+        let content = GroupCallEventContent {
+            intent: CallIntent::Prompt,
+            call_type: GroupCallType::Video,
+            terminated: false,
+            name: Some("Standup".to_string()),
+        };
+        let json_data = json!({
+            "m.intent": "m.prompt",
+            "m.type": "m.video",
+            "m.name": "Standup"
+        });
+
+        assert_eq!(to_json_value(&content).unwrap(), json_data);
+
+        let call = json!({
+            "content": {
+                "m.intent": "m.room",
+                "m.type": "m.voice",
+                "m.terminated": true
+            },
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!roomid:room.com",
+            "sender": "@carl:example.com",
+            "state_key": "1234",
+            "type": "m.call"
+        });
+
+        assert_matches!(
+            from_json_value::<EventJson<StateEvent>>(call)
+                .unwrap()
+                .deserialize()
+                .unwrap(),
+            StateEvent::Call(event)
+                if event.state_key == "1234"
+                    && event.content.intent == CallIntent::Room
+                    && event.content.terminated
+        );
+
+        let member = json!({
+            "content": {
+                "m.calls": [
+                    {
+                        "m.call_id": "1234",
+                        "m.devices": [
+                            {
+                                "device_id": "ABCDEF",
+                                "session_id": "gm9hh3k",
+                                "feeds": [{ "purpose": "m.usermedia" }, { "purpose": "m.screenshare" }]
+                            }
+                        ]
+                    }
+                ]
+            },
+            "sender": "@carl:example.com",
+            "state_key": "@carl:example.com",
+            "type": "m.call.member"
+        });
+
+        match from_json_value::<EventJson<AnyStrippedStateEvent>>(member)
+            .unwrap()
+            .deserialize()
+            .unwrap()
+        {
+            AnyStrippedStateEvent::CallMember(event) => {
+                let call = &event.content.calls[0];
+                assert_eq!(call.call_id, "1234");
+                assert_eq!(call.devices[0].device_id, "ABCDEF");
+                assert_eq!(call.devices[0].feeds[1].purpose, FeedPurpose::ScreenShare);
+            }
+            _ => panic!("Wrong stripped state event type, expected m.call.member"),
+        }
+    }
+}
@@ -1,9 +1,8 @@
 //! Types for the *m.call.answer* event.
 
-use js_int::UInt;
 use ruma_events_macros::ruma_event;
 
-use super::SessionDescription;
+use super::{SessionDescription, VoipVersion};
 
 ruma_event! {
     /// This event is sent by the callee when they wish to answer the call.
@@ -17,8 +16,13 @@ ruma_event! {
             /// The ID of the call this event relates to.
             pub call_id: String,
 
+            /// The unique ID of the client or device sending the event, which is required from
+            /// version 1 of the VoIP specification on.
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub party_id: Option<String>,
+
             /// The version of the VoIP specification this messages adheres to.
-            pub version: UInt,
+            pub version: VoipVersion,
         },
     }
 }
@@ -4,6 +4,8 @@ use js_int::UInt;
 use ruma_events_macros::ruma_event;
 use serde::{Deserialize, Serialize};
 
+use super::VoipVersion;
+
 ruma_event! {
     /// This event is sent by callers after sending an invite and by the callee after answering.
     /// Its purpose is to give the other party additional ICE candidates to try using to
@@ -18,8 +20,13 @@ ruma_event! {
             /// A list of candidates.
             pub candidates: Vec<Candidate>,
 
+            /// The unique ID of the client or device sending the event, which is required from
+            /// version 1 of the VoIP specification on.
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub party_id: Option<String>,
+
             /// The version of the VoIP specification this messages adheres to.
-            pub version: UInt,
+            pub version: VoipVersion,
         },
     }
 }
@@ -0,0 +1,158 @@
+//! Types for the *m.call* event.
+
+use std::fmt::{self, Display, Formatter};
+
+use ruma_events_macros::ruma_event;
+use serde::{Deserialize, Serialize};
+
+ruma_event! {
+    /// The state of a group call in a room. The state key is the ID of the call.
+    ///
+    /// The participants of the call are listed in *m.call.member* events.
+    GroupCallEvent {
+        kind: StateEvent,
+        event_type: "m.call",
+        content: {
+            /// The intent of the call.
+            #[serde(rename = "m.intent")]
+            pub intent: CallIntent,
+
+            /// The type of the call.
+            #[serde(rename = "m.type")]
+            pub call_type: GroupCallType,
+
+            /// Whether the call has ended.
+            ///
+            /// Ended calls should not be joined anymore.
+            #[serde(
+                rename = "m.terminated",
+                default,
+                skip_serializing_if = "ruma_serde::is_default"
+            )]
+            pub terminated: bool,
+
+            /// The name of the call.
+            #[serde(rename = "m.name", skip_serializing_if = "Option::is_none")]
+            pub name: Option<String>,
+        },
+    }
+}
+
+/// The intent of a group call.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[non_exhaustive]
+#[serde(from = "String", into = "String")]
+pub enum CallIntent {
+    /// The call should ring the other members of the room, like a call in a DM room.
+    Ring,
+
+    /// The other members of the room should be prompted to join the call, without ringing.
+    Prompt,
+
+    /// The call is a permanent conference call of the room, which members can join at any time.
+    Room,
+
+    /// Any intent that is not part of the specification.
+    Custom(String),
+}
+
+impl Display for CallIntent {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let intent_str = match *self {
+            CallIntent::Ring => "m.ring",
+            CallIntent::Prompt => "m.prompt",
+            CallIntent::Room => "m.room",
+            CallIntent::Custom(ref intent) => intent,
+        };
+
+        write!(f, "{}", intent_str)
+    }
+}
+
+impl<T> From<T> for CallIntent
+where
+    T: Into<String> + AsRef<str>,
+{
+    fn from(s: T) -> CallIntent {
+        match s.as_ref() {
+            "m.ring" => CallIntent::Ring,
+            "m.prompt" => CallIntent::Prompt,
+            "m.room" => CallIntent::Room,
+            _ => CallIntent::Custom(s.into()),
+        }
+    }
+}
+
+impl From<CallIntent> for String {
+    fn from(intent: CallIntent) -> String {
+        intent.to_string()
+    }
+}
+
+/// The type of a group call.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[non_exhaustive]
+#[serde(from = "String", into = "String")]
+pub enum GroupCallType {
+    /// A voice call.
+    Voice,
+
+    /// A video call.
+    Video,
+
+    /// Any call type that is not part of the specification.
+    Custom(String),
+}
+
+impl Display for GroupCallType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let call_type_str = match *self {
+            GroupCallType::Voice => "m.voice",
+            GroupCallType::Video => "m.video",
+            GroupCallType::Custom(ref call_type) => call_type,
+        };
+
+        write!(f, "{}", call_type_str)
+    }
+}
+
+impl<T> From<T> for GroupCallType
+where
+    T: Into<String> + AsRef<str>,
+{
+    fn from(s: T) -> GroupCallType {
+        match s.as_ref() {
+            "m.voice" => GroupCallType::Voice,
+            "m.video" => GroupCallType::Video,
+            _ => GroupCallType::Custom(s.into()),
+        }
+    }
+}
+
+impl From<GroupCallType> for String {
+    fn from(call_type: GroupCallType) -> String {
+        call_type.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruma_serde::test::serde_json_eq;
+    use serde_json::json;
+
+    use super::{CallIntent, GroupCallType};
+
+    #[test]
+    fn serialize_and_deserialize_from_display_form() {
+        serde_json_eq(CallIntent::Ring, json!("m.ring"));
+        serde_json_eq(
+            CallIntent::Custom("io.ruma.test".to_string()),
+            json!("io.ruma.test"),
+        );
+        serde_json_eq(GroupCallType::Video, json!("m.video"));
+        serde_json_eq(
+            GroupCallType::Custom("io.ruma.test".to_string()),
+            json!("io.ruma.test"),
+        );
+    }
+}
@@ -1,9 +1,12 @@
 //! Types for the *m.call.hangup* event.
 
-use js_int::UInt;
+use std::fmt::{self, Display, Formatter};
+
 use ruma_events_macros::ruma_event;
 use serde::{Deserialize, Serialize};
 
+use super::VoipVersion;
+
 ruma_event! {
     /// Sent by either party to signal their termination of the call. This can be sent either once
     /// the call has has been established or before to abort the call.
@@ -14,8 +17,13 @@ ruma_event! {
             /// The ID of the call this event relates to.
             pub call_id: String,
 
+            /// The unique ID of the client or device sending the event, which is required from
+            /// version 1 of the VoIP specification on.
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub party_id: Option<String>,
+
             /// The version of the VoIP specification this messages adheres to.
-            pub version: UInt,
+            pub version: VoipVersion,
 
             /// Optional error reason for the hangup.
             #[serde(skip_serializing_if = "Option::is_none")]
@@ -26,22 +34,90 @@ ruma_event! {
 
 /// A reason for a hangup.
 ///
-/// This should not be provided when the user naturally ends or rejects the call. When there was an
-/// error in the call negotiation, this should be `ice_failed` for when ICE negotiation fails or
-/// `invite_timeout` for when the other party did not answer in time.
-#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
-#[serde(rename_all = "snake_case")]
+/// In version 0 of the VoIP specification, this should not be provided when the user naturally
+/// ends or rejects the call. When there was an error in the call negotiation, this should be
+/// `ice_failed` for when ICE negotiation fails or `invite_timeout` for when the other party did
+/// not answer in time. Version 1 adds `user_hangup` for calls ended by the user and further error
+/// reasons.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[non_exhaustive]
+#[serde(from = "String", into = "String")]
 pub enum Reason {
     /// ICE negotiation failure.
     IceFailed,
 
     /// Party did not answer in time.
     InviteTimeout,
+
+    /// The connection timed out after being established.
+    IceTimeout,
+
+    /// The user chose to end the call.
+    UserHangup,
+
+    /// The client was unable to start capturing media.
+    UserMediaFailed,
+
+    /// Some other failure occurred that meant the client was unable to continue the call.
+    UnknownError,
+
+    /// Any reason that is not part of the specification.
+    Custom(String),
 }
 
-impl_enum! {
-    Reason {
-        IceFailed => "ice_failed",
-        InviteTimeout => "invite_timeout",
+impl Display for Reason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let reason_str = match *self {
+            Reason::IceFailed => "ice_failed",
+            Reason::InviteTimeout => "invite_timeout",
+            Reason::IceTimeout => "ice_timeout",
+            Reason::UserHangup => "user_hangup",
+            Reason::UserMediaFailed => "user_media_failed",
+            Reason::UnknownError => "unknown_error",
+            Reason::Custom(ref reason) => reason,
+        };
+
+        write!(f, "{}", reason_str)
+    }
+}
+
+impl<T> From<T> for Reason
+where
+    T: Into<String> + AsRef<str>,
+{
+    fn from(s: T) -> Reason {
+        match s.as_ref() {
+            "ice_failed" => Reason::IceFailed,
+            "invite_timeout" => Reason::InviteTimeout,
+            "ice_timeout" => Reason::IceTimeout,
+            "user_hangup" => Reason::UserHangup,
+            "user_media_failed" => Reason::UserMediaFailed,
+            "unknown_error" => Reason::UnknownError,
+            _ => Reason::Custom(s.into()),
+        }
+    }
+}
+
+impl From<Reason> for String {
+    fn from(reason: Reason) -> String {
+        reason.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruma_serde::test::serde_json_eq;
+    use serde_json::json;
+
+    use super::Reason;
+
+    #[test]
+    fn serialize_and_deserialize_from_display_form() {
+        serde_json_eq(Reason::InviteTimeout, json!("invite_timeout"));
+        serde_json_eq(Reason::UserMediaFailed, json!("user_media_failed"));
+        serde_json_eq(
+            Reason::Custom("io.ruma.test".to_string()),
+            json!("io.ruma.test"),
+        );
     }
 }
@@ -3,7 +3,7 @@
 use js_int::UInt;
 use ruma_events_macros::ruma_event;
 
-use super::SessionDescription;
+use super::{SessionDescription, VoipVersion};
 
 ruma_event! {
     /// This event is sent by the caller when they wish to establish a call.
@@ -22,8 +22,13 @@ ruma_event! {
             /// The session description object. The session description type must be *offer*.
             pub offer: SessionDescription,
 
+            /// The unique ID of the client or device sending the event, which is required from
+            /// version 1 of the VoIP specification on.
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub party_id: Option<String>,
+
             /// The version of the VoIP specification this messages adheres to.
-            pub version: UInt,
+            pub version: VoipVersion,
         },
     }
 }
@@ -0,0 +1,715 @@
+//! Tracking of the lifecycle of VoIP calls.
+//!
+//! A call goes through the following events, all sharing the same `call_id`:
+//!
+//! 1. *m.call.invite*, sent by the caller with the offer.
+//! 2. *m.call.candidates*, sent by the caller after the invite and by the callee after the answer.
+//! 3. *m.call.answer*, sent by the callee with the answer. From version 1 of the VoIP
+//!    specification on, several devices of the callee may answer, and the caller picks one of the
+//!    answers with *m.call.select_answer*. The callee may also decline the call with
+//!    *m.call.reject* instead.
+//! 4. *m.call.hangup*, sent by either party to end or reject the call.
+//!
+//! `Calls` checks that the events arrive in this order and expires invites that were not answered
+//! within their `lifetime`.
+
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{self, Display, Formatter},
+    time::{Duration, SystemTime},
+};
+
+use super::{
+    answer::AnswerEventContent,
+    candidates::{Candidate, CandidatesEventContent},
+    hangup::{HangupEventContent, Reason},
+    invite::InviteEventContent,
+    reject::RejectEventContent,
+    select_answer::SelectAnswerEventContent,
+    SessionDescription, SessionDescriptionType, VoipVersion,
+};
+
+/// Which party sent a call event.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Origin {
+    /// The event was sent by this client.
+    Own,
+
+    /// The event was sent by the other party.
+    Other,
+}
+
+/// The state of a call.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CallState {
+    /// The call was offered and waits for an answer.
+    Invited,
+
+    /// The call was answered.
+    Answered,
+
+    /// The call ended.
+    Ended {
+        /// The party that hung up or rejected the call, or `None` if the invite expired without an
+        /// answer.
+        by: Option<Origin>,
+
+        /// The reason for the hangup, if any.
+        ///
+        /// This is `None` for rejected calls.
+        reason: Option<Reason>,
+    },
+}
+
+/// An error encountered while tracking a call event.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum CallError {
+    /// No call with the `call_id` of the event is tracked.
+    UnknownCall,
+
+    /// An invite with the same `call_id` was received before.
+    DuplicateCall,
+
+    /// The event is not expected in the current state of the call, e.g. an answer by the caller.
+    UnexpectedEvent,
+
+    /// The session description has the wrong type, e.g. an invite with an *answer*.
+    WrongSessionDescriptionType,
+
+    /// The invite expired before it was answered.
+    Expired,
+
+    /// The call already ended.
+    Ended,
+}
+
+impl Display for CallError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CallError::UnknownCall => write!(f, "unknown call"),
+            CallError::DuplicateCall => write!(f, "duplicate call invite"),
+            CallError::UnexpectedEvent => write!(f, "unexpected call event"),
+            CallError::WrongSessionDescriptionType => {
+                write!(f, "wrong session description type")
+            }
+            CallError::Expired => write!(f, "call invite expired"),
+            CallError::Ended => write!(f, "call already ended"),
+        }
+    }
+}
+
+impl Error for CallError {}
+
+/// A call tracked by `Calls`.
+#[derive(Clone, Debug)]
+pub struct Call {
+    call_id: String,
+    state: CallState,
+    invited_by: Origin,
+    party_id: Option<String>,
+    version: VoipVersion,
+    offer: SessionDescription,
+    answers: BTreeMap<Option<String>, SessionDescription>,
+    selected_party_id: Option<String>,
+    remote_candidates: Vec<Candidate>,
+    expires_at: SystemTime,
+}
+
+impl Call {
+    /// The ID of the call.
+    pub fn call_id(&self) -> &str {
+        &self.call_id
+    }
+
+    /// The current state of the call.
+    pub fn state(&self) -> &CallState {
+        &self.state
+    }
+
+    /// The party that sent the invite.
+    pub fn invited_by(&self) -> Origin {
+        self.invited_by
+    }
+
+    /// The `party_id` of the invite.
+    pub fn party_id(&self) -> Option<&str> {
+        self.party_id.as_deref()
+    }
+
+    /// The version of the VoIP specification of the invite.
+    pub fn version(&self) -> &VoipVersion {
+        &self.version
+    }
+
+    /// The session description of the invite.
+    pub fn offer(&self) -> &SessionDescription {
+        &self.offer
+    }
+
+    /// The session description of the selected answer, once the call was answered.
+    ///
+    /// Until an *m.call.select_answer* event is tracked, this is the first answer.
+    pub fn answer(&self) -> Option<&SessionDescription> {
+        self.answers.get(&self.selected_party_id)
+    }
+
+    /// The `party_id` of the selected answer, once the call was answered with a `party_id`.
+    pub fn selected_party_id(&self) -> Option<&str> {
+        self.selected_party_id.as_deref()
+    }
+
+    /// Whether the call ended.
+    pub fn is_ended(&self) -> bool {
+        match self.state {
+            CallState::Ended { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// The point in time after which the invite expires if it was not answered.
+    pub fn expires_at(&self) -> SystemTime {
+        self.expires_at
+    }
+
+    /// Takes the ICE candidates received from the other party since the last call.
+    ///
+    /// Candidates that arrive before the call was answered are held back until then, since they
+    /// can only be used once both session descriptions are known.
+    pub fn take_remote_candidates(&mut self) -> Vec<Candidate> {
+        if self.state != CallState::Answered {
+            return Vec::new();
+        }
+
+        std::mem::take(&mut self.remote_candidates)
+    }
+
+    fn end(&mut self, by: Option<Origin>, reason: Option<Reason>) {
+        self.state = CallState::Ended { by, reason };
+    }
+}
+
+/// The calls in a room, indexed by their call ID.
+///
+/// Each method that tracks an event returns an error if the event is not valid in the current
+/// state of the call. Such events should be ignored.
+#[derive(Clone, Debug, Default)]
+pub struct Calls {
+    calls: BTreeMap<String, Call>,
+}
+
+impl Calls {
+    /// Creates an empty set of calls.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The call with the given ID, if any.
+    pub fn get(&self, call_id: &str) -> Option<&Call> {
+        self.calls.get(call_id)
+    }
+
+    /// The call with the given ID, if any.
+    pub fn get_mut(&mut self, call_id: &str) -> Option<&mut Call> {
+        self.calls.get_mut(call_id)
+    }
+
+    /// Tracks an *m.call.invite* event.
+    ///
+    /// `origin_server_ts` is the `origin_server_ts` of the event; the invite expires `lifetime`
+    /// milliseconds after it. Invites that already expired are not tracked.
+    pub fn invite(
+        &mut self,
+        origin: Origin,
+        content: &InviteEventContent,
+        origin_server_ts: SystemTime,
+        now: SystemTime,
+    ) -> Result<(), CallError> {
+        if content.offer.session_type != SessionDescriptionType::Offer {
+            return Err(CallError::WrongSessionDescriptionType);
+        }
+
+        if self.calls.contains_key(&content.call_id) {
+            return Err(CallError::DuplicateCall);
+        }
+
+        let expires_at = origin_server_ts + Duration::from_millis(content.lifetime.into());
+        if expires_at <= now {
+            return Err(CallError::Expired);
+        }
+
+        self.calls.insert(
+            content.call_id.clone(),
+            Call {
+                call_id: content.call_id.clone(),
+                state: CallState::Invited,
+                invited_by: origin,
+                party_id: content.party_id.clone(),
+                version: content.version.clone(),
+                offer: content.offer.clone(),
+                answers: BTreeMap::new(),
+                selected_party_id: None,
+                remote_candidates: Vec::new(),
+                expires_at,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Tracks an *m.call.answer* event.
+    ///
+    /// The answer must be sent by the party that did not send the invite, before the invite
+    /// expires. Further answers by other devices of that party, i.e. with a different `party_id`,
+    /// are kept so that one of them can be selected with *m.call.select_answer*; until then, the
+    /// first answer is selected.
+    pub fn answer(
+        &mut self,
+        origin: Origin,
+        content: &AnswerEventContent,
+        now: SystemTime,
+    ) -> Result<(), CallError> {
+        let call = self.active(&content.call_id, now)?;
+
+        if content.answer.session_type != SessionDescriptionType::Answer {
+            return Err(CallError::WrongSessionDescriptionType);
+        }
+
+        if call.invited_by == origin || call.answers.contains_key(&content.party_id) {
+            return Err(CallError::UnexpectedEvent);
+        }
+
+        if call.state == CallState::Invited {
+            call.selected_party_id = content.party_id.clone();
+            call.state = CallState::Answered;
+        } else if content.party_id.is_none() || call.selected_party_id.is_none() {
+            // Only version 1 answers, which have a `party_id`, can come from several devices.
+            return Err(CallError::UnexpectedEvent);
+        }
+
+        call.answers
+            .insert(content.party_id.clone(), content.answer.clone());
+
+        Ok(())
+    }
+
+    /// Tracks an *m.call.select_answer* event.
+    ///
+    /// The event must be sent by the party that sent the invite, and select one of the answers
+    /// tracked with `answer`. Candidates are only collected from the selected party afterwards.
+    pub fn select_answer(
+        &mut self,
+        origin: Origin,
+        content: &SelectAnswerEventContent,
+        now: SystemTime,
+    ) -> Result<(), CallError> {
+        let call = self.active(&content.call_id, now)?;
+        let selected_party_id = Some(content.selected_party_id.clone());
+
+        if call.invited_by != origin || !call.answers.contains_key(&selected_party_id) {
+            return Err(CallError::UnexpectedEvent);
+        }
+
+        if call.selected_party_id != selected_party_id {
+            call.selected_party_id = selected_party_id;
+            call.remote_candidates.clear();
+        }
+
+        Ok(())
+    }
+
+    /// Tracks an *m.call.reject* event.
+    ///
+    /// The event must be sent by the party that did not send the invite, before the call was
+    /// answered. It ends the call without a reason.
+    pub fn reject(
+        &mut self,
+        origin: Origin,
+        content: &RejectEventContent,
+        now: SystemTime,
+    ) -> Result<(), CallError> {
+        let call = self.active(&content.call_id, now)?;
+
+        if call.state != CallState::Invited || call.invited_by == origin {
+            return Err(CallError::UnexpectedEvent);
+        }
+
+        call.end(Some(origin), None);
+
+        Ok(())
+    }
+
+    /// Tracks an *m.call.candidates* event.
+    ///
+    /// The callee may only send candidates after answering the call. Candidates sent by the other
+    /// party are collected in the call, see `Call::take_remote_candidates`, unless they were sent
+    /// by a device of the callee whose answer was not selected.
+    pub fn candidates(
+        &mut self,
+        origin: Origin,
+        content: &CandidatesEventContent,
+        now: SystemTime,
+    ) -> Result<(), CallError> {
+        let call = self.active(&content.call_id, now)?;
+
+        if call.state == CallState::Invited && call.invited_by != origin {
+            return Err(CallError::UnexpectedEvent);
+        }
+
+        let from_unselected_callee = call.invited_by != origin
+            && content.party_id.is_some()
+            && call.selected_party_id.is_some()
+            && content.party_id != call.selected_party_id;
+
+        if origin == Origin::Other && !from_unselected_callee {
+            call.remote_candidates
+                .extend(content.candidates.iter().cloned());
+        }
+
+        Ok(())
+    }
+
+    /// Tracks an *m.call.hangup* event sent by either party.
+    ///
+    /// Hangups of calls that already ended are ignored.
+    pub fn hangup(
+        &mut self,
+        origin: Origin,
+        content: &HangupEventContent,
+    ) -> Result<(), CallError> {
+        let call = self
+            .calls
+            .get_mut(&content.call_id)
+            .ok_or(CallError::UnknownCall)?;
+
+        if !call.is_ended() {
+            call.end(Some(origin), content.reason.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Ends all calls whose invite expired at the given point in time without an answer.
+    ///
+    /// Returns the contents of the *m.call.hangup* events to send for the expired calls that this
+    /// client invited to.
+    pub fn expire(&mut self, now: SystemTime) -> Vec<HangupEventContent> {
+        let mut hangups = Vec::new();
+
+        for call in self.calls.values_mut() {
+            if call.state == CallState::Invited && call.expires_at <= now {
+                call.end(None, Some(Reason::InviteTimeout));
+
+                if call.invited_by == Origin::Own {
+                    hangups.push(HangupEventContent {
+                        call_id: call.call_id.clone(),
+                        party_id: call.party_id.clone(),
+                        version: call.version.clone(),
+                        reason: Some(Reason::InviteTimeout),
+                    });
+                }
+            }
+        }
+
+        hangups
+    }
+
+    /// Stops tracking calls that ended.
+    pub fn remove_ended(&mut self) {
+        self.calls = std::mem::take(&mut self.calls)
+            .into_iter()
+            .filter(|(_, call)| !call.is_ended())
+            .collect();
+    }
+
+    /// Looks up a call that has not ended or expired yet.
+    fn active(&mut self, call_id: &str, now: SystemTime) -> Result<&mut Call, CallError> {
+        let call = self.calls.get_mut(call_id).ok_or(CallError::UnknownCall)?;
+
+        if call.is_ended() {
+            return Err(CallError::Ended);
+        }
+
+        if call.state == CallState::Invited && call.expires_at <= now {
+            call.end(None, Some(Reason::InviteTimeout));
+            return Err(CallError::Expired);
+        }
+
+        Ok(call)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use js_int::UInt;
+
+    use super::{CallError, CallState, Calls, Origin};
+    use crate::call::{
+        answer::AnswerEventContent,
+        candidates::{Candidate, CandidatesEventContent},
+        hangup::{HangupEventContent, Reason},
+        invite::InviteEventContent,
+        reject::RejectEventContent,
+        select_answer::SelectAnswerEventContent,
+        SessionDescription, SessionDescriptionType, VoipVersion,
+    };
+
+    fn invite() -> InviteEventContent {
+        InviteEventContent {
+            call_id: "12345".to_string(),
+            lifetime: UInt::from(60_000_u32),
+            offer: SessionDescription {
+                session_type: SessionDescriptionType::Offer,
+                sdp: "v=0\r\no=- 6584580628695956864 2 IN IP4 127.0.0.1\r\n".to_string(),
+            },
+            party_id: None,
+            version: VoipVersion::V0,
+        }
+    }
+
+    fn answer() -> AnswerEventContent {
+        AnswerEventContent {
+            answer: SessionDescription {
+                session_type: SessionDescriptionType::Answer,
+                sdp: "v=0\r\no=- 6584580628695956864 2 IN IP4 127.0.0.1\r\n".to_string(),
+            },
+            call_id: "12345".to_string(),
+            party_id: None,
+            version: VoipVersion::V0,
+        }
+    }
+
+    fn candidates() -> CandidatesEventContent {
+        CandidatesEventContent {
+            call_id: "12345".to_string(),
+            candidates: vec![Candidate {
+                candidate: "candidate:863018703 1 udp 2122260223 10.9.64.156 43670 typ host"
+                    .to_string(),
+                sdp_mid: "audio".to_string(),
+                sdp_m_line_index: UInt::from(0_u32),
+            }],
+            party_id: None,
+            version: VoipVersion::V0,
+        }
+    }
+
+    #[test]
+    fn answered_call() {
+        let now = SystemTime::now();
+        let mut calls = Calls::new();
+
+        calls.invite(Origin::Own, &invite(), now, now).unwrap();
+        calls.candidates(Origin::Own, &candidates(), now).unwrap();
+        assert_eq!(
+            calls.candidates(Origin::Other, &candidates(), now),
+            Err(CallError::UnexpectedEvent)
+        );
+        assert_eq!(
+            calls.answer(Origin::Own, &answer(), now),
+            Err(CallError::UnexpectedEvent)
+        );
+
+        calls.answer(Origin::Other, &answer(), now).unwrap();
+        calls.candidates(Origin::Other, &candidates(), now).unwrap();
+        let call = calls.get_mut("12345").unwrap();
+        assert_eq!(call.state(), &CallState::Answered);
+        assert_eq!(call.take_remote_candidates().len(), 1);
+        assert!(call.take_remote_candidates().is_empty());
+
+        calls
+            .hangup(
+                Origin::Other,
+                &HangupEventContent {
+                    call_id: "12345".to_string(),
+                    party_id: None,
+                    version: VoipVersion::V0,
+                    reason: Some(Reason::IceFailed),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            calls.get("12345").unwrap().state(),
+            &CallState::Ended {
+                by: Some(Origin::Other),
+                reason: Some(Reason::IceFailed)
+            }
+        );
+        assert_eq!(
+            calls.candidates(Origin::Other, &candidates(), now),
+            Err(CallError::Ended)
+        );
+
+        calls.remove_ended();
+        assert!(calls.get("12345").is_none());
+    }
+
+    #[test]
+    fn candidates_before_answer() {
+        let now = SystemTime::now();
+        let mut calls = Calls::new();
+
+        calls.invite(Origin::Other, &invite(), now, now).unwrap();
+        calls.candidates(Origin::Other, &candidates(), now).unwrap();
+        calls.candidates(Origin::Other, &candidates(), now).unwrap();
+        assert!(calls
+            .get_mut("12345")
+            .unwrap()
+            .take_remote_candidates()
+            .is_empty());
+        calls.answer(Origin::Own, &answer(), now).unwrap();
+
+        assert_eq!(
+            calls
+                .get_mut("12345")
+                .unwrap()
+                .take_remote_candidates()
+                .len(),
+            2
+        );
+    }
+
+    fn answer_v1(party_id: &str) -> AnswerEventContent {
+        AnswerEventContent {
+            answer: SessionDescription {
+                session_type: SessionDescriptionType::Answer,
+                sdp: format!("v=0\r\no=- {} 2 IN IP4 127.0.0.1\r\n", party_id),
+            },
+            call_id: "12345".to_string(),
+            party_id: Some(party_id.to_string()),
+            version: VoipVersion::V1,
+        }
+    }
+
+    #[test]
+    fn answers_from_several_devices() {
+        let now = SystemTime::now();
+        let mut calls = Calls::new();
+        let mut invite = invite();
+        invite.party_id = Some("CALLER".to_string());
+        invite.version = VoipVersion::V1;
+
+        calls.invite(Origin::Own, &invite, now, now).unwrap();
+        calls
+            .answer(Origin::Other, &answer_v1("PHONE"), now)
+            .unwrap();
+        calls
+            .answer(Origin::Other, &answer_v1("LAPTOP"), now)
+            .unwrap();
+        assert_eq!(
+            calls.answer(Origin::Other, &answer_v1("LAPTOP"), now),
+            Err(CallError::UnexpectedEvent)
+        );
+        assert_eq!(
+            calls.get("12345").unwrap().selected_party_id(),
+            Some("PHONE")
+        );
+
+        let select_answer = SelectAnswerEventContent {
+            call_id: "12345".to_string(),
+            party_id: "CALLER".to_string(),
+            selected_party_id: "LAPTOP".to_string(),
+            version: VoipVersion::V1,
+        };
+        assert_eq!(
+            calls.select_answer(Origin::Other, &select_answer, now),
+            Err(CallError::UnexpectedEvent)
+        );
+        calls
+            .select_answer(Origin::Own, &select_answer, now)
+            .unwrap();
+
+        let mut phone_candidates = candidates();
+        phone_candidates.party_id = Some("PHONE".to_string());
+        calls
+            .candidates(Origin::Other, &phone_candidates, now)
+            .unwrap();
+        let mut laptop_candidates = candidates();
+        laptop_candidates.party_id = Some("LAPTOP".to_string());
+        calls
+            .candidates(Origin::Other, &laptop_candidates, now)
+            .unwrap();
+
+        let call = calls.get_mut("12345").unwrap();
+        assert_eq!(call.selected_party_id(), Some("LAPTOP"));
+        assert_eq!(call.answer(), Some(&answer_v1("LAPTOP").answer));
+        assert_eq!(call.take_remote_candidates().len(), 1);
+    }
+
+    #[test]
+    fn rejected_call() {
+        let now = SystemTime::now();
+        let mut calls = Calls::new();
+        let reject = RejectEventContent {
+            call_id: "12345".to_string(),
+            party_id: "CALLEE".to_string(),
+            version: VoipVersion::V1,
+        };
+
+        calls.invite(Origin::Own, &invite(), now, now).unwrap();
+        assert_eq!(
+            calls.reject(Origin::Own, &reject, now),
+            Err(CallError::UnexpectedEvent)
+        );
+        calls.reject(Origin::Other, &reject, now).unwrap();
+        assert_eq!(
+            calls.get("12345").unwrap().state(),
+            &CallState::Ended {
+                by: Some(Origin::Other),
+                reason: None
+            }
+        );
+    }
+
+    #[test]
+    fn expired_invite() {
+        let now = SystemTime::now();
+        let mut calls = Calls::new();
+
+        assert_eq!(
+            calls.invite(Origin::Own, &invite(), now - Duration::from_secs(61), now),
+            Err(CallError::Expired)
+        );
+
+        let mut v1_invite = invite();
+        v1_invite.party_id = Some("CALLER".to_string());
+        v1_invite.version = VoipVersion::V1;
+        calls.invite(Origin::Own, &v1_invite, now, now).unwrap();
+        let hangups = calls.expire(now + Duration::from_secs(61));
+        assert_eq!(hangups.len(), 1);
+        assert_eq!(hangups[0].party_id.as_ref().unwrap(), "CALLER");
+        assert_eq!(hangups[0].version, VoipVersion::V1);
+        assert_eq!(hangups[0].reason, Some(Reason::InviteTimeout));
+        assert_eq!(
+            calls.get("12345").unwrap().state(),
+            &CallState::Ended {
+                by: None,
+                reason: Some(Reason::InviteTimeout)
+            }
+        );
+
+        let mut calls = Calls::new();
+        calls.invite(Origin::Other, &invite(), now, now).unwrap();
+        assert_eq!(
+            calls.answer(Origin::Own, &answer(), now + Duration::from_secs(61)),
+            Err(CallError::Expired)
+        );
+        assert!(calls.get("12345").unwrap().is_ended());
+    }
+
+    #[test]
+    fn wrong_session_description_type() {
+        let now = SystemTime::now();
+        let mut calls = Calls::new();
+        let mut invite = invite();
+        invite.offer.session_type = SessionDescriptionType::Answer;
+
+        assert_eq!(
+            calls.invite(Origin::Own, &invite, now, now),
+            Err(CallError::WrongSessionDescriptionType)
+        );
+        assert!(calls.get("12345").is_none());
+    }
+}
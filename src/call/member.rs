@@ -0,0 +1,120 @@
+//! Types for the *m.call.member* event.
+
+use std::fmt::{self, Display, Formatter};
+
+use ruma_events_macros::ruma_event;
+use ruma_identifiers::DeviceId;
+use serde::{Deserialize, Serialize};
+
+ruma_event! {
+    /// The group calls a user participates in. The state key is the ID of the user.
+    ///
+    /// A user leaves all calls of a room by sending an event with an empty list of calls.
+    CallMemberEvent {
+        kind: StateEvent,
+        event_type: "m.call.member",
+        content: {
+            /// The group calls the user participates in.
+            #[serde(rename = "m.calls")]
+            pub calls: Vec<CallMembership>,
+        },
+    }
+}
+
+/// A user's participation in a group call.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CallMembership {
+    /// The ID of the call, i.e. the state key of its *m.call* event.
+    #[serde(rename = "m.call_id")]
+    pub call_id: String,
+
+    /// The devices of the user that joined the call.
+    #[serde(rename = "m.devices")]
+    pub devices: Vec<CallMemberDevice>,
+}
+
+/// A device that joined a group call.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CallMemberDevice {
+    /// The ID of the device.
+    pub device_id: DeviceId,
+
+    /// The ID of the device's session in the call.
+    ///
+    /// A device that rejoins the call uses a new session ID, so that other participants can tell
+    /// the new session apart from the old one.
+    pub session_id: String,
+
+    /// The media streams the device sends in the call.
+    pub feeds: Vec<CallFeed>,
+}
+
+/// A media stream sent by a device in a group call.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CallFeed {
+    /// The purpose of the stream.
+    pub purpose: FeedPurpose,
+}
+
+/// The purpose of a media stream in a group call.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[non_exhaustive]
+#[serde(from = "String", into = "String")]
+pub enum FeedPurpose {
+    /// The stream carries the user's camera and microphone.
+    UserMedia,
+
+    /// The stream carries a screen share.
+    ScreenShare,
+
+    /// Any purpose that is not part of the specification.
+    Custom(String),
+}
+
+impl Display for FeedPurpose {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let purpose_str = match *self {
+            FeedPurpose::UserMedia => "m.usermedia",
+            FeedPurpose::ScreenShare => "m.screenshare",
+            FeedPurpose::Custom(ref purpose) => purpose,
+        };
+
+        write!(f, "{}", purpose_str)
+    }
+}
+
+impl<T> From<T> for FeedPurpose
+where
+    T: Into<String> + AsRef<str>,
+{
+    fn from(s: T) -> FeedPurpose {
+        match s.as_ref() {
+            "m.usermedia" => FeedPurpose::UserMedia,
+            "m.screenshare" => FeedPurpose::ScreenShare,
+            _ => FeedPurpose::Custom(s.into()),
+        }
+    }
+}
+
+impl From<FeedPurpose> for String {
+    fn from(purpose: FeedPurpose) -> String {
+        purpose.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruma_serde::test::serde_json_eq;
+    use serde_json::json;
+
+    use super::FeedPurpose;
+
+    #[test]
+    fn serialize_and_deserialize_from_display_form() {
+        serde_json_eq(FeedPurpose::ScreenShare, json!("m.screenshare"));
+        serde_json_eq(
+            FeedPurpose::Custom("io.ruma.test".to_string()),
+            json!("io.ruma.test"),
+        );
+    }
+}
@@ -0,0 +1,36 @@
+//! Types for the *m.call.negotiate* event.
+
+use js_int::UInt;
+use ruma_events_macros::ruma_event;
+
+use super::{SessionDescription, VoipVersion};
+
+ruma_event! {
+    /// This event is sent by either party once a call is established, to renegotiate the session,
+    /// e.g. to put the call on hold or to add a video stream.
+    NegotiateEvent {
+        kind: RoomEvent,
+        event_type: "m.call.negotiate",
+        content: {
+            /// The ID of the call this event relates to.
+            pub call_id: String,
+
+            /// The unique ID of the client or device sending the event.
+            pub party_id: String,
+
+            /// The time in milliseconds that the negotiation is valid for.
+            pub lifetime: UInt,
+
+            /// The session description of the renegotiation.
+            ///
+            /// The session description type is *offer* for a new offer, or *answer* for the
+            /// answer to an offer.
+            pub description: SessionDescription,
+
+            /// The version of the VoIP specification this messages adheres to.
+            ///
+            /// This event was added in version 1.
+            pub version: VoipVersion,
+        },
+    }
+}
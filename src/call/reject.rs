@@ -0,0 +1,26 @@
+//! Types for the *m.call.reject* event.
+
+use ruma_events_macros::ruma_event;
+
+use super::VoipVersion;
+
+ruma_event! {
+    /// This event is sent by the callee to reject an invite. Unlike a hangup, it also tells the
+    /// other devices of the callee to stop ringing.
+    RejectEvent {
+        kind: RoomEvent,
+        event_type: "m.call.reject",
+        content: {
+            /// The ID of the call this event relates to.
+            pub call_id: String,
+
+            /// The unique ID of the client or device sending the event.
+            pub party_id: String,
+
+            /// The version of the VoIP specification this messages adheres to.
+            ///
+            /// This event was added in version 1.
+            pub version: VoipVersion,
+        },
+    }
+}
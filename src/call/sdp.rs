@@ -0,0 +1,667 @@
+//! Parsing of the [SDP](https://tools.ietf.org/html/rfc4566) session descriptions and ICE
+//! candidates exchanged in call events.
+//!
+//! The parsed types keep every line of the session description, so that a parsed `Sdp` can be
+//! modified and serialized again without losing attributes this module doesn't know about.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use super::{candidates::Candidate, SessionDescription};
+use crate::InvalidInput;
+
+/// A parsed SDP session description.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sdp {
+    /// The session-level lines, starting with the `v=` line.
+    pub session_lines: Vec<SdpLine>,
+
+    /// The media sections, in the order of their `m=` lines.
+    pub media: Vec<MediaSection>,
+}
+
+/// A line of an SDP session description, e.g. `a=mid:0`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SdpLine {
+    /// The type of the line, e.g. `a` for attributes.
+    pub kind: char,
+
+    /// The value of the line after the `=`.
+    pub value: String,
+}
+
+/// A media section of an SDP session description, starting with an `m=` line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediaSection {
+    /// The media type, e.g. `audio`, `video` or `application`.
+    pub media: String,
+
+    /// The transport port.
+    pub port: u16,
+
+    /// The number of ports, if given.
+    pub port_count: Option<u16>,
+
+    /// The transport protocol, e.g. `UDP/TLS/RTP/SAVPF`.
+    pub protocol: String,
+
+    /// The media formats, i.e. RTP payload types for RTP media.
+    pub formats: Vec<String>,
+
+    /// The lines of the media section after the `m=` line.
+    pub lines: Vec<SdpLine>,
+}
+
+/// A codec of a media section, described by its `a=rtpmap` and `a=fmtp` attributes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Codec {
+    /// The RTP payload type.
+    pub payload_type: u8,
+
+    /// The encoding name, e.g. `opus` or `VP8`.
+    pub name: String,
+
+    /// The clock rate in Hz.
+    pub clock_rate: u32,
+
+    /// The number of audio channels, if given.
+    pub channels: Option<u16>,
+
+    /// The format parameters, e.g. `minptime=10;useinbandfec=1`.
+    pub parameters: Option<String>,
+}
+
+/// The ICE credentials of a session or a media section.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IceCredentials {
+    /// The ICE username fragment.
+    pub ufrag: String,
+
+    /// The ICE password.
+    pub pwd: String,
+}
+
+/// The DTLS certificate fingerprint of a session or a media section.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fingerprint {
+    /// The hash function, e.g. `sha-256`.
+    pub hash_function: String,
+
+    /// The fingerprint, as colon-separated uppercase hex bytes.
+    pub fingerprint: String,
+}
+
+/// A parsed ICE candidate, as described in
+/// [RFC 5245](https://tools.ietf.org/html/rfc5245#section-15.1).
+#[derive(Clone, Debug, PartialEq)]
+pub struct IceCandidate {
+    /// The foundation of the candidate.
+    pub foundation: String,
+
+    /// The ID of the component, 1 for RTP and 2 for RTCP.
+    pub component: u32,
+
+    /// The transport protocol, e.g. `udp`.
+    pub transport: String,
+
+    /// The priority of the candidate.
+    pub priority: u32,
+
+    /// The IP address or hostname of the candidate.
+    pub address: String,
+
+    /// The port of the candidate.
+    pub port: u16,
+
+    /// The type of the candidate, e.g. `host`, `srflx`, `prflx` or `relay`.
+    pub candidate_type: String,
+
+    /// The related address, for reflexive and relayed candidates.
+    pub related_address: Option<String>,
+
+    /// The related port, for reflexive and relayed candidates.
+    pub related_port: Option<u16>,
+
+    /// Further attributes of the candidate, e.g. `generation 0`, as name and value.
+    pub extensions: Vec<(String, String)>,
+}
+
+impl Sdp {
+    /// The session-level ICE credentials, if any.
+    ///
+    /// Most WebRTC implementations set the ICE credentials per media section instead, see
+    /// `MediaSection::ice_credentials`.
+    pub fn ice_credentials(&self) -> Option<IceCredentials> {
+        ice_credentials(&self.session_lines)
+    }
+
+    /// The session-level DTLS fingerprint, if any.
+    pub fn fingerprint(&self) -> Option<Fingerprint> {
+        fingerprint(&self.session_lines)
+    }
+
+    /// The value of the first session-level attribute with the given name, if any.
+    ///
+    /// Returns an empty string for attributes without a value, e.g. `a=ice-lite`.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        attributes(&self.session_lines, name).next()
+    }
+}
+
+impl MediaSection {
+    /// The media ID of the section, from its `a=mid` attribute.
+    pub fn mid(&self) -> Option<&str> {
+        self.attribute("mid")
+    }
+
+    /// The codecs of the section, in the order of its formats.
+    ///
+    /// Formats without an `a=rtpmap` attribute are skipped.
+    pub fn codecs(&self) -> Vec<Codec> {
+        self.formats
+            .iter()
+            .filter_map(|format| {
+                let payload_type = format.parse().ok()?;
+                let rtpmap = attributes(&self.lines, "rtpmap")
+                    .find_map(|value| strip_payload_type(value, format))?;
+
+                let mut encoding = rtpmap.split('/');
+                let name = encoding.next()?.to_string();
+                let clock_rate = encoding.next()?.parse().ok()?;
+                let channels = encoding.next().and_then(|channels| channels.parse().ok());
+                let parameters = attributes(&self.lines, "fmtp")
+                    .find_map(|value| strip_payload_type(value, format))
+                    .map(ToOwned::to_owned);
+
+                Some(Codec {
+                    payload_type,
+                    name,
+                    clock_rate,
+                    channels,
+                    parameters,
+                })
+            })
+            .collect()
+    }
+
+    /// Removes the codecs for which `f` returns `false`, along with their `a=rtpmap`, `a=fmtp`
+    /// and `a=rtcp-fb` attributes.
+    pub fn retain_codecs<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Codec) -> bool,
+    {
+        let removed: Vec<String> = self
+            .codecs()
+            .iter()
+            .filter(|codec| !f(codec))
+            .map(|codec| codec.payload_type.to_string())
+            .collect();
+
+        self.formats.retain(|format| !removed.contains(format));
+        self.lines.retain(|line| {
+            let (name, value) = match split_attribute(line) {
+                Some(attribute) => attribute,
+                None => return true,
+            };
+
+            !(["rtpmap", "fmtp", "rtcp-fb"].contains(&name)
+                && removed
+                    .iter()
+                    .any(|payload_type| strip_payload_type(value, payload_type).is_some()))
+        });
+    }
+
+    /// The ICE credentials of the section, if any.
+    pub fn ice_credentials(&self) -> Option<IceCredentials> {
+        ice_credentials(&self.lines)
+    }
+
+    /// The DTLS fingerprint of the section, if any.
+    pub fn fingerprint(&self) -> Option<Fingerprint> {
+        fingerprint(&self.lines)
+    }
+
+    /// The value of the first attribute of the section with the given name, if any.
+    ///
+    /// Returns an empty string for attributes without a value, e.g. `a=rtcp-mux`.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        attributes(&self.lines, name).next()
+    }
+
+    /// Replaces the value of the first attribute with the given name, or adds the attribute at the
+    /// end of the section if it doesn't exist yet.
+    ///
+    /// Pass `None` as the value for attributes without a value.
+    pub fn set_attribute(&mut self, name: &str, value: Option<&str>) {
+        let line = SdpLine {
+            kind: 'a',
+            value: match value {
+                Some(value) => format!("{}:{}", name, value),
+                None => name.to_string(),
+            },
+        };
+
+        match self.lines.iter_mut().find(|line| is_attribute(line, name)) {
+            Some(existing) => *existing = line,
+            None => self.lines.push(line),
+        }
+    }
+
+    /// Removes all attributes with the given name.
+    pub fn remove_attribute(&mut self, name: &str) {
+        self.lines.retain(|line| !is_attribute(line, name));
+    }
+}
+
+impl SessionDescription {
+    /// Parses the SDP text of the session description.
+    pub fn parse_sdp(&self) -> Result<Sdp, InvalidInput> {
+        self.sdp.parse()
+    }
+
+    /// Replaces the SDP text of the session description with the serialization of `sdp`.
+    pub fn set_sdp(&mut self, sdp: &Sdp) {
+        self.sdp = sdp.to_string();
+    }
+}
+
+impl Candidate {
+    /// Parses the SDP "a" line of the candidate.
+    pub fn parse_candidate(&self) -> Result<IceCandidate, InvalidInput> {
+        self.candidate.parse()
+    }
+
+    /// Replaces the SDP "a" line of the candidate with the serialization of `candidate`.
+    pub fn set_candidate(&mut self, candidate: &IceCandidate) {
+        self.candidate = candidate.to_string();
+    }
+}
+
+impl FromStr for Sdp {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| InvalidInput(format!("invalid SDP: {}", reason));
+
+        let mut session_lines = Vec::new();
+        let mut media: Vec<MediaSection> = Vec::new();
+
+        for line in s.lines().filter(|line| !line.is_empty()) {
+            let mut chars = line.chars();
+            let kind = chars.next().expect("line is not empty");
+            let value = match strip_prefix(chars.as_str(), "=") {
+                Some(value) => value.to_string(),
+                None => return Err(invalid("line without `=`")),
+            };
+
+            if session_lines.is_empty() && kind != 'v' {
+                return Err(invalid("missing `v=` line"));
+            }
+
+            match kind {
+                'm' => media
+                    .push(parse_media_line(&value).ok_or_else(|| invalid("invalid `m=` line"))?),
+                _ => match media.last_mut() {
+                    Some(section) => section.lines.push(SdpLine { kind, value }),
+                    None => session_lines.push(SdpLine { kind, value }),
+                },
+            }
+        }
+
+        if session_lines.is_empty() {
+            return Err(invalid("empty session description"));
+        }
+
+        Ok(Self {
+            session_lines,
+            media,
+        })
+    }
+}
+
+impl Display for Sdp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for line in &self.session_lines {
+            write!(f, "{}\r\n", line)?;
+        }
+
+        for section in &self.media {
+            write!(f, "{}\r\n", section)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for SdpLine {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.kind, self.value)
+    }
+}
+
+impl Display for MediaSection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "m={} {}", self.media, self.port)?;
+        if let Some(port_count) = self.port_count {
+            write!(f, "/{}", port_count)?;
+        }
+        write!(f, " {}", self.protocol)?;
+        for format in &self.formats {
+            write!(f, " {}", format)?;
+        }
+
+        for line in &self.lines {
+            write!(f, "\r\n{}", line)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for IceCandidate {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidInput(format!("invalid ICE candidate: {}", s));
+
+        let s = s.trim();
+        let s = strip_prefix(s, "a=").unwrap_or(s);
+        let s = strip_prefix(s, "candidate:").unwrap_or(s);
+        let mut tokens = s.split_whitespace();
+        let mut next = || tokens.next().ok_or_else(invalid);
+
+        let foundation = next()?.to_string();
+        let component = next()?.parse().map_err(|_| invalid())?;
+        let transport = next()?.to_string();
+        let priority = next()?.parse().map_err(|_| invalid())?;
+        let address = next()?.to_string();
+        let port = next()?.parse().map_err(|_| invalid())?;
+        if next()? != "typ" {
+            return Err(invalid());
+        }
+        let candidate_type = next()?.to_string();
+
+        let mut related_address = None;
+        let mut related_port = None;
+        let mut extensions = Vec::new();
+        while let Some(name) = tokens.next() {
+            let value = tokens.next().ok_or_else(invalid)?;
+
+            match name {
+                "raddr" => related_address = Some(value.to_string()),
+                "rport" => related_port = Some(value.parse().map_err(|_| invalid())?),
+                _ => extensions.push((name.to_string(), value.to_string())),
+            }
+        }
+
+        Ok(Self {
+            foundation,
+            component,
+            transport,
+            priority,
+            address,
+            port,
+            candidate_type,
+            related_address,
+            related_port,
+            extensions,
+        })
+    }
+}
+
+impl Display for IceCandidate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "candidate:{} {} {} {} {} {} typ {}",
+            self.foundation,
+            self.component,
+            self.transport,
+            self.priority,
+            self.address,
+            self.port,
+            self.candidate_type
+        )?;
+
+        if let Some(related_address) = &self.related_address {
+            write!(f, " raddr {}", related_address)?;
+        }
+        if let Some(related_port) = self.related_port {
+            write!(f, " rport {}", related_port)?;
+        }
+        for (name, value) in &self.extensions {
+            write!(f, " {} {}", name, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the value of an `m=` line.
+fn parse_media_line(value: &str) -> Option<MediaSection> {
+    let mut tokens = value.split_whitespace();
+
+    let media = tokens.next()?.to_string();
+    let mut port = tokens.next()?.splitn(2, '/');
+    let (port, port_count) = match (port.next()?, port.next()) {
+        (port, None) => (port.parse().ok()?, None),
+        (port, Some(count)) => (port.parse().ok()?, Some(count.parse().ok()?)),
+    };
+    let protocol = tokens.next()?.to_string();
+    let formats = tokens.map(ToOwned::to_owned).collect();
+
+    Some(MediaSection {
+        media,
+        port,
+        port_count,
+        protocol,
+        formats,
+        lines: Vec::new(),
+    })
+}
+
+/// Splits an `a=` line into the name and value of the attribute.
+fn split_attribute(line: &SdpLine) -> Option<(&str, &str)> {
+    if line.kind != 'a' {
+        return None;
+    }
+
+    let mut parts = line.value.splitn(2, ':');
+    Some((parts.next()?, parts.next().unwrap_or("")))
+}
+
+/// Whether the line is an attribute with the given name.
+fn is_attribute(line: &SdpLine, name: &str) -> bool {
+    match split_attribute(line) {
+        Some((n, _)) => n == name,
+        None => false,
+    }
+}
+
+/// The values of the attributes with the given name.
+fn attributes<'a: 'b, 'b>(
+    lines: &'a [SdpLine],
+    name: &'b str,
+) -> impl Iterator<Item = &'a str> + 'b {
+    lines
+        .iter()
+        .filter_map(split_attribute)
+        .filter(move |(n, _)| *n == name)
+        .map(|(_, value)| value)
+}
+
+/// Strips the given payload type from an attribute value like `111 opus/48000/2`.
+fn strip_payload_type<'a>(value: &'a str, payload_type: &str) -> Option<&'a str> {
+    let mut parts = value.splitn(2, ' ');
+
+    if parts.next()? == payload_type {
+        Some(parts.next().unwrap_or(""))
+    } else {
+        None
+    }
+}
+
+/// Like `str::strip_prefix`, which requires Rust 1.45.
+fn strip_prefix<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    if s.starts_with(prefix) {
+        Some(&s[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+fn ice_credentials(lines: &[SdpLine]) -> Option<IceCredentials> {
+    Some(IceCredentials {
+        ufrag: attributes(lines, "ice-ufrag").next()?.to_string(),
+        pwd: attributes(lines, "ice-pwd").next()?.to_string(),
+    })
+}
+
+fn fingerprint(lines: &[SdpLine]) -> Option<Fingerprint> {
+    let mut parts = attributes(lines, "fingerprint").next()?.splitn(2, ' ');
+
+    Some(Fingerprint {
+        hash_function: parts.next()?.to_string(),
+        fingerprint: parts.next()?.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Codec, Fingerprint, IceCandidate, IceCredentials, Sdp};
+
+    const OFFER: &str = "v=0\r\n\
+        o=- 6584580628695956864 2 IN IP4 127.0.0.1\r\n\
+        s=-\r\n\
+        t=0 0\r\n\
+        a=group:BUNDLE 0 1\r\n\
+        m=audio 9 UDP/TLS/RTP/SAVPF 111 0\r\n\
+        c=IN IP4 0.0.0.0\r\n\
+        a=ice-ufrag:Fx2k\r\n\
+        a=ice-pwd:kIL6Aj7xL9rGzCsTzDd6JYw5\r\n\
+        a=fingerprint:sha-256 7B:8B:F0:65:5F:78:E2:51:3B:AC:6F:F3:3F:46:1B:35\r\n\
+        a=mid:0\r\n\
+        a=rtcp-mux\r\n\
+        a=rtpmap:111 opus/48000/2\r\n\
+        a=rtcp-fb:111 transport-cc\r\n\
+        a=fmtp:111 minptime=10;useinbandfec=1\r\n\
+        a=rtpmap:0 PCMU/8000\r\n\
+        m=video 9 UDP/TLS/RTP/SAVPF 96\r\n\
+        c=IN IP4 0.0.0.0\r\n\
+        a=mid:1\r\n\
+        a=rtpmap:96 VP8/90000\r\n";
+
+    #[test]
+    fn parse_and_serialize_offer() {
+        let sdp: Sdp = OFFER.parse().unwrap();
+
+        assert_eq!(sdp.session_lines.len(), 5);
+        assert_eq!(sdp.attribute("group"), Some("BUNDLE 0 1"));
+        assert_eq!(sdp.media.len(), 2);
+
+        let audio = &sdp.media[0];
+        assert_eq!(audio.media, "audio");
+        assert_eq!(audio.port, 9);
+        assert_eq!(audio.protocol, "UDP/TLS/RTP/SAVPF");
+        assert_eq!(audio.mid(), Some("0"));
+        assert_eq!(audio.attribute("rtcp-mux"), Some(""));
+        assert_eq!(
+            audio.ice_credentials(),
+            Some(IceCredentials {
+                ufrag: "Fx2k".to_string(),
+                pwd: "kIL6Aj7xL9rGzCsTzDd6JYw5".to_string(),
+            })
+        );
+        assert_eq!(
+            audio.fingerprint(),
+            Some(Fingerprint {
+                hash_function: "sha-256".to_string(),
+                fingerprint: "7B:8B:F0:65:5F:78:E2:51:3B:AC:6F:F3:3F:46:1B:35".to_string(),
+            })
+        );
+        assert_eq!(
+            audio.codecs(),
+            vec![
+                Codec {
+                    payload_type: 111,
+                    name: "opus".to_string(),
+                    clock_rate: 48000,
+                    channels: Some(2),
+                    parameters: Some("minptime=10;useinbandfec=1".to_string()),
+                },
+                Codec {
+                    payload_type: 0,
+                    name: "PCMU".to_string(),
+                    clock_rate: 8000,
+                    channels: None,
+                    parameters: None,
+                },
+            ]
+        );
+        assert_eq!(sdp.media[1].codecs()[0].name, "VP8");
+
+        assert_eq!(sdp.to_string(), OFFER);
+    }
+
+    #[test]
+    fn rewrite_offer() {
+        let mut sdp: Sdp = OFFER.parse().unwrap();
+        let audio = &mut sdp.media[0];
+
+        audio.retain_codecs(|codec| codec.name != "opus");
+        audio.set_attribute("ice-ufrag", Some("Ab3d"));
+        audio.remove_attribute("rtcp-mux");
+
+        assert_eq!(audio.formats, vec!["0"]);
+        assert_eq!(audio.codecs().len(), 1);
+        assert_eq!(audio.ice_credentials().unwrap().ufrag, "Ab3d");
+        assert_eq!(audio.attribute("rtcp-mux"), None);
+        assert!(!sdp.to_string().contains("opus"));
+        assert!(!sdp.to_string().contains("transport-cc"));
+        assert!(sdp
+            .to_string()
+            .contains("m=audio 9 UDP/TLS/RTP/SAVPF 0\r\n"));
+    }
+
+    #[test]
+    fn invalid_sdp() {
+        assert!("".parse::<Sdp>().is_err());
+        assert!("o=- 1 2 IN IP4 127.0.0.1\r\n".parse::<Sdp>().is_err());
+        assert!("v=0\r\nm=audio nine RTP/AVP 0\r\n".parse::<Sdp>().is_err());
+        assert!("v=0\r\nnot a line\r\n".parse::<Sdp>().is_err());
+    }
+
+    #[test]
+    fn parse_and_serialize_candidate() {
+        let line = "candidate:842163049 1 udp 1677729535 203.0.113.7 53166 typ srflx \
+                    raddr 10.0.0.4 rport 53166 generation 0 network-cost 999";
+        let candidate: IceCandidate = line.parse().unwrap();
+
+        assert_eq!(candidate.foundation, "842163049");
+        assert_eq!(candidate.component, 1);
+        assert_eq!(candidate.transport, "udp");
+        assert_eq!(candidate.priority, 1_677_729_535);
+        assert_eq!(candidate.address, "203.0.113.7");
+        assert_eq!(candidate.port, 53166);
+        assert_eq!(candidate.candidate_type, "srflx");
+        assert_eq!(candidate.related_address.as_deref(), Some("10.0.0.4"));
+        assert_eq!(candidate.related_port, Some(53166));
+        assert_eq!(
+            candidate.extensions,
+            vec![
+                ("generation".to_string(), "0".to_string()),
+                ("network-cost".to_string(), "999".to_string()),
+            ]
+        );
+        assert_eq!(candidate.to_string(), line);
+
+        assert!("candidate:1 1 udp 1 10.0.0.4 70000 typ host"
+            .parse::<IceCandidate>()
+            .is_err());
+        assert!("candidate:1 1 udp 1 10.0.0.4 5000 host"
+            .parse::<IceCandidate>()
+            .is_err());
+    }
+}
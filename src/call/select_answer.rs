@@ -0,0 +1,29 @@
+//! Types for the *m.call.select_answer* event.
+
+use ruma_events_macros::ruma_event;
+
+use super::VoipVersion;
+
+ruma_event! {
+    /// This event is sent by the caller when it receives answers from multiple devices of the
+    /// callee, to tell them which answer was chosen. The other devices should stop ringing.
+    SelectAnswerEvent {
+        kind: RoomEvent,
+        event_type: "m.call.select_answer",
+        content: {
+            /// The ID of the call this event relates to.
+            pub call_id: String,
+
+            /// The unique ID of the client or device sending the event.
+            pub party_id: String,
+
+            /// The `party_id` of the answer that was selected.
+            pub selected_party_id: String,
+
+            /// The version of the VoIP specification this messages adheres to.
+            ///
+            /// This event was added in version 1.
+            pub version: VoipVersion,
+        },
+    }
+}
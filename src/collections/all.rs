@@ -6,7 +6,12 @@ use serde::Serialize;
 use super::raw::all as raw;
 use crate::{
     call::{
-        answer::AnswerEvent, candidates::CandidatesEvent, hangup::HangupEvent, invite::InviteEvent,
+        answer::AnswerEvent, candidates::CandidatesEvent, group::GroupCallEvent,
+        hangup::HangupEvent, invite::InviteEvent, member::CallMemberEvent,
+        negotiate::NegotiateEvent, reject::RejectEvent, select_answer::SelectAnswerEvent,
+    },
+    cross_signing::{
+        master::MasterEvent, self_signing::SelfSigningEvent, user_signing::UserSigningEvent,
     },
     direct::DirectEvent,
     dummy::DummyEvent,
@@ -14,8 +19,19 @@ use crate::{
     fully_read::FullyReadEvent,
     ignored_user_list::IgnoredUserListEvent,
     key::verification::{
-        accept::AcceptEvent, cancel::CancelEvent, key::KeyEvent, mac::MacEvent,
-        request::RequestEvent, start::StartEvent,
+        accept::AcceptEvent,
+        cancel::CancelEvent,
+        done::DoneEvent,
+        in_room::{
+            InRoomVerificationAccept, InRoomVerificationCancel, InRoomVerificationDone,
+            InRoomVerificationKey, InRoomVerificationMac, InRoomVerificationReady,
+            InRoomVerificationStart,
+        },
+        key::KeyEvent,
+        mac::MacEvent,
+        ready::ReadyEvent,
+        request::RequestEvent,
+        start::StartEvent,
     },
     presence::PresenceEvent,
     push_rules::PushRulesEvent,
@@ -41,8 +57,12 @@ use crate::{
         tombstone::TombstoneEvent,
         topic::TopicEvent,
     },
+    room_key::withheld::WithheldEvent,
     room_key::RoomKeyEvent,
     room_key_request::RoomKeyRequestEvent,
+    secret::{request::RequestEvent as SecretRequestEvent, send::SendEvent},
+    secret_storage::{default_key::DefaultKeyEvent, key::KeyEvent as SecretStorageKeyEvent},
+    space::{child::SpaceChildEvent, parent::SpaceParentEvent},
     sticker::StickerEvent,
     tag::TagEvent,
     typing::TypingEvent,
@@ -54,6 +74,9 @@ use crate::{
 #[serde(untagged)]
 #[allow(clippy::large_enum_variant)]
 pub enum Event {
+    /// m.call
+    Call(GroupCallEvent),
+
     /// m.call.answer
     CallAnswer(AnswerEvent),
 
@@ -66,6 +89,48 @@ pub enum Event {
     /// m.call.invite
     CallInvite(InviteEvent),
 
+    /// m.call.member
+    CallMember(CallMemberEvent),
+
+    /// m.call.negotiate
+    CallNegotiate(NegotiateEvent),
+
+    /// m.call.reject
+    CallReject(RejectEvent),
+
+    /// m.call.select_answer
+    CallSelectAnswer(SelectAnswerEvent),
+
+    /// m.cross_signing.master
+    CrossSigningMaster(MasterEvent),
+
+    /// m.cross_signing.self_signing
+    CrossSigningSelfSigning(SelfSigningEvent),
+
+    /// m.cross_signing.user_signing
+    CrossSigningUserSigning(UserSigningEvent),
+
+    /// In-room version of m.key.verification.accept
+    InRoomKeyVerificationAccept(InRoomVerificationAccept),
+
+    /// In-room version of m.key.verification.cancel
+    InRoomKeyVerificationCancel(InRoomVerificationCancel),
+
+    /// In-room version of m.key.verification.done
+    InRoomKeyVerificationDone(InRoomVerificationDone),
+
+    /// In-room version of m.key.verification.key
+    InRoomKeyVerificationKey(InRoomVerificationKey),
+
+    /// In-room version of m.key.verification.mac
+    InRoomKeyVerificationMac(InRoomVerificationMac),
+
+    /// In-room version of m.key.verification.ready
+    InRoomKeyVerificationReady(InRoomVerificationReady),
+
+    /// In-room version of m.key.verification.start
+    InRoomKeyVerificationStart(InRoomVerificationStart),
+
     /// m.direct
     Direct(DirectEvent),
 
@@ -87,12 +152,18 @@ pub enum Event {
     /// m.key.verification.cancel
     KeyVerificationCancel(CancelEvent),
 
+    /// m.key.verification.done
+    KeyVerificationDone(DoneEvent),
+
     /// m.key.verification.key
     KeyVerificationKey(KeyEvent),
 
     /// m.key.verification.mac
     KeyVerificationMac(MacEvent),
 
+    /// m.key.verification.ready
+    KeyVerificationReady(ReadyEvent),
+
     /// m.key.verification.request
     KeyVerificationRequest(RequestEvent),
 
@@ -168,12 +239,33 @@ pub enum Event {
     /// m.room.topic
     RoomTopic(TopicEvent),
 
+    /// m.space.child
+    SpaceChild(SpaceChildEvent),
+
+    /// m.space.parent
+    SpaceParent(SpaceParentEvent),
+
     /// m.room_key
     RoomKey(RoomKeyEvent),
 
     /// m.room_key_request
     RoomKeyRequest(RoomKeyRequestEvent),
 
+    /// m.room_key.withheld
+    RoomKeyWithheld(WithheldEvent),
+
+    /// m.secret.request
+    SecretRequest(SecretRequestEvent),
+
+    /// m.secret.send
+    SecretSend(SendEvent),
+
+    /// m.secret_storage.default_key
+    SecretStorageDefaultKey(DefaultKeyEvent),
+
+    /// m.secret_storage.key.*
+    SecretStorageKey(SecretStorageKeyEvent),
+
     /// m.sticker
     Sticker(StickerEvent),
 
@@ -198,6 +290,9 @@ pub enum Event {
 #[serde(untagged)]
 #[allow(clippy::large_enum_variant)]
 pub enum RoomEvent {
+    /// m.call
+    Call(GroupCallEvent),
+
     /// m.call.answer
     CallAnswer(AnswerEvent),
 
@@ -210,6 +305,39 @@ pub enum RoomEvent {
     /// m.call.invite
     CallInvite(InviteEvent),
 
+    /// m.call.member
+    CallMember(CallMemberEvent),
+
+    /// m.call.negotiate
+    CallNegotiate(NegotiateEvent),
+
+    /// m.call.reject
+    CallReject(RejectEvent),
+
+    /// m.call.select_answer
+    CallSelectAnswer(SelectAnswerEvent),
+
+    /// In-room version of m.key.verification.accept
+    InRoomKeyVerificationAccept(InRoomVerificationAccept),
+
+    /// In-room version of m.key.verification.cancel
+    InRoomKeyVerificationCancel(InRoomVerificationCancel),
+
+    /// In-room version of m.key.verification.done
+    InRoomKeyVerificationDone(InRoomVerificationDone),
+
+    /// In-room version of m.key.verification.key
+    InRoomKeyVerificationKey(InRoomVerificationKey),
+
+    /// In-room version of m.key.verification.mac
+    InRoomKeyVerificationMac(InRoomVerificationMac),
+
+    /// In-room version of m.key.verification.ready
+    InRoomKeyVerificationReady(InRoomVerificationReady),
+
+    /// In-room version of m.key.verification.start
+    InRoomKeyVerificationStart(InRoomVerificationStart),
+
     /// m.room.aliases
     RoomAliases(AliasesEvent),
 
@@ -270,6 +398,12 @@ pub enum RoomEvent {
     /// m.room.topic
     RoomTopic(TopicEvent),
 
+    /// m.space.child
+    SpaceChild(SpaceChildEvent),
+
+    /// m.space.parent
+    SpaceParent(SpaceParentEvent),
+
     /// m.sticker
     Sticker(StickerEvent),
 
@@ -285,6 +419,12 @@ pub enum RoomEvent {
 #[serde(untagged)]
 #[allow(clippy::large_enum_variant)]
 pub enum StateEvent {
+    /// m.call
+    Call(GroupCallEvent),
+
+    /// m.call.member
+    CallMember(CallMemberEvent),
+
     /// m.room.aliases
     RoomAliases(AliasesEvent),
 
@@ -333,6 +473,12 @@ pub enum StateEvent {
     /// m.room.topic
     RoomTopic(TopicEvent),
 
+    /// m.space.child
+    SpaceChild(SpaceChildEvent),
+
+    /// m.space.parent
+    SpaceParent(SpaceParentEvent),
+
     /// Any state event that is not part of the specification.
     CustomState(CustomStateEvent),
 }
@@ -346,10 +492,25 @@ impl TryFromRaw for Event {
         use raw::Event::*;
 
         match raw {
+            Call(c) => conv(Event::Call, c),
             CallAnswer(c) => conv(Event::CallAnswer, c),
             CallCandidates(c) => conv(Event::CallCandidates, c),
             CallHangup(c) => conv(Event::CallHangup, c),
             CallInvite(c) => conv(Event::CallInvite, c),
+            CallMember(c) => conv(Event::CallMember, c),
+            CallNegotiate(c) => conv(Event::CallNegotiate, c),
+            CallReject(c) => conv(Event::CallReject, c),
+            CallSelectAnswer(c) => conv(Event::CallSelectAnswer, c),
+            CrossSigningMaster(c) => conv(Event::CrossSigningMaster, c),
+            CrossSigningSelfSigning(c) => conv(Event::CrossSigningSelfSigning, c),
+            CrossSigningUserSigning(c) => conv(Event::CrossSigningUserSigning, c),
+            InRoomKeyVerificationAccept(c) => conv(Event::InRoomKeyVerificationAccept, c),
+            InRoomKeyVerificationCancel(c) => conv(Event::InRoomKeyVerificationCancel, c),
+            InRoomKeyVerificationDone(c) => conv(Event::InRoomKeyVerificationDone, c),
+            InRoomKeyVerificationKey(c) => conv(Event::InRoomKeyVerificationKey, c),
+            InRoomKeyVerificationMac(c) => conv(Event::InRoomKeyVerificationMac, c),
+            InRoomKeyVerificationReady(c) => conv(Event::InRoomKeyVerificationReady, c),
+            InRoomKeyVerificationStart(c) => conv(Event::InRoomKeyVerificationStart, c),
             Direct(c) => conv(Event::Direct, c),
             Dummy(c) => conv(Event::Dummy, c),
             ForwardedRoomKey(c) => conv(Event::ForwardedRoomKey, c),
@@ -357,8 +518,10 @@ impl TryFromRaw for Event {
             IgnoredUserList(c) => conv(Event::IgnoredUserList, c),
             KeyVerificationAccept(c) => conv(Event::KeyVerificationAccept, c),
             KeyVerificationCancel(c) => conv(Event::KeyVerificationCancel, c),
+            KeyVerificationDone(c) => conv(Event::KeyVerificationDone, c),
             KeyVerificationKey(c) => conv(Event::KeyVerificationKey, c),
             KeyVerificationMac(c) => conv(Event::KeyVerificationMac, c),
+            KeyVerificationReady(c) => conv(Event::KeyVerificationReady, c),
             KeyVerificationRequest(c) => conv(Event::KeyVerificationRequest, c),
             KeyVerificationStart(c) => conv(Event::KeyVerificationStart, c),
             Presence(c) => conv(Event::Presence, c),
@@ -384,8 +547,15 @@ impl TryFromRaw for Event {
             RoomThirdPartyInvite(c) => conv(Event::RoomThirdPartyInvite, c),
             RoomTombstone(c) => conv(Event::RoomTombstone, c),
             RoomTopic(c) => conv(Event::RoomTopic, c),
+            SpaceChild(c) => conv(Event::SpaceChild, c),
+            SpaceParent(c) => conv(Event::SpaceParent, c),
             RoomKey(c) => conv(Event::RoomKey, c),
             RoomKeyRequest(c) => conv(Event::RoomKeyRequest, c),
+            RoomKeyWithheld(c) => conv(Event::RoomKeyWithheld, c),
+            SecretRequest(c) => conv(Event::SecretRequest, c),
+            SecretSend(c) => conv(Event::SecretSend, c),
+            SecretStorageDefaultKey(c) => conv(Event::SecretStorageDefaultKey, c),
+            SecretStorageKey(c) => conv(Event::SecretStorageKey, c),
             Sticker(c) => conv(Event::Sticker, c),
             Tag(c) => conv(Event::Tag, c),
             Typing(c) => conv(Event::Typing, c),
@@ -405,10 +575,22 @@ impl TryFromRaw for RoomEvent {
         use raw::RoomEvent::*;
 
         match raw {
+            Call(c) => conv(RoomEvent::Call, c),
             CallAnswer(c) => conv(RoomEvent::CallAnswer, c),
             CallCandidates(c) => conv(RoomEvent::CallCandidates, c),
             CallHangup(c) => conv(RoomEvent::CallHangup, c),
             CallInvite(c) => conv(RoomEvent::CallInvite, c),
+            CallMember(c) => conv(RoomEvent::CallMember, c),
+            CallNegotiate(c) => conv(RoomEvent::CallNegotiate, c),
+            CallReject(c) => conv(RoomEvent::CallReject, c),
+            CallSelectAnswer(c) => conv(RoomEvent::CallSelectAnswer, c),
+            InRoomKeyVerificationAccept(c) => conv(RoomEvent::InRoomKeyVerificationAccept, c),
+            InRoomKeyVerificationCancel(c) => conv(RoomEvent::InRoomKeyVerificationCancel, c),
+            InRoomKeyVerificationDone(c) => conv(RoomEvent::InRoomKeyVerificationDone, c),
+            InRoomKeyVerificationKey(c) => conv(RoomEvent::InRoomKeyVerificationKey, c),
+            InRoomKeyVerificationMac(c) => conv(RoomEvent::InRoomKeyVerificationMac, c),
+            InRoomKeyVerificationReady(c) => conv(RoomEvent::InRoomKeyVerificationReady, c),
+            InRoomKeyVerificationStart(c) => conv(RoomEvent::InRoomKeyVerificationStart, c),
             RoomAliases(c) => conv(RoomEvent::RoomAliases, c),
             RoomAvatar(c) => conv(RoomEvent::RoomAvatar, c),
             RoomCanonicalAlias(c) => conv(RoomEvent::RoomCanonicalAlias, c),
@@ -429,6 +611,8 @@ impl TryFromRaw for RoomEvent {
             RoomThirdPartyInvite(c) => conv(RoomEvent::RoomThirdPartyInvite, c),
             RoomTombstone(c) => conv(RoomEvent::RoomTombstone, c),
             RoomTopic(c) => conv(RoomEvent::RoomTopic, c),
+            SpaceChild(c) => conv(RoomEvent::SpaceChild, c),
+            SpaceParent(c) => conv(RoomEvent::SpaceParent, c),
             Sticker(c) => conv(RoomEvent::Sticker, c),
             CustomRoom(c) => conv(RoomEvent::CustomRoom, c),
             CustomState(c) => conv(RoomEvent::CustomState, c),
@@ -445,6 +629,8 @@ impl TryFromRaw for StateEvent {
         use raw::StateEvent::*;
 
         match raw {
+            Call(c) => conv(StateEvent::Call, c),
+            CallMember(c) => conv(StateEvent::CallMember, c),
             RoomAliases(c) => conv(StateEvent::RoomAliases, c),
             RoomAvatar(c) => conv(StateEvent::RoomAvatar, c),
             RoomCanonicalAlias(c) => conv(StateEvent::RoomCanonicalAlias, c),
@@ -461,23 +647,42 @@ impl TryFromRaw for StateEvent {
             RoomThirdPartyInvite(c) => conv(StateEvent::RoomThirdPartyInvite, c),
             RoomTombstone(c) => conv(StateEvent::RoomTombstone, c),
             RoomTopic(c) => conv(StateEvent::RoomTopic, c),
+            SpaceChild(c) => conv(StateEvent::SpaceChild, c),
+            SpaceParent(c) => conv(StateEvent::SpaceParent, c),
             CustomState(c) => conv(StateEvent::CustomState, c),
         }
     }
 }
 
+impl_from_for_enum!(Event, GroupCallEvent, Call);
 impl_from_for_enum!(Event, AnswerEvent, CallAnswer);
 impl_from_for_enum!(Event, CandidatesEvent, CallCandidates);
 impl_from_for_enum!(Event, HangupEvent, CallHangup);
 impl_from_for_enum!(Event, InviteEvent, CallInvite);
+impl_from_for_enum!(Event, CallMemberEvent, CallMember);
+impl_from_for_enum!(Event, NegotiateEvent, CallNegotiate);
+impl_from_for_enum!(Event, RejectEvent, CallReject);
+impl_from_for_enum!(Event, SelectAnswerEvent, CallSelectAnswer);
+impl_from_for_enum!(Event, MasterEvent, CrossSigningMaster);
+impl_from_for_enum!(Event, SelfSigningEvent, CrossSigningSelfSigning);
+impl_from_for_enum!(Event, UserSigningEvent, CrossSigningUserSigning);
+impl_from_for_enum!(Event, InRoomVerificationAccept, InRoomKeyVerificationAccept);
+impl_from_for_enum!(Event, InRoomVerificationCancel, InRoomKeyVerificationCancel);
+impl_from_for_enum!(Event, InRoomVerificationDone, InRoomKeyVerificationDone);
+impl_from_for_enum!(Event, InRoomVerificationKey, InRoomKeyVerificationKey);
+impl_from_for_enum!(Event, InRoomVerificationMac, InRoomKeyVerificationMac);
+impl_from_for_enum!(Event, InRoomVerificationReady, InRoomKeyVerificationReady);
+impl_from_for_enum!(Event, InRoomVerificationStart, InRoomKeyVerificationStart);
 impl_from_for_enum!(Event, DirectEvent, Direct);
 impl_from_for_enum!(Event, DummyEvent, Dummy);
 impl_from_for_enum!(Event, ForwardedRoomKeyEvent, ForwardedRoomKey);
 impl_from_for_enum!(Event, FullyReadEvent, FullyRead);
 impl_from_for_enum!(Event, AcceptEvent, KeyVerificationAccept);
 impl_from_for_enum!(Event, CancelEvent, KeyVerificationCancel);
+impl_from_for_enum!(Event, DoneEvent, KeyVerificationDone);
 impl_from_for_enum!(Event, KeyEvent, KeyVerificationKey);
 impl_from_for_enum!(Event, MacEvent, KeyVerificationMac);
+impl_from_for_enum!(Event, ReadyEvent, KeyVerificationReady);
 impl_from_for_enum!(Event, RequestEvent, KeyVerificationRequest);
 impl_from_for_enum!(Event, StartEvent, KeyVerificationStart);
 impl_from_for_enum!(Event, IgnoredUserListEvent, IgnoredUserList);
@@ -504,8 +709,15 @@ impl_from_for_enum!(Event, ServerAclEvent, RoomServerAcl);
 impl_from_for_enum!(Event, ThirdPartyInviteEvent, RoomThirdPartyInvite);
 impl_from_for_enum!(Event, TombstoneEvent, RoomTombstone);
 impl_from_for_enum!(Event, TopicEvent, RoomTopic);
+impl_from_for_enum!(Event, SpaceChildEvent, SpaceChild);
+impl_from_for_enum!(Event, SpaceParentEvent, SpaceParent);
 impl_from_for_enum!(Event, RoomKeyEvent, RoomKey);
 impl_from_for_enum!(Event, RoomKeyRequestEvent, RoomKeyRequest);
+impl_from_for_enum!(Event, WithheldEvent, RoomKeyWithheld);
+impl_from_for_enum!(Event, SecretRequestEvent, SecretRequest);
+impl_from_for_enum!(Event, SendEvent, SecretSend);
+impl_from_for_enum!(Event, DefaultKeyEvent, SecretStorageDefaultKey);
+impl_from_for_enum!(Event, SecretStorageKeyEvent, SecretStorageKey);
 impl_from_for_enum!(Event, StickerEvent, Sticker);
 impl_from_for_enum!(Event, TagEvent, Tag);
 impl_from_for_enum!(Event, TypingEvent, Typing);
@@ -513,10 +725,38 @@ impl_from_for_enum!(Event, CustomEvent, Custom);
 impl_from_for_enum!(Event, CustomRoomEvent, CustomRoom);
 impl_from_for_enum!(Event, CustomStateEvent, CustomState);
 
+impl_from_for_enum!(RoomEvent, GroupCallEvent, Call);
 impl_from_for_enum!(RoomEvent, AnswerEvent, CallAnswer);
 impl_from_for_enum!(RoomEvent, CandidatesEvent, CallCandidates);
 impl_from_for_enum!(RoomEvent, HangupEvent, CallHangup);
 impl_from_for_enum!(RoomEvent, InviteEvent, CallInvite);
+impl_from_for_enum!(RoomEvent, CallMemberEvent, CallMember);
+impl_from_for_enum!(RoomEvent, NegotiateEvent, CallNegotiate);
+impl_from_for_enum!(RoomEvent, RejectEvent, CallReject);
+impl_from_for_enum!(RoomEvent, SelectAnswerEvent, CallSelectAnswer);
+impl_from_for_enum!(
+    RoomEvent,
+    InRoomVerificationAccept,
+    InRoomKeyVerificationAccept
+);
+impl_from_for_enum!(
+    RoomEvent,
+    InRoomVerificationCancel,
+    InRoomKeyVerificationCancel
+);
+impl_from_for_enum!(RoomEvent, InRoomVerificationDone, InRoomKeyVerificationDone);
+impl_from_for_enum!(RoomEvent, InRoomVerificationKey, InRoomKeyVerificationKey);
+impl_from_for_enum!(RoomEvent, InRoomVerificationMac, InRoomKeyVerificationMac);
+impl_from_for_enum!(
+    RoomEvent,
+    InRoomVerificationReady,
+    InRoomKeyVerificationReady
+);
+impl_from_for_enum!(
+    RoomEvent,
+    InRoomVerificationStart,
+    InRoomKeyVerificationStart
+);
 impl_from_for_enum!(RoomEvent, AliasesEvent, RoomAliases);
 impl_from_for_enum!(RoomEvent, AvatarEvent, RoomAvatar);
 impl_from_for_enum!(RoomEvent, CanonicalAliasEvent, RoomCanonicalAlias);
@@ -538,9 +778,13 @@ impl_from_for_enum!(RoomEvent, StickerEvent, Sticker);
 impl_from_for_enum!(RoomEvent, ThirdPartyInviteEvent, RoomThirdPartyInvite);
 impl_from_for_enum!(RoomEvent, TombstoneEvent, RoomTombstone);
 impl_from_for_enum!(RoomEvent, TopicEvent, RoomTopic);
+impl_from_for_enum!(RoomEvent, SpaceChildEvent, SpaceChild);
+impl_from_for_enum!(RoomEvent, SpaceParentEvent, SpaceParent);
 impl_from_for_enum!(RoomEvent, CustomRoomEvent, CustomRoom);
 impl_from_for_enum!(RoomEvent, CustomStateEvent, CustomState);
 
+impl_from_for_enum!(StateEvent, GroupCallEvent, Call);
+impl_from_for_enum!(StateEvent, CallMemberEvent, CallMember);
 impl_from_for_enum!(StateEvent, AliasesEvent, RoomAliases);
 impl_from_for_enum!(StateEvent, AvatarEvent, RoomAvatar);
 impl_from_for_enum!(StateEvent, CanonicalAliasEvent, RoomCanonicalAlias);
@@ -557,4 +801,6 @@ impl_from_for_enum!(StateEvent, ServerAclEvent, RoomServerAcl);
 impl_from_for_enum!(StateEvent, ThirdPartyInviteEvent, RoomThirdPartyInvite);
 impl_from_for_enum!(StateEvent, TombstoneEvent, RoomTombstone);
 impl_from_for_enum!(StateEvent, TopicEvent, RoomTopic);
+impl_from_for_enum!(StateEvent, SpaceChildEvent, SpaceChild);
+impl_from_for_enum!(StateEvent, SpaceParentEvent, SpaceParent);
 impl_from_for_enum!(StateEvent, CustomStateEvent, CustomState);
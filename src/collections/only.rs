@@ -8,6 +8,10 @@ use super::raw::only as raw;
 use crate::{
     call::{
         answer::AnswerEvent, candidates::CandidatesEvent, hangup::HangupEvent, invite::InviteEvent,
+        negotiate::NegotiateEvent, reject::RejectEvent, select_answer::SelectAnswerEvent,
+    },
+    cross_signing::{
+        master::MasterEvent, self_signing::SelfSigningEvent, user_signing::UserSigningEvent,
     },
     direct::DirectEvent,
     dummy::DummyEvent,
@@ -15,8 +19,19 @@ use crate::{
     fully_read::FullyReadEvent,
     ignored_user_list::IgnoredUserListEvent,
     key::verification::{
-        accept::AcceptEvent, cancel::CancelEvent, key::KeyEvent, mac::MacEvent,
-        request::RequestEvent, start::StartEvent,
+        accept::AcceptEvent,
+        cancel::CancelEvent,
+        done::DoneEvent,
+        in_room::{
+            InRoomVerificationAccept, InRoomVerificationCancel, InRoomVerificationDone,
+            InRoomVerificationKey, InRoomVerificationMac, InRoomVerificationReady,
+            InRoomVerificationStart,
+        },
+        key::KeyEvent,
+        mac::MacEvent,
+        ready::ReadyEvent,
+        request::RequestEvent,
+        start::StartEvent,
     },
     presence::PresenceEvent,
     push_rules::PushRulesEvent,
@@ -26,8 +41,11 @@ use crate::{
         message::{feedback::FeedbackEvent, MessageEvent},
         redaction::RedactionEvent,
     },
+    room_key::withheld::WithheldEvent,
     room_key::RoomKeyEvent,
     room_key_request::RoomKeyRequestEvent,
+    secret::{request::RequestEvent as SecretRequestEvent, send::SendEvent},
+    secret_storage::{default_key::DefaultKeyEvent, key::KeyEvent as SecretStorageKeyEvent},
     sticker::StickerEvent,
     tag::TagEvent,
     typing::TypingEvent,
@@ -39,6 +57,15 @@ use crate::{
 #[serde(untagged)]
 #[allow(clippy::large_enum_variant)]
 pub enum Event {
+    /// m.cross_signing.master
+    CrossSigningMaster(MasterEvent),
+
+    /// m.cross_signing.self_signing
+    CrossSigningSelfSigning(SelfSigningEvent),
+
+    /// m.cross_signing.user_signing
+    CrossSigningUserSigning(UserSigningEvent),
+
     /// m.direct
     Direct(DirectEvent),
 
@@ -57,12 +84,18 @@ pub enum Event {
     /// m.key.verification.cancel
     KeyVerificationCancel(CancelEvent),
 
+    /// m.key.verification.done
+    KeyVerificationDone(DoneEvent),
+
     /// m.key.verification.key
     KeyVerificationKey(KeyEvent),
 
     /// m.key.verification.mac
     KeyVerificationMac(MacEvent),
 
+    /// m.key.verification.ready
+    KeyVerificationReady(ReadyEvent),
+
     /// m.key.verification.request
     KeyVerificationRequest(RequestEvent),
 
@@ -84,6 +117,21 @@ pub enum Event {
     /// m.room_key_request
     RoomKeyRequest(RoomKeyRequestEvent),
 
+    /// m.room_key.withheld
+    RoomKeyWithheld(WithheldEvent),
+
+    /// m.secret.request
+    SecretRequest(SecretRequestEvent),
+
+    /// m.secret.send
+    SecretSend(SendEvent),
+
+    /// m.secret_storage.default_key
+    SecretStorageDefaultKey(DefaultKeyEvent),
+
+    /// m.secret_storage.key.*
+    SecretStorageKey(SecretStorageKeyEvent),
+
     /// m.receipt
     Receipt(ReceiptEvent),
 
@@ -114,6 +162,36 @@ pub enum RoomEvent {
     /// m.call.invite
     CallInvite(InviteEvent),
 
+    /// m.call.negotiate
+    CallNegotiate(NegotiateEvent),
+
+    /// m.call.reject
+    CallReject(RejectEvent),
+
+    /// m.call.select_answer
+    CallSelectAnswer(SelectAnswerEvent),
+
+    /// In-room version of m.key.verification.accept
+    InRoomKeyVerificationAccept(InRoomVerificationAccept),
+
+    /// In-room version of m.key.verification.cancel
+    InRoomKeyVerificationCancel(InRoomVerificationCancel),
+
+    /// In-room version of m.key.verification.done
+    InRoomKeyVerificationDone(InRoomVerificationDone),
+
+    /// In-room version of m.key.verification.key
+    InRoomKeyVerificationKey(InRoomVerificationKey),
+
+    /// In-room version of m.key.verification.mac
+    InRoomKeyVerificationMac(InRoomVerificationMac),
+
+    /// In-room version of m.key.verification.ready
+    InRoomKeyVerificationReady(InRoomVerificationReady),
+
+    /// In-room version of m.key.verification.start
+    InRoomKeyVerificationStart(InRoomVerificationStart),
+
     /// m.room.encrypted
     RoomEncrypted(EncryptedEvent),
 
@@ -142,14 +220,19 @@ impl TryFromRaw for Event {
         use raw::Event::*;
 
         match raw {
+            CrossSigningMaster(c) => conv(Event::CrossSigningMaster, c),
+            CrossSigningSelfSigning(c) => conv(Event::CrossSigningSelfSigning, c),
+            CrossSigningUserSigning(c) => conv(Event::CrossSigningUserSigning, c),
             Direct(c) => conv(Event::Direct, c),
             Dummy(c) => conv(Event::Dummy, c),
             ForwardedRoomKey(c) => conv(Event::ForwardedRoomKey, c),
             FullyRead(c) => conv(Event::FullyRead, c),
             KeyVerificationAccept(c) => conv(Event::KeyVerificationAccept, c),
             KeyVerificationCancel(c) => conv(Event::KeyVerificationCancel, c),
+            KeyVerificationDone(c) => conv(Event::KeyVerificationDone, c),
             KeyVerificationKey(c) => conv(Event::KeyVerificationKey, c),
             KeyVerificationMac(c) => conv(Event::KeyVerificationMac, c),
+            KeyVerificationReady(c) => conv(Event::KeyVerificationReady, c),
             KeyVerificationRequest(c) => conv(Event::KeyVerificationRequest, c),
             KeyVerificationStart(c) => conv(Event::KeyVerificationStart, c),
             IgnoredUserList(c) => conv(Event::IgnoredUserList, c),
@@ -157,6 +240,11 @@ impl TryFromRaw for Event {
             PushRules(c) => conv(Event::PushRules, c),
             RoomKey(c) => conv(Event::RoomKey, c),
             RoomKeyRequest(c) => conv(Event::RoomKeyRequest, c),
+            RoomKeyWithheld(c) => conv(Event::RoomKeyWithheld, c),
+            SecretRequest(c) => conv(Event::SecretRequest, c),
+            SecretSend(c) => conv(Event::SecretSend, c),
+            SecretStorageDefaultKey(c) => conv(Event::SecretStorageDefaultKey, c),
+            SecretStorageKey(c) => conv(Event::SecretStorageKey, c),
             Receipt(c) => conv(Event::Receipt, c),
             Tag(c) => conv(Event::Tag, c),
             Typing(c) => conv(Event::Typing, c),
@@ -178,6 +266,16 @@ impl TryFromRaw for RoomEvent {
             CallCandidates(c) => conv(RoomEvent::CallCandidates, c),
             CallHangup(c) => conv(RoomEvent::CallHangup, c),
             CallInvite(c) => conv(RoomEvent::CallInvite, c),
+            CallNegotiate(c) => conv(RoomEvent::CallNegotiate, c),
+            CallReject(c) => conv(RoomEvent::CallReject, c),
+            CallSelectAnswer(c) => conv(RoomEvent::CallSelectAnswer, c),
+            InRoomKeyVerificationAccept(c) => conv(RoomEvent::InRoomKeyVerificationAccept, c),
+            InRoomKeyVerificationCancel(c) => conv(RoomEvent::InRoomKeyVerificationCancel, c),
+            InRoomKeyVerificationDone(c) => conv(RoomEvent::InRoomKeyVerificationDone, c),
+            InRoomKeyVerificationKey(c) => conv(RoomEvent::InRoomKeyVerificationKey, c),
+            InRoomKeyVerificationMac(c) => conv(RoomEvent::InRoomKeyVerificationMac, c),
+            InRoomKeyVerificationReady(c) => conv(RoomEvent::InRoomKeyVerificationReady, c),
+            InRoomKeyVerificationStart(c) => conv(RoomEvent::InRoomKeyVerificationStart, c),
             RoomEncrypted(c) => conv(RoomEvent::RoomEncrypted, c),
             RoomMessage(c) => conv(RoomEvent::RoomMessage, c),
             RoomMessageFeedback(c) => conv(RoomEvent::RoomMessageFeedback, c),
@@ -188,20 +286,30 @@ impl TryFromRaw for RoomEvent {
     }
 }
 
+impl_from_for_enum!(Event, MasterEvent, CrossSigningMaster);
+impl_from_for_enum!(Event, SelfSigningEvent, CrossSigningSelfSigning);
+impl_from_for_enum!(Event, UserSigningEvent, CrossSigningUserSigning);
 impl_from_for_enum!(Event, DirectEvent, Direct);
 impl_from_for_enum!(Event, DummyEvent, Dummy);
 impl_from_for_enum!(Event, ForwardedRoomKeyEvent, ForwardedRoomKey);
 impl_from_for_enum!(Event, FullyReadEvent, FullyRead);
 impl_from_for_enum!(Event, AcceptEvent, KeyVerificationAccept);
 impl_from_for_enum!(Event, CancelEvent, KeyVerificationCancel);
+impl_from_for_enum!(Event, DoneEvent, KeyVerificationDone);
 impl_from_for_enum!(Event, KeyEvent, KeyVerificationKey);
 impl_from_for_enum!(Event, MacEvent, KeyVerificationMac);
+impl_from_for_enum!(Event, ReadyEvent, KeyVerificationReady);
 impl_from_for_enum!(Event, RequestEvent, KeyVerificationRequest);
 impl_from_for_enum!(Event, StartEvent, KeyVerificationStart);
 impl_from_for_enum!(Event, IgnoredUserListEvent, IgnoredUserList);
 impl_from_for_enum!(Event, PresenceEvent, Presence);
 impl_from_for_enum!(Event, PushRulesEvent, PushRules);
 impl_from_for_enum!(Event, ReceiptEvent, Receipt);
+impl_from_for_enum!(Event, WithheldEvent, RoomKeyWithheld);
+impl_from_for_enum!(Event, SecretRequestEvent, SecretRequest);
+impl_from_for_enum!(Event, SendEvent, SecretSend);
+impl_from_for_enum!(Event, DefaultKeyEvent, SecretStorageDefaultKey);
+impl_from_for_enum!(Event, SecretStorageKeyEvent, SecretStorageKey);
 impl_from_for_enum!(Event, TagEvent, Tag);
 impl_from_for_enum!(Event, TypingEvent, Typing);
 impl_from_for_enum!(Event, CustomEvent, Custom);
@@ -210,6 +318,32 @@ impl_from_for_enum!(RoomEvent, AnswerEvent, CallAnswer);
 impl_from_for_enum!(RoomEvent, CandidatesEvent, CallCandidates);
 impl_from_for_enum!(RoomEvent, HangupEvent, CallHangup);
 impl_from_for_enum!(RoomEvent, InviteEvent, CallInvite);
+impl_from_for_enum!(RoomEvent, NegotiateEvent, CallNegotiate);
+impl_from_for_enum!(RoomEvent, RejectEvent, CallReject);
+impl_from_for_enum!(RoomEvent, SelectAnswerEvent, CallSelectAnswer);
+impl_from_for_enum!(
+    RoomEvent,
+    InRoomVerificationAccept,
+    InRoomKeyVerificationAccept
+);
+impl_from_for_enum!(
+    RoomEvent,
+    InRoomVerificationCancel,
+    InRoomKeyVerificationCancel
+);
+impl_from_for_enum!(RoomEvent, InRoomVerificationDone, InRoomKeyVerificationDone);
+impl_from_for_enum!(RoomEvent, InRoomVerificationKey, InRoomKeyVerificationKey);
+impl_from_for_enum!(RoomEvent, InRoomVerificationMac, InRoomKeyVerificationMac);
+impl_from_for_enum!(
+    RoomEvent,
+    InRoomVerificationReady,
+    InRoomKeyVerificationReady
+);
+impl_from_for_enum!(
+    RoomEvent,
+    InRoomVerificationStart,
+    InRoomKeyVerificationStart
+);
 impl_from_for_enum!(RoomEvent, EncryptedEvent, RoomEncrypted);
 impl_from_for_enum!(RoomEvent, MessageEvent, RoomMessage);
 impl_from_for_enum!(RoomEvent, FeedbackEvent, RoomMessageFeedback);
@@ -7,8 +7,14 @@ use serde_json::Value as JsonValue;
 use super::only;
 use crate::{
     call::{
-        answer::raw::AnswerEvent, candidates::raw::CandidatesEvent, hangup::raw::HangupEvent,
-        invite::raw::InviteEvent,
+        answer::raw::AnswerEvent, candidates::raw::CandidatesEvent, group::raw::GroupCallEvent,
+        hangup::raw::HangupEvent, invite::raw::InviteEvent, member::raw::CallMemberEvent,
+        negotiate::raw::NegotiateEvent, reject::raw::RejectEvent,
+        select_answer::raw::SelectAnswerEvent,
+    },
+    cross_signing::{
+        master::raw::MasterEvent, self_signing::raw::SelfSigningEvent,
+        user_signing::raw::UserSigningEvent,
     },
     custom::raw::{CustomEvent, CustomRoomEvent, CustomStateEvent},
     direct::raw::DirectEvent,
@@ -17,8 +23,19 @@ use crate::{
     fully_read::raw::FullyReadEvent,
     ignored_user_list::raw::IgnoredUserListEvent,
     key::verification::{
-        accept::raw::AcceptEvent, cancel::raw::CancelEvent, key::raw::KeyEvent, mac::raw::MacEvent,
-        request::raw::RequestEvent, start::raw::StartEvent,
+        accept::raw::AcceptEvent,
+        cancel::raw::CancelEvent,
+        done::raw::DoneEvent,
+        in_room::raw::{
+            InRoomVerificationAccept, InRoomVerificationCancel, InRoomVerificationDone,
+            InRoomVerificationKey, InRoomVerificationMac, InRoomVerificationReady,
+            InRoomVerificationStart,
+        },
+        key::raw::KeyEvent,
+        mac::raw::MacEvent,
+        ready::raw::ReadyEvent,
+        request::raw::RequestEvent,
+        start::raw::StartEvent,
     },
     presence::raw::PresenceEvent,
     push_rules::raw::PushRulesEvent,
@@ -45,7 +62,14 @@ use crate::{
         topic::raw::TopicEvent,
     },
     room_key::raw::RoomKeyEvent,
+    room_key::withheld::raw::WithheldEvent,
     room_key_request::raw::RoomKeyRequestEvent,
+    secret::{request::raw::RequestEvent as SecretRequestEvent, send::raw::SendEvent},
+    secret_storage::{
+        default_key::raw::DefaultKeyEvent,
+        key::{is_key_event_type, KeyEvent as SecretStorageKeyEvent},
+    },
+    space::{child::raw::SpaceChildEvent, parent::raw::SpaceParentEvent},
     sticker::raw::StickerEvent,
     tag::raw::TagEvent,
     typing::raw::TypingEvent,
@@ -57,6 +81,9 @@ use crate::{
 #[derive(Clone, Debug)]
 #[allow(clippy::large_enum_variant)]
 pub enum Event {
+    /// m.call
+    Call(GroupCallEvent),
+
     /// m.call.answer
     CallAnswer(AnswerEvent),
 
@@ -69,6 +96,48 @@ pub enum Event {
     /// m.call.invite
     CallInvite(InviteEvent),
 
+    /// m.call.member
+    CallMember(CallMemberEvent),
+
+    /// m.call.negotiate
+    CallNegotiate(NegotiateEvent),
+
+    /// m.call.reject
+    CallReject(RejectEvent),
+
+    /// m.call.select_answer
+    CallSelectAnswer(SelectAnswerEvent),
+
+    /// m.cross_signing.master
+    CrossSigningMaster(MasterEvent),
+
+    /// m.cross_signing.self_signing
+    CrossSigningSelfSigning(SelfSigningEvent),
+
+    /// m.cross_signing.user_signing
+    CrossSigningUserSigning(UserSigningEvent),
+
+    /// In-room version of m.key.verification.accept
+    InRoomKeyVerificationAccept(InRoomVerificationAccept),
+
+    /// In-room version of m.key.verification.cancel
+    InRoomKeyVerificationCancel(InRoomVerificationCancel),
+
+    /// In-room version of m.key.verification.done
+    InRoomKeyVerificationDone(InRoomVerificationDone),
+
+    /// In-room version of m.key.verification.key
+    InRoomKeyVerificationKey(InRoomVerificationKey),
+
+    /// In-room version of m.key.verification.mac
+    InRoomKeyVerificationMac(InRoomVerificationMac),
+
+    /// In-room version of m.key.verification.ready
+    InRoomKeyVerificationReady(InRoomVerificationReady),
+
+    /// In-room version of m.key.verification.start
+    InRoomKeyVerificationStart(InRoomVerificationStart),
+
     /// m.direct
     Direct(DirectEvent),
 
@@ -90,12 +159,18 @@ pub enum Event {
     /// m.key.verification.cancel
     KeyVerificationCancel(CancelEvent),
 
+    /// m.key.verification.done
+    KeyVerificationDone(DoneEvent),
+
     /// m.key.verification.key
     KeyVerificationKey(KeyEvent),
 
     /// m.key.verification.mac
     KeyVerificationMac(MacEvent),
 
+    /// m.key.verification.ready
+    KeyVerificationReady(ReadyEvent),
+
     /// m.key.verification.request
     KeyVerificationRequest(RequestEvent),
 
@@ -171,12 +246,33 @@ pub enum Event {
     /// m.room.topic
     RoomTopic(TopicEvent),
 
+    /// m.space.child
+    SpaceChild(SpaceChildEvent),
+
+    /// m.space.parent
+    SpaceParent(SpaceParentEvent),
+
     /// m.room_key
     RoomKey(RoomKeyEvent),
 
     /// m.room_key_request
     RoomKeyRequest(RoomKeyRequestEvent),
 
+    /// m.room_key.withheld
+    RoomKeyWithheld(WithheldEvent),
+
+    /// m.secret.request
+    SecretRequest(SecretRequestEvent),
+
+    /// m.secret.send
+    SecretSend(SendEvent),
+
+    /// m.secret_storage.default_key
+    SecretStorageDefaultKey(DefaultKeyEvent),
+
+    /// m.secret_storage.key.*
+    SecretStorageKey(SecretStorageKeyEvent),
+
     /// m.sticker
     Sticker(StickerEvent),
 
@@ -200,6 +296,9 @@ pub enum Event {
 #[derive(Clone, Debug)]
 #[allow(clippy::large_enum_variant)]
 pub enum RoomEvent {
+    /// m.call
+    Call(GroupCallEvent),
+
     /// m.call.answer
     CallAnswer(AnswerEvent),
 
@@ -212,6 +311,39 @@ pub enum RoomEvent {
     /// m.call.invite
     CallInvite(InviteEvent),
 
+    /// m.call.member
+    CallMember(CallMemberEvent),
+
+    /// m.call.negotiate
+    CallNegotiate(NegotiateEvent),
+
+    /// m.call.reject
+    CallReject(RejectEvent),
+
+    /// m.call.select_answer
+    CallSelectAnswer(SelectAnswerEvent),
+
+    /// In-room version of m.key.verification.accept
+    InRoomKeyVerificationAccept(InRoomVerificationAccept),
+
+    /// In-room version of m.key.verification.cancel
+    InRoomKeyVerificationCancel(InRoomVerificationCancel),
+
+    /// In-room version of m.key.verification.done
+    InRoomKeyVerificationDone(InRoomVerificationDone),
+
+    /// In-room version of m.key.verification.key
+    InRoomKeyVerificationKey(InRoomVerificationKey),
+
+    /// In-room version of m.key.verification.mac
+    InRoomKeyVerificationMac(InRoomVerificationMac),
+
+    /// In-room version of m.key.verification.ready
+    InRoomKeyVerificationReady(InRoomVerificationReady),
+
+    /// In-room version of m.key.verification.start
+    InRoomKeyVerificationStart(InRoomVerificationStart),
+
     /// m.room.aliases
     RoomAliases(AliasesEvent),
 
@@ -272,6 +404,12 @@ pub enum RoomEvent {
     /// m.room.topic
     RoomTopic(TopicEvent),
 
+    /// m.space.child
+    SpaceChild(SpaceChildEvent),
+
+    /// m.space.parent
+    SpaceParent(SpaceParentEvent),
+
     /// m.sticker
     Sticker(StickerEvent),
 
@@ -286,6 +424,12 @@ pub enum RoomEvent {
 #[derive(Clone, Debug)]
 #[allow(clippy::large_enum_variant)]
 pub enum StateEvent {
+    /// m.call
+    Call(GroupCallEvent),
+
+    /// m.call.member
+    CallMember(CallMemberEvent),
+
     /// m.room.aliases
     RoomAliases(AliasesEvent),
 
@@ -334,6 +478,12 @@ pub enum StateEvent {
     /// m.room.topic
     RoomTopic(TopicEvent),
 
+    /// m.space.child
+    SpaceChild(SpaceChildEvent),
+
+    /// m.space.parent
+    SpaceParent(SpaceParentEvent),
+
     /// Any state event that is not part of the specification.
     CustomState(CustomStateEvent),
 }
@@ -343,28 +493,69 @@ impl<'de> Deserialize<'de> for Event {
     where
         D: Deserializer<'de>,
     {
-        use crate::util::try_variant_from_value as from_value;
+        use crate::util::{
+            try_in_room_or_to_device_variant_from_value as in_room_or_to_device,
+            try_variant_from_value as from_value,
+        };
         use EventType::*;
 
         let value = JsonValue::deserialize(deserializer)?;
         let event_type = get_field(&value, "type")?;
 
         match event_type {
+            Call => from_value(value, Event::Call),
             CallAnswer => from_value(value, Event::CallAnswer),
             CallCandidates => from_value(value, Event::CallCandidates),
             CallHangup => from_value(value, Event::CallHangup),
             CallInvite => from_value(value, Event::CallInvite),
+            CallMember => from_value(value, Event::CallMember),
+            CallNegotiate => from_value(value, Event::CallNegotiate),
+            CallReject => from_value(value, Event::CallReject),
+            CallSelectAnswer => from_value(value, Event::CallSelectAnswer),
+            CrossSigningMaster => from_value(value, Event::CrossSigningMaster),
+            CrossSigningSelfSigning => from_value(value, Event::CrossSigningSelfSigning),
+            CrossSigningUserSigning => from_value(value, Event::CrossSigningUserSigning),
             Direct => from_value(value, Event::Direct),
             Dummy => from_value(value, Event::Dummy),
             ForwardedRoomKey => from_value(value, Event::ForwardedRoomKey),
             FullyRead => from_value(value, Event::FullyRead),
             IgnoredUserList => from_value(value, Event::IgnoredUserList),
-            KeyVerificationAccept => from_value(value, Event::KeyVerificationAccept),
-            KeyVerificationCancel => from_value(value, Event::KeyVerificationCancel),
-            KeyVerificationKey => from_value(value, Event::KeyVerificationKey),
-            KeyVerificationMac => from_value(value, Event::KeyVerificationMac),
+            KeyVerificationAccept => in_room_or_to_device(
+                value,
+                Event::InRoomKeyVerificationAccept,
+                Event::KeyVerificationAccept,
+            ),
+            KeyVerificationCancel => in_room_or_to_device(
+                value,
+                Event::InRoomKeyVerificationCancel,
+                Event::KeyVerificationCancel,
+            ),
+            KeyVerificationDone => in_room_or_to_device(
+                value,
+                Event::InRoomKeyVerificationDone,
+                Event::KeyVerificationDone,
+            ),
+            KeyVerificationKey => in_room_or_to_device(
+                value,
+                Event::InRoomKeyVerificationKey,
+                Event::KeyVerificationKey,
+            ),
+            KeyVerificationMac => in_room_or_to_device(
+                value,
+                Event::InRoomKeyVerificationMac,
+                Event::KeyVerificationMac,
+            ),
+            KeyVerificationReady => in_room_or_to_device(
+                value,
+                Event::InRoomKeyVerificationReady,
+                Event::KeyVerificationReady,
+            ),
+            KeyVerificationStart => in_room_or_to_device(
+                value,
+                Event::InRoomKeyVerificationStart,
+                Event::KeyVerificationStart,
+            ),
             KeyVerificationRequest => from_value(value, Event::KeyVerificationRequest),
-            KeyVerificationStart => from_value(value, Event::KeyVerificationStart),
             Presence => from_value(value, Event::Presence),
             PushRules => from_value(value, Event::PushRules),
             Receipt => from_value(value, Event::Receipt),
@@ -388,13 +579,21 @@ impl<'de> Deserialize<'de> for Event {
             RoomThirdPartyInvite => from_value(value, Event::RoomThirdPartyInvite),
             RoomTombstone => from_value(value, Event::RoomTombstone),
             RoomTopic => from_value(value, Event::RoomTopic),
+            SpaceChild => from_value(value, Event::SpaceChild),
+            SpaceParent => from_value(value, Event::SpaceParent),
             RoomKey => from_value(value, Event::RoomKey),
             RoomKeyRequest => from_value(value, Event::RoomKeyRequest),
+            RoomKeyWithheld => from_value(value, Event::RoomKeyWithheld),
+            SecretRequest => from_value(value, Event::SecretRequest),
+            SecretSend => from_value(value, Event::SecretSend),
+            SecretStorageDefaultKey => from_value(value, Event::SecretStorageDefaultKey),
             Sticker => from_value(value, Event::Sticker),
             Tag => from_value(value, Event::Tag),
             Typing => from_value(value, Event::Typing),
-            Custom(_event_type_name) => {
-                if value.get("state_key").is_some() {
+            Custom(event_type_name) => {
+                if is_key_event_type(&event_type_name) {
+                    from_value(value, Event::SecretStorageKey)
+                } else if value.get("state_key").is_some() {
                     from_value(value, Event::CustomState)
                 } else if value.get("event_id").is_some()
                     && value.get("room_id").is_some()
@@ -421,10 +620,22 @@ impl<'de> Deserialize<'de> for RoomEvent {
         let event_type = get_field(&value, "type")?;
 
         match event_type {
+            Call => from_value(value, RoomEvent::Call),
             CallAnswer => from_value(value, RoomEvent::CallAnswer),
             CallCandidates => from_value(value, RoomEvent::CallCandidates),
             CallHangup => from_value(value, RoomEvent::CallHangup),
             CallInvite => from_value(value, RoomEvent::CallInvite),
+            CallMember => from_value(value, RoomEvent::CallMember),
+            CallNegotiate => from_value(value, RoomEvent::CallNegotiate),
+            CallReject => from_value(value, RoomEvent::CallReject),
+            CallSelectAnswer => from_value(value, RoomEvent::CallSelectAnswer),
+            KeyVerificationAccept => from_value(value, RoomEvent::InRoomKeyVerificationAccept),
+            KeyVerificationCancel => from_value(value, RoomEvent::InRoomKeyVerificationCancel),
+            KeyVerificationDone => from_value(value, RoomEvent::InRoomKeyVerificationDone),
+            KeyVerificationKey => from_value(value, RoomEvent::InRoomKeyVerificationKey),
+            KeyVerificationMac => from_value(value, RoomEvent::InRoomKeyVerificationMac),
+            KeyVerificationReady => from_value(value, RoomEvent::InRoomKeyVerificationReady),
+            KeyVerificationStart => from_value(value, RoomEvent::InRoomKeyVerificationStart),
             RoomAliases => from_value(value, RoomEvent::RoomAliases),
             RoomAvatar => from_value(value, RoomEvent::RoomAvatar),
             RoomCanonicalAlias => from_value(value, RoomEvent::RoomCanonicalAlias),
@@ -445,6 +656,8 @@ impl<'de> Deserialize<'de> for RoomEvent {
             RoomThirdPartyInvite => from_value(value, RoomEvent::RoomThirdPartyInvite),
             RoomTombstone => from_value(value, RoomEvent::RoomTombstone),
             RoomTopic => from_value(value, RoomEvent::RoomTopic),
+            SpaceChild => from_value(value, RoomEvent::SpaceChild),
+            SpaceParent => from_value(value, RoomEvent::SpaceParent),
             Sticker => from_value(value, RoomEvent::Sticker),
             Custom(_event_type_name) => {
                 if value.get("state_key").is_some() {
@@ -453,22 +666,24 @@ impl<'de> Deserialize<'de> for RoomEvent {
                     from_value(value, RoomEvent::CustomRoom)
                 }
             }
-            Direct
+            CrossSigningMaster
+            | CrossSigningSelfSigning
+            | CrossSigningUserSigning
+            | Direct
             | Dummy
             | ForwardedRoomKey
             | FullyRead
             | IgnoredUserList
-            | KeyVerificationAccept
-            | KeyVerificationCancel
-            | KeyVerificationKey
-            | KeyVerificationMac
             | KeyVerificationRequest
-            | KeyVerificationStart
             | Presence
             | PushRules
             | Receipt
             | RoomKey
             | RoomKeyRequest
+            | RoomKeyWithheld
+            | SecretRequest
+            | SecretSend
+            | SecretStorageDefaultKey
             | Tag
             | Typing => Err(D::Error::custom("invalid event type")),
         }
@@ -487,6 +702,8 @@ impl<'de> Deserialize<'de> for StateEvent {
         let event_type = get_field(&value, "type")?;
 
         match event_type {
+            Call => from_value(value, StateEvent::Call),
+            CallMember => from_value(value, StateEvent::CallMember),
             RoomAliases => from_value(value, StateEvent::RoomAliases),
             RoomAvatar => from_value(value, StateEvent::RoomAvatar),
             RoomCanonicalAlias => from_value(value, StateEvent::RoomCanonicalAlias),
@@ -503,11 +720,19 @@ impl<'de> Deserialize<'de> for StateEvent {
             RoomThirdPartyInvite => from_value(value, StateEvent::RoomThirdPartyInvite),
             RoomTombstone => from_value(value, StateEvent::RoomTombstone),
             RoomTopic => from_value(value, StateEvent::RoomTopic),
+            SpaceChild => from_value(value, StateEvent::SpaceChild),
+            SpaceParent => from_value(value, StateEvent::SpaceParent),
             Custom(_event_type_name) => from_value(value, StateEvent::CustomState),
             CallAnswer
             | CallCandidates
             | CallHangup
             | CallInvite
+            | CallNegotiate
+            | CallReject
+            | CallSelectAnswer
+            | CrossSigningMaster
+            | CrossSigningSelfSigning
+            | CrossSigningUserSigning
             | Direct
             | Dummy
             | ForwardedRoomKey
@@ -515,8 +740,10 @@ impl<'de> Deserialize<'de> for StateEvent {
             | IgnoredUserList
             | KeyVerificationAccept
             | KeyVerificationCancel
+            | KeyVerificationDone
             | KeyVerificationKey
             | KeyVerificationMac
+            | KeyVerificationReady
             | KeyVerificationRequest
             | KeyVerificationStart
             | Presence
@@ -525,9 +752,13 @@ impl<'de> Deserialize<'de> for StateEvent {
             | RoomEncrypted
             | RoomKey
             | RoomKeyRequest
+            | RoomKeyWithheld
             | RoomMessage
             | RoomMessageFeedback
             | RoomRedaction
+            | SecretRequest
+            | SecretSend
+            | SecretStorageDefaultKey
             | Sticker
             | Tag
             | Typing => Err(D::Error::custom("invalid event type")),
@@ -540,14 +771,19 @@ impl From<only::Event> for Event {
         use only::Event::*;
 
         match event {
+            CrossSigningMaster(ev) => Event::CrossSigningMaster(ev),
+            CrossSigningSelfSigning(ev) => Event::CrossSigningSelfSigning(ev),
+            CrossSigningUserSigning(ev) => Event::CrossSigningUserSigning(ev),
             Direct(ev) => Event::Direct(ev),
             Dummy(ev) => Event::Dummy(ev),
             ForwardedRoomKey(ev) => Event::ForwardedRoomKey(ev),
             FullyRead(ev) => Event::FullyRead(ev),
             KeyVerificationAccept(ev) => Event::KeyVerificationAccept(ev),
             KeyVerificationCancel(ev) => Event::KeyVerificationCancel(ev),
+            KeyVerificationDone(ev) => Event::KeyVerificationDone(ev),
             KeyVerificationKey(ev) => Event::KeyVerificationKey(ev),
             KeyVerificationMac(ev) => Event::KeyVerificationMac(ev),
+            KeyVerificationReady(ev) => Event::KeyVerificationReady(ev),
             KeyVerificationRequest(ev) => Event::KeyVerificationRequest(ev),
             KeyVerificationStart(ev) => Event::KeyVerificationStart(ev),
             IgnoredUserList(ev) => Event::IgnoredUserList(ev),
@@ -555,6 +791,11 @@ impl From<only::Event> for Event {
             PushRules(ev) => Event::PushRules(ev),
             RoomKey(ev) => Event::RoomKey(ev),
             RoomKeyRequest(ev) => Event::RoomKeyRequest(ev),
+            RoomKeyWithheld(ev) => Event::RoomKeyWithheld(ev),
+            SecretRequest(ev) => Event::SecretRequest(ev),
+            SecretSend(ev) => Event::SecretSend(ev),
+            SecretStorageDefaultKey(ev) => Event::SecretStorageDefaultKey(ev),
+            SecretStorageKey(ev) => Event::SecretStorageKey(ev),
             Receipt(ev) => Event::Receipt(ev),
             Tag(ev) => Event::Tag(ev),
             Typing(ev) => Event::Typing(ev),
@@ -568,10 +809,22 @@ impl From<RoomEvent> for Event {
         use RoomEvent::*;
 
         match room_event {
+            Call(ev) => Event::Call(ev),
             CallAnswer(ev) => Event::CallAnswer(ev),
             CallCandidates(ev) => Event::CallCandidates(ev),
             CallHangup(ev) => Event::CallHangup(ev),
             CallInvite(ev) => Event::CallInvite(ev),
+            CallMember(ev) => Event::CallMember(ev),
+            CallNegotiate(ev) => Event::CallNegotiate(ev),
+            CallReject(ev) => Event::CallReject(ev),
+            CallSelectAnswer(ev) => Event::CallSelectAnswer(ev),
+            InRoomKeyVerificationAccept(ev) => Event::InRoomKeyVerificationAccept(ev),
+            InRoomKeyVerificationCancel(ev) => Event::InRoomKeyVerificationCancel(ev),
+            InRoomKeyVerificationDone(ev) => Event::InRoomKeyVerificationDone(ev),
+            InRoomKeyVerificationKey(ev) => Event::InRoomKeyVerificationKey(ev),
+            InRoomKeyVerificationMac(ev) => Event::InRoomKeyVerificationMac(ev),
+            InRoomKeyVerificationReady(ev) => Event::InRoomKeyVerificationReady(ev),
+            InRoomKeyVerificationStart(ev) => Event::InRoomKeyVerificationStart(ev),
             RoomAliases(ev) => Event::RoomAliases(ev),
             RoomAvatar(ev) => Event::RoomAvatar(ev),
             RoomCanonicalAlias(ev) => Event::RoomCanonicalAlias(ev),
@@ -592,6 +845,8 @@ impl From<RoomEvent> for Event {
             RoomThirdPartyInvite(ev) => Event::RoomThirdPartyInvite(ev),
             RoomTombstone(ev) => Event::RoomTombstone(ev),
             RoomTopic(ev) => Event::RoomTopic(ev),
+            SpaceChild(ev) => Event::SpaceChild(ev),
+            SpaceParent(ev) => Event::SpaceParent(ev),
             Sticker(ev) => Event::Sticker(ev),
             CustomRoom(ev) => Event::CustomRoom(ev),
             CustomState(ev) => Event::CustomState(ev),
@@ -608,6 +863,16 @@ impl From<only::RoomEvent> for RoomEvent {
             CallCandidates(ev) => RoomEvent::CallCandidates(ev),
             CallHangup(ev) => RoomEvent::CallHangup(ev),
             CallInvite(ev) => RoomEvent::CallInvite(ev),
+            CallNegotiate(ev) => RoomEvent::CallNegotiate(ev),
+            CallReject(ev) => RoomEvent::CallReject(ev),
+            CallSelectAnswer(ev) => RoomEvent::CallSelectAnswer(ev),
+            InRoomKeyVerificationAccept(ev) => RoomEvent::InRoomKeyVerificationAccept(ev),
+            InRoomKeyVerificationCancel(ev) => RoomEvent::InRoomKeyVerificationCancel(ev),
+            InRoomKeyVerificationDone(ev) => RoomEvent::InRoomKeyVerificationDone(ev),
+            InRoomKeyVerificationKey(ev) => RoomEvent::InRoomKeyVerificationKey(ev),
+            InRoomKeyVerificationMac(ev) => RoomEvent::InRoomKeyVerificationMac(ev),
+            InRoomKeyVerificationReady(ev) => RoomEvent::InRoomKeyVerificationReady(ev),
+            InRoomKeyVerificationStart(ev) => RoomEvent::InRoomKeyVerificationStart(ev),
             RoomEncrypted(ev) => RoomEvent::RoomEncrypted(ev),
             RoomMessage(ev) => RoomEvent::RoomMessage(ev),
             RoomMessageFeedback(ev) => RoomEvent::RoomMessageFeedback(ev),
@@ -623,6 +888,8 @@ impl From<StateEvent> for RoomEvent {
         use StateEvent::*;
 
         match state_event {
+            Call(ev) => RoomEvent::Call(ev),
+            CallMember(ev) => RoomEvent::CallMember(ev),
             RoomAliases(ev) => RoomEvent::RoomAliases(ev),
             RoomAvatar(ev) => RoomEvent::RoomAvatar(ev),
             RoomCanonicalAlias(ev) => RoomEvent::RoomCanonicalAlias(ev),
@@ -639,6 +906,8 @@ impl From<StateEvent> for RoomEvent {
             RoomThirdPartyInvite(ev) => RoomEvent::RoomThirdPartyInvite(ev),
             RoomTombstone(ev) => RoomEvent::RoomTombstone(ev),
             RoomTopic(ev) => RoomEvent::RoomTopic(ev),
+            SpaceChild(ev) => RoomEvent::SpaceChild(ev),
+            SpaceParent(ev) => RoomEvent::SpaceParent(ev),
             CustomState(ev) => RoomEvent::CustomState(ev),
         }
     }
@@ -4,11 +4,15 @@
 use serde::{de::Error as _, Deserialize, Deserializer};
 use serde_json::Value as JsonValue;
 
-pub use super::all::StateEvent;
 use crate::{
     call::{
         answer::raw::AnswerEvent, candidates::raw::CandidatesEvent, hangup::raw::HangupEvent,
-        invite::raw::InviteEvent,
+        invite::raw::InviteEvent, negotiate::raw::NegotiateEvent, reject::raw::RejectEvent,
+        select_answer::raw::SelectAnswerEvent,
+    },
+    cross_signing::{
+        master::raw::MasterEvent, self_signing::raw::SelfSigningEvent,
+        user_signing::raw::UserSigningEvent,
     },
     custom::raw::{CustomEvent, CustomRoomEvent},
     direct::raw::DirectEvent,
@@ -17,8 +21,19 @@ use crate::{
     fully_read::raw::FullyReadEvent,
     ignored_user_list::raw::IgnoredUserListEvent,
     key::verification::{
-        accept::raw::AcceptEvent, cancel::raw::CancelEvent, key::raw::KeyEvent, mac::raw::MacEvent,
-        request::raw::RequestEvent, start::raw::StartEvent,
+        accept::raw::AcceptEvent,
+        cancel::raw::CancelEvent,
+        done::raw::DoneEvent,
+        in_room::raw::{
+            InRoomVerificationAccept, InRoomVerificationCancel, InRoomVerificationDone,
+            InRoomVerificationKey, InRoomVerificationMac, InRoomVerificationReady,
+            InRoomVerificationStart,
+        },
+        key::raw::KeyEvent,
+        mac::raw::MacEvent,
+        ready::raw::ReadyEvent,
+        request::raw::RequestEvent,
+        start::raw::StartEvent,
     },
     presence::raw::PresenceEvent,
     push_rules::raw::PushRulesEvent,
@@ -29,7 +44,13 @@ use crate::{
         redaction::raw::RedactionEvent,
     },
     room_key::raw::RoomKeyEvent,
+    room_key::withheld::raw::WithheldEvent,
     room_key_request::raw::RoomKeyRequestEvent,
+    secret::{request::raw::RequestEvent as SecretRequestEvent, send::raw::SendEvent},
+    secret_storage::{
+        default_key::raw::DefaultKeyEvent,
+        key::{is_key_event_type, KeyEvent as SecretStorageKeyEvent},
+    },
     sticker::raw::StickerEvent,
     tag::raw::TagEvent,
     typing::raw::TypingEvent,
@@ -41,6 +62,15 @@ use crate::{
 #[derive(Clone, Debug)]
 #[allow(clippy::large_enum_variant)]
 pub enum Event {
+    /// m.cross_signing.master
+    CrossSigningMaster(MasterEvent),
+
+    /// m.cross_signing.self_signing
+    CrossSigningSelfSigning(SelfSigningEvent),
+
+    /// m.cross_signing.user_signing
+    CrossSigningUserSigning(UserSigningEvent),
+
     /// m.direct
     Direct(DirectEvent),
 
@@ -59,12 +89,18 @@ pub enum Event {
     /// m.key.verification.cancel
     KeyVerificationCancel(CancelEvent),
 
+    /// m.key.verification.done
+    KeyVerificationDone(DoneEvent),
+
     /// m.key.verification.key
     KeyVerificationKey(KeyEvent),
 
     /// m.key.verification.mac
     KeyVerificationMac(MacEvent),
 
+    /// m.key.verification.ready
+    KeyVerificationReady(ReadyEvent),
+
     /// m.key.verification.request
     KeyVerificationRequest(RequestEvent),
 
@@ -86,6 +122,21 @@ pub enum Event {
     /// m.room_key_request
     RoomKeyRequest(RoomKeyRequestEvent),
 
+    /// m.room_key.withheld
+    RoomKeyWithheld(WithheldEvent),
+
+    /// m.secret.request
+    SecretRequest(SecretRequestEvent),
+
+    /// m.secret.send
+    SecretSend(SendEvent),
+
+    /// m.secret_storage.default_key
+    SecretStorageDefaultKey(DefaultKeyEvent),
+
+    /// m.secret_storage.key.*
+    SecretStorageKey(SecretStorageKeyEvent),
+
     /// m.receipt
     Receipt(ReceiptEvent),
 
@@ -115,6 +166,36 @@ pub enum RoomEvent {
     /// m.call.invite
     CallInvite(InviteEvent),
 
+    /// m.call.negotiate
+    CallNegotiate(NegotiateEvent),
+
+    /// m.call.reject
+    CallReject(RejectEvent),
+
+    /// m.call.select_answer
+    CallSelectAnswer(SelectAnswerEvent),
+
+    /// In-room version of m.key.verification.accept
+    InRoomKeyVerificationAccept(InRoomVerificationAccept),
+
+    /// In-room version of m.key.verification.cancel
+    InRoomKeyVerificationCancel(InRoomVerificationCancel),
+
+    /// In-room version of m.key.verification.done
+    InRoomKeyVerificationDone(InRoomVerificationDone),
+
+    /// In-room version of m.key.verification.key
+    InRoomKeyVerificationKey(InRoomVerificationKey),
+
+    /// In-room version of m.key.verification.mac
+    InRoomKeyVerificationMac(InRoomVerificationMac),
+
+    /// In-room version of m.key.verification.ready
+    InRoomKeyVerificationReady(InRoomVerificationReady),
+
+    /// In-room version of m.key.verification.start
+    InRoomKeyVerificationStart(InRoomVerificationStart),
+
     /// m.room.encrypted
     RoomEncrypted(EncryptedEvent),
 
@@ -146,14 +227,19 @@ impl<'de> Deserialize<'de> for Event {
         let event_type = get_field(&value, "type")?;
 
         match event_type {
+            CrossSigningMaster => from_value(value, Event::CrossSigningMaster),
+            CrossSigningSelfSigning => from_value(value, Event::CrossSigningSelfSigning),
+            CrossSigningUserSigning => from_value(value, Event::CrossSigningUserSigning),
             Direct => from_value(value, Event::Direct),
             Dummy => from_value(value, Event::Dummy),
             ForwardedRoomKey => from_value(value, Event::ForwardedRoomKey),
             FullyRead => from_value(value, Event::FullyRead),
             KeyVerificationAccept => from_value(value, Event::KeyVerificationAccept),
             KeyVerificationCancel => from_value(value, Event::KeyVerificationCancel),
+            KeyVerificationDone => from_value(value, Event::KeyVerificationDone),
             KeyVerificationKey => from_value(value, Event::KeyVerificationKey),
             KeyVerificationMac => from_value(value, Event::KeyVerificationMac),
+            KeyVerificationReady => from_value(value, Event::KeyVerificationReady),
             KeyVerificationRequest => from_value(value, Event::KeyVerificationRequest),
             KeyVerificationStart => from_value(value, Event::KeyVerificationStart),
             IgnoredUserList => from_value(value, Event::IgnoredUserList),
@@ -161,14 +247,29 @@ impl<'de> Deserialize<'de> for Event {
             PushRules => from_value(value, Event::PushRules),
             RoomKey => from_value(value, Event::RoomKey),
             RoomKeyRequest => from_value(value, Event::RoomKeyRequest),
+            RoomKeyWithheld => from_value(value, Event::RoomKeyWithheld),
+            SecretRequest => from_value(value, Event::SecretRequest),
+            SecretSend => from_value(value, Event::SecretSend),
+            SecretStorageDefaultKey => from_value(value, Event::SecretStorageDefaultKey),
             Receipt => from_value(value, Event::Receipt),
             Tag => from_value(value, Event::Tag),
             Typing => from_value(value, Event::Typing),
-            Custom(_event_type_name) => from_value(value, Event::Custom),
-            CallAnswer
+            Custom(event_type_name) => {
+                if is_key_event_type(&event_type_name) {
+                    from_value(value, Event::SecretStorageKey)
+                } else {
+                    from_value(value, Event::Custom)
+                }
+            }
+            Call
+            | CallAnswer
             | CallCandidates
             | CallHangup
             | CallInvite
+            | CallMember
+            | CallNegotiate
+            | CallReject
+            | CallSelectAnswer
             | RoomAliases
             | RoomAvatar
             | RoomCanonicalAlias
@@ -188,6 +289,8 @@ impl<'de> Deserialize<'de> for Event {
             | RoomThirdPartyInvite
             | RoomTombstone
             | RoomTopic
+            | SpaceChild
+            | SpaceParent
             | RoomRedaction
             | Sticker => Err(D::Error::custom("invalid event type")),
         }
@@ -210,23 +313,33 @@ impl<'de> Deserialize<'de> for RoomEvent {
             CallCandidates => from_value(value, RoomEvent::CallCandidates),
             CallHangup => from_value(value, RoomEvent::CallHangup),
             CallInvite => from_value(value, RoomEvent::CallInvite),
+            CallNegotiate => from_value(value, RoomEvent::CallNegotiate),
+            CallReject => from_value(value, RoomEvent::CallReject),
+            CallSelectAnswer => from_value(value, RoomEvent::CallSelectAnswer),
+            KeyVerificationAccept => from_value(value, RoomEvent::InRoomKeyVerificationAccept),
+            KeyVerificationCancel => from_value(value, RoomEvent::InRoomKeyVerificationCancel),
+            KeyVerificationDone => from_value(value, RoomEvent::InRoomKeyVerificationDone),
+            KeyVerificationKey => from_value(value, RoomEvent::InRoomKeyVerificationKey),
+            KeyVerificationMac => from_value(value, RoomEvent::InRoomKeyVerificationMac),
+            KeyVerificationReady => from_value(value, RoomEvent::InRoomKeyVerificationReady),
+            KeyVerificationStart => from_value(value, RoomEvent::InRoomKeyVerificationStart),
             RoomEncrypted => from_value(value, RoomEvent::RoomEncrypted),
             RoomMessage => from_value(value, RoomEvent::RoomMessage),
             RoomMessageFeedback => from_value(value, RoomEvent::RoomMessageFeedback),
             RoomRedaction => from_value(value, RoomEvent::RoomRedaction),
             Sticker => from_value(value, RoomEvent::Sticker),
             Custom(_event_type_name) => from_value(value, RoomEvent::CustomRoom),
-            Direct
+            Call
+            | CallMember
+            | CrossSigningMaster
+            | CrossSigningSelfSigning
+            | CrossSigningUserSigning
+            | Direct
             | Dummy
             | ForwardedRoomKey
             | FullyRead
             | IgnoredUserList
-            | KeyVerificationAccept
-            | KeyVerificationCancel
-            | KeyVerificationKey
-            | KeyVerificationMac
             | KeyVerificationRequest
-            | KeyVerificationStart
             | Presence
             | PushRules
             | Receipt
@@ -240,6 +353,7 @@ impl<'de> Deserialize<'de> for RoomEvent {
             | RoomJoinRules
             | RoomKey
             | RoomKeyRequest
+            | RoomKeyWithheld
             | RoomMember
             | RoomName
             | RoomPinnedEvents
@@ -248,6 +362,11 @@ impl<'de> Deserialize<'de> for RoomEvent {
             | RoomThirdPartyInvite
             | RoomTombstone
             | RoomTopic
+            | SpaceChild
+            | SpaceParent
+            | SecretRequest
+            | SecretSend
+            | SecretStorageDefaultKey
             | Tag
             | Typing => Err(D::Error::custom("invalid event type")),
         }
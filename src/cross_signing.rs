@@ -0,0 +1,184 @@
+//! Modules for events in the *m.cross_signing* namespace.
+//!
+//! This module also contains the public cross-signing keys, as uploaded to and downloaded from the
+//! homeserver's key endpoints.
+
+use std::collections::BTreeMap;
+
+use ruma_identifiers::UserId;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "cross-signing")]
+mod verify;
+
+pub mod master;
+pub mod self_signing;
+pub mod user_signing;
+
+#[cfg(feature = "cross-signing")]
+pub use self::verify::{verify_device_keys, SignatureError};
+
+#[cfg(feature = "cross-signing")]
+pub(crate) use self::verify::verify_json;
+
+/// A public cross-signing key.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CrossSigningKey {
+    /// The ID of the user the key belongs to.
+    pub user_id: UserId,
+
+    /// What the key is used for.
+    pub usage: Vec<KeyUsage>,
+
+    /// The public key, keyed by its ID, e.g. `ed25519:<unpadded base64 public key>`.
+    ///
+    /// This must contain exactly one key.
+    pub keys: BTreeMap<String, String>,
+
+    /// Signatures of the key, keyed by the ID of the signing user and then by the ID of the
+    /// signing key.
+    ///
+    /// The master key is signed by the user's devices, the self-signing and user-signing keys are
+    /// signed by the master key.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub signatures: BTreeMap<UserId, BTreeMap<String, String>>,
+}
+
+impl CrossSigningKey {
+    /// Whether the key has the given usage.
+    pub fn has_usage(&self, usage: KeyUsage) -> bool {
+        self.usage.contains(&usage)
+    }
+
+    /// The ID and public part of the key's Ed25519 key.
+    ///
+    /// Returns `None` unless `keys` contains exactly one Ed25519 key whose ID is
+    /// `ed25519:<public key>`.
+    pub fn ed25519_key(&self) -> Option<(&str, &str)> {
+        if self.keys.len() != 1 {
+            return None;
+        }
+
+        let (key_id, key) = self.keys.iter().next()?;
+        if *key_id != format!("ed25519:{}", key) {
+            return None;
+        }
+
+        Some((key_id.as_str(), key.as_str()))
+    }
+}
+
+/// What a cross-signing key is used for.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum KeyUsage {
+    /// The master key, which signs the user's other cross-signing keys.
+    #[serde(rename = "master")]
+    Master,
+
+    /// The self-signing key, which signs the user's own devices.
+    #[serde(rename = "self_signing")]
+    SelfSigning,
+
+    /// The user-signing key, which signs other users' master keys.
+    #[serde(rename = "user_signing")]
+    UserSigning,
+}
+
+impl_enum! {
+    KeyUsage {
+        Master => "master",
+        SelfSigning => "self_signing",
+        UserSigning => "user_signing",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use maplit::btreemap;
+    use ruma_identifiers::UserId;
+    use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
+
+    use super::{CrossSigningKey, KeyUsage};
+
+    #[test]
+    fn serde_cross_signing_key() {
+        let user_id = UserId::try_from("@alice:example.org").unwrap();
+        let key = CrossSigningKey {
+            user_id: user_id.clone(),
+            usage: vec![KeyUsage::SelfSigning],
+            keys: btreemap! {
+                "ed25519:base64+self+signing+public+key".to_string() =>
+                    "base64+self+signing+public+key".to_string(),
+            },
+            signatures: btreemap! {
+                user_id => btreemap! {
+                    "ed25519:base64+master+public+key".to_string() =>
+                        "signature+of+self+signing+key".to_string(),
+                },
+            },
+        };
+        let json_data = json!({
+            "user_id": "@alice:example.org",
+            "usage": ["self_signing"],
+            "keys": {
+                "ed25519:base64+self+signing+public+key": "base64+self+signing+public+key"
+            },
+            "signatures": {
+                "@alice:example.org": {
+                    "ed25519:base64+master+public+key": "signature+of+self+signing+key"
+                }
+            }
+        });
+
+        assert_eq!(to_json_value(&key).unwrap(), json_data);
+        assert_eq!(from_json_value::<CrossSigningKey>(json_data).unwrap(), key);
+        assert!(key.has_usage(KeyUsage::SelfSigning));
+        assert!(!key.has_usage(KeyUsage::Master));
+        assert_eq!(
+            key.ed25519_key(),
+            Some((
+                "ed25519:base64+self+signing+public+key",
+                "base64+self+signing+public+key"
+            ))
+        );
+    }
+
+    #[test]
+    fn master_key_without_signatures() {
+        let json_data = json!({
+            "user_id": "@alice:example.org",
+            "usage": ["master"],
+            "keys": {
+                "ed25519:base64+master+public+key": "base64+master+public+key"
+            }
+        });
+
+        let key = from_json_value::<CrossSigningKey>(json_data.clone()).unwrap();
+        assert!(key.signatures.is_empty());
+        assert_eq!(to_json_value(&key).unwrap(), json_data);
+    }
+
+    #[test]
+    fn ed25519_key_must_be_unique_and_match_its_id() {
+        let mut key = CrossSigningKey {
+            user_id: UserId::try_from("@alice:example.org").unwrap(),
+            usage: vec![KeyUsage::Master],
+            keys: btreemap! {
+                "ed25519:base64+master+public+key".to_string() =>
+                    "base64+other+public+key".to_string(),
+            },
+            signatures: btreemap! {},
+        };
+        assert_eq!(key.ed25519_key(), None);
+
+        key.keys = btreemap! {
+            "ed25519:base64+master+public+key".to_string() =>
+                "base64+master+public+key".to_string(),
+            "ed25519:base64+other+public+key".to_string() =>
+                "base64+other+public+key".to_string(),
+        };
+        assert_eq!(key.ed25519_key(), None);
+    }
+}
@@ -0,0 +1,19 @@
+//! Types for the *m.cross_signing.self_signing* event.
+
+use ruma_events_macros::ruma_event;
+
+use crate::secret_storage::SecretEventContent;
+
+ruma_event! {
+    /// The private part of the user's self-signing key, encrypted with secret storage.
+    ///
+    /// This is stored in the user's account data.
+    SelfSigningEvent {
+        kind: Event,
+        event_type: "m.cross_signing.self_signing",
+        content_type_alias: {
+            /// The payload for `SelfSigningEvent`.
+            SecretEventContent
+        },
+    }
+}
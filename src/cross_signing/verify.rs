@@ -0,0 +1,273 @@
+//! Verification of Ed25519 signatures made with cross-signing keys.
+
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use ed25519_dalek::{Signature, VerifyingKey};
+use ruma_identifiers::UserId;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+use super::{CrossSigningKey, KeyUsage};
+use crate::util::{decode_base64, encode_base64, to_canonical_json_string};
+
+/// An error encountered while verifying a signature made with a cross-signing key.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SignatureError {
+    /// A key doesn't have the usage required by its position in the chain.
+    WrongUsage,
+
+    /// The keys or the signed object don't all belong to the same user.
+    UserMismatch,
+
+    /// A cross-signing key doesn't contain an Ed25519 key, or it is not a valid Ed25519 public key.
+    InvalidKey,
+
+    /// The signed object doesn't contain a signature by the expected key.
+    MissingSignature,
+
+    /// The signed object is not a JSON object or doesn't have the expected fields.
+    InvalidObject,
+
+    /// The signature is not a valid Ed25519 signature of the object.
+    InvalidSignature,
+}
+
+impl Display for SignatureError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureError::WrongUsage => write!(f, "cross-signing key has the wrong usage"),
+            SignatureError::UserMismatch => write!(f, "keys belong to different users"),
+            SignatureError::InvalidKey => write!(f, "invalid Ed25519 public key"),
+            SignatureError::MissingSignature => write!(f, "missing signature"),
+            SignatureError::InvalidObject => write!(f, "signed object is not a JSON object"),
+            SignatureError::InvalidSignature => write!(f, "invalid signature"),
+        }
+    }
+}
+
+impl Error for SignatureError {}
+
+impl CrossSigningKey {
+    /// Creates an unsigned cross-signing key from the raw bytes of its Ed25519 public key.
+    pub fn new(user_id: UserId, usage: KeyUsage, public_key: &[u8; 32]) -> Self {
+        let public_key = encode_base64(public_key);
+
+        Self {
+            user_id,
+            usage: vec![usage],
+            keys: vec![(format!("ed25519:{}", public_key), public_key)]
+                .into_iter()
+                .collect(),
+            signatures: BTreeMap::new(),
+        }
+    }
+
+    /// Verifies that the given JSON object, e.g. another cross-signing key or a device's keys, was
+    /// signed by this key.
+    ///
+    /// The object must be the JSON as received, since re-serializing a deserialized type would
+    /// drop any fields the type doesn't know about. The signature is looked up under the ID of
+    /// this key's user and the ID of this key. As described in the specification, the
+    /// `signatures` and `unsigned` fields of the object are not signed.
+    pub fn verify_signature(&self, object: &JsonValue) -> Result<(), SignatureError> {
+        let (key_id, public_key) = self.ed25519_key().ok_or(SignatureError::InvalidKey)?;
+
+        verify_json(&self.user_id, key_id, public_key, object)
+    }
+}
+
+/// Verifies that the given JSON object was signed by `user_id` with the Ed25519 key with the given
+/// ID and unpadded base64 public key.
+///
+/// The `signatures` and `unsigned` fields of the object are not signed.
+pub(crate) fn verify_json(
+    user_id: &UserId,
+    key_id: &str,
+    public_key: &str,
+    object: &JsonValue,
+) -> Result<(), SignatureError> {
+    let public_key = decode_base64(public_key).map_err(|_| SignatureError::InvalidKey)?;
+    let public_key =
+        <[u8; 32]>::try_from(public_key.as_slice()).map_err(|_| SignatureError::InvalidKey)?;
+    let public_key =
+        VerifyingKey::from_bytes(&public_key).map_err(|_| SignatureError::InvalidKey)?;
+
+    let mut object = match object {
+        JsonValue::Object(object) => object.clone(),
+        _ => return Err(SignatureError::InvalidObject),
+    };
+    let signatures = object.remove("signatures");
+    object.remove("unsigned");
+
+    let signature = signatures
+        .as_ref()
+        .and_then(|signatures| signatures.get(user_id.to_string()))
+        .and_then(|signatures| signatures.get(key_id))
+        .and_then(JsonValue::as_str)
+        .ok_or(SignatureError::MissingSignature)?;
+    let signature = decode_base64(signature).map_err(|_| SignatureError::InvalidSignature)?;
+    let signature =
+        Signature::from_slice(&signature).map_err(|_| SignatureError::InvalidSignature)?;
+
+    let canonical_json =
+        to_canonical_json_string(&object).map_err(|_| SignatureError::InvalidObject)?;
+    public_key
+        .verify_strict(canonical_json.as_bytes(), &signature)
+        .map_err(|_| SignatureError::InvalidSignature)
+}
+
+/// Verifies a device's signature chain up to its user's master cross-signing key.
+///
+/// This checks that the self-signing key is signed by the master key, and that the device's keys
+/// are signed by the self-signing key. Both the self-signing key and the device's keys must be the
+/// JSON as returned by the homeserver's key query endpoint. Whether the master key itself is
+/// trusted, e.g. because it was verified with `key::verification`, is up to the caller.
+pub fn verify_device_keys(
+    master_key: &CrossSigningKey,
+    self_signing_key_json: &JsonValue,
+    device_keys: &JsonValue,
+) -> Result<(), SignatureError> {
+    let self_signing_key = CrossSigningKey::deserialize(self_signing_key_json)
+        .map_err(|_| SignatureError::InvalidObject)?;
+
+    if !master_key.has_usage(KeyUsage::Master) || !self_signing_key.has_usage(KeyUsage::SelfSigning)
+    {
+        return Err(SignatureError::WrongUsage);
+    }
+
+    if master_key.user_id != self_signing_key.user_id {
+        return Err(SignatureError::UserMismatch);
+    }
+
+    let device_user_id = device_keys
+        .get("user_id")
+        .and_then(JsonValue::as_str)
+        .and_then(|user_id| UserId::try_from(user_id).ok())
+        .ok_or(SignatureError::InvalidObject)?;
+    if device_user_id != master_key.user_id {
+        return Err(SignatureError::UserMismatch);
+    }
+
+    master_key.verify_signature(self_signing_key_json)?;
+    self_signing_key.verify_signature(device_keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ed25519_dalek::{Signer, SigningKey};
+    use ruma_identifiers::UserId;
+    use serde_json::{json, Value as JsonValue};
+
+    use super::{verify_device_keys, SignatureError};
+    use crate::{
+        cross_signing::{CrossSigningKey, KeyUsage},
+        util::{encode_base64, to_canonical_json_string},
+    };
+
+    fn cross_signing_key(signing_key: &SigningKey, usage: KeyUsage) -> CrossSigningKey {
+        CrossSigningKey::new(
+            UserId::try_from("@alice:example.org").unwrap(),
+            usage,
+            signing_key.verifying_key().as_bytes(),
+        )
+    }
+
+    fn sign(object: &mut JsonValue, signing_key: &SigningKey) {
+        let signature = signing_key.sign(to_canonical_json_string(object).unwrap().as_bytes());
+        let key_id = format!(
+            "ed25519:{}",
+            encode_base64(signing_key.verifying_key().as_bytes())
+        );
+
+        object["signatures"] = json!({ "@alice:example.org": {} });
+        object["signatures"]["@alice:example.org"][key_id] =
+            json!(encode_base64(signature.to_bytes()));
+    }
+
+    fn chain() -> (CrossSigningKey, JsonValue, JsonValue) {
+        let master = SigningKey::from_bytes(&[1; 32]);
+        let self_signing = SigningKey::from_bytes(&[2; 32]);
+
+        let master_key = cross_signing_key(&master, KeyUsage::Master);
+        let mut self_signing_key =
+            serde_json::to_value(cross_signing_key(&self_signing, KeyUsage::SelfSigning)).unwrap();
+        sign(&mut self_signing_key, &master);
+
+        let mut device_keys = json!({
+            "user_id": "@alice:example.org",
+            "device_id": "JLAFKJWSCS",
+            "algorithms": ["m.olm.v1.curve25519-aes-sha2", "m.megolm.v1.aes-sha2"],
+            "keys": {
+                "curve25519:JLAFKJWSCS": "3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI",
+                "ed25519:JLAFKJWSCS": "lEuiRJBit0IG6nUf5pUzWTUEsRVVe/HJkoKuEww9ULI"
+            }
+        });
+        sign(&mut device_keys, &self_signing);
+        device_keys["unsigned"] = json!({ "device_display_name": "Alice's phone" });
+
+        (master_key, self_signing_key, device_keys)
+    }
+
+    #[test]
+    fn valid_chain() {
+        let (master_key, self_signing_key, device_keys) = chain();
+
+        assert!(verify_device_keys(&master_key, &self_signing_key, &device_keys).is_ok());
+    }
+
+    #[test]
+    fn unknown_fields_are_signed() {
+        let (master_key, self_signing_key, mut device_keys) = chain();
+        let device_keys_object = device_keys.as_object_mut().unwrap();
+        device_keys_object.remove("signatures");
+        device_keys_object.remove("unsigned");
+        device_keys_object.insert("org.example.field".to_string(), json!(true));
+        sign(&mut device_keys, &SigningKey::from_bytes(&[2; 32]));
+
+        assert!(verify_device_keys(&master_key, &self_signing_key, &device_keys).is_ok());
+    }
+
+    #[test]
+    fn tampered_device_keys() {
+        let (master_key, self_signing_key, mut device_keys) = chain();
+        device_keys["device_id"] = json!("EVILDEVICE");
+
+        assert!(matches!(
+            verify_device_keys(&master_key, &self_signing_key, &device_keys),
+            Err(SignatureError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn self_signing_key_not_signed_by_master_key() {
+        let (_, self_signing_key, device_keys) = chain();
+        let other_master_key =
+            cross_signing_key(&SigningKey::from_bytes(&[3; 32]), KeyUsage::Master);
+
+        assert!(matches!(
+            verify_device_keys(&other_master_key, &self_signing_key, &device_keys),
+            Err(SignatureError::MissingSignature)
+        ));
+    }
+
+    #[test]
+    fn wrong_usage() {
+        let (master_key, self_signing_key, device_keys) = chain();
+
+        let master_key = serde_json::to_value(master_key).unwrap();
+        let self_signing_key = serde_json::from_value(self_signing_key).unwrap();
+
+        assert!(matches!(
+            verify_device_keys(&self_signing_key, &master_key, &device_keys),
+            Err(SignatureError::WrongUsage)
+        ));
+    }
+}
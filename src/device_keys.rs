@@ -0,0 +1,193 @@
+//! Types for the identity and one-time keys of devices, as uploaded to and downloaded from the
+//! homeserver's key endpoints.
+
+use std::collections::BTreeMap;
+
+use ruma_identifiers::{DeviceId, UserId};
+use serde::{Deserialize, Serialize};
+
+use crate::Algorithm;
+
+#[cfg(feature = "cross-signing")]
+mod verify;
+
+/// The identity keys of a device.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct DeviceKeys {
+    /// The ID of the user the device belongs to.
+    pub user_id: UserId,
+
+    /// The ID of the device.
+    pub device_id: DeviceId,
+
+    /// The encryption algorithms supported by the device.
+    pub algorithms: Vec<Algorithm>,
+
+    /// The public identity keys of the device, keyed by `<algorithm>:<device ID>`.
+    pub keys: BTreeMap<String, String>,
+
+    /// Signatures of the device keys, keyed by the ID of the signing user and then by the ID of
+    /// the signing key.
+    ///
+    /// The device keys are signed by the device's own Ed25519 key, and by the user's
+    /// self-signing key if the device was cross-signed.
+    pub signatures: BTreeMap<UserId, BTreeMap<String, String>>,
+
+    /// Additional data added to the device keys by the homeserver, which is not signed.
+    #[serde(default, skip_serializing_if = "ruma_serde::is_default")]
+    pub unsigned: UnsignedDeviceInfo,
+}
+
+impl DeviceKeys {
+    /// The unpadded base64 Ed25519 fingerprint key of the device, if it has one.
+    pub fn ed25519_key(&self) -> Option<&str> {
+        self.key("ed25519")
+    }
+
+    /// The unpadded base64 Curve25519 identity key of the device, if it has one.
+    pub fn curve25519_key(&self) -> Option<&str> {
+        self.key("curve25519")
+    }
+
+    fn key(&self, algorithm: &str) -> Option<&str> {
+        self.keys
+            .get(&format!("{}:{}", algorithm, self.device_id))
+            .map(String::as_str)
+    }
+}
+
+/// Additional data added to device keys by the homeserver.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct UnsignedDeviceInfo {
+    /// The display name which the user set on the device.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_display_name: Option<String>,
+}
+
+/// A one-time or fallback key of a device.
+///
+/// One-time keys are keyed by `<algorithm>:<key ID>`. For the *signed_curve25519* algorithm, the
+/// key is a `SignedKey`; for others, it is the unpadded base64 public key.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum OneTimeKey {
+    /// A key signed by the device's Ed25519 key.
+    SignedKey(SignedKey),
+
+    /// An unsigned key, encoded as unpadded base64.
+    Key(String),
+}
+
+/// A one-time or fallback key signed by the device's Ed25519 key.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct SignedKey {
+    /// The unpadded base64 Curve25519 public key.
+    pub key: String,
+
+    /// Signatures of the key, keyed by the ID of the user and then by the ID of the device's
+    /// Ed25519 key, i.e. `ed25519:<device ID>`.
+    pub signatures: BTreeMap<UserId, BTreeMap<String, String>>,
+
+    /// Whether this is a fallback key, used once all one-time keys are claimed.
+    #[serde(default, skip_serializing_if = "ruma_serde::is_default")]
+    pub fallback: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use maplit::btreemap;
+    use ruma_identifiers::UserId;
+    use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
+
+    use super::{DeviceKeys, OneTimeKey, SignedKey, UnsignedDeviceInfo};
+    use crate::Algorithm;
+
+    #[test]
+    fn device_keys_serde() {
+        let device_keys = DeviceKeys {
+            user_id: UserId::try_from("@alice:example.org").unwrap(),
+            device_id: "JLAFKJWSCS".to_string(),
+            algorithms: vec![
+                Algorithm::OlmV1Curve25519AesSha2,
+                Algorithm::MegolmV1AesSha2,
+            ],
+            keys: btreemap! {
+                "curve25519:JLAFKJWSCS".to_string() =>
+                    "3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI".to_string(),
+                "ed25519:JLAFKJWSCS".to_string() =>
+                    "lEuiRJBit0IG6nUf5pUzWTUEsRVVe/HJkoKuEww9ULI".to_string(),
+            },
+            signatures: btreemap! {
+                UserId::try_from("@alice:example.org").unwrap() => btreemap! {
+                    "ed25519:JLAFKJWSCS".to_string() =>
+                        "dSO80A01XiigH3uBiDVx/EjzaoycHcjq9lfQX0uWsqxl2giMIiSPR8a4d291W1ihKJL/a+myXS367WT6NAIcBA".to_string(),
+                },
+            },
+            unsigned: UnsignedDeviceInfo {
+                device_display_name: Some("Alice's mobile phone".to_string()),
+            },
+        };
+        let json_data = json!({
+            "user_id": "@alice:example.org",
+            "device_id": "JLAFKJWSCS",
+            "algorithms": ["m.olm.v1.curve25519-aes-sha2", "m.megolm.v1.aes-sha2"],
+            "keys": {
+                "curve25519:JLAFKJWSCS": "3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI",
+                "ed25519:JLAFKJWSCS": "lEuiRJBit0IG6nUf5pUzWTUEsRVVe/HJkoKuEww9ULI"
+            },
+            "signatures": {
+                "@alice:example.org": {
+                    "ed25519:JLAFKJWSCS": "dSO80A01XiigH3uBiDVx/EjzaoycHcjq9lfQX0uWsqxl2giMIiSPR8a4d291W1ihKJL/a+myXS367WT6NAIcBA"
+                }
+            },
+            "unsigned": {
+                "device_display_name": "Alice's mobile phone"
+            }
+        });
+
+        assert_eq!(to_json_value(&device_keys).unwrap(), json_data);
+        assert_eq!(
+            from_json_value::<DeviceKeys>(json_data).unwrap(),
+            device_keys
+        );
+        assert_eq!(
+            device_keys.ed25519_key(),
+            Some("lEuiRJBit0IG6nUf5pUzWTUEsRVVe/HJkoKuEww9ULI")
+        );
+        assert_eq!(
+            device_keys.curve25519_key(),
+            Some("3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI")
+        );
+    }
+
+    #[test]
+    fn one_time_keys_serde() {
+        let one_time_keys = from_json_value::<std::collections::BTreeMap<String, OneTimeKey>>(json!({
+            "curve25519:AAAAAQ": "/qyvZvwjiTxGdGU0RCguDCLeR+nmsb3FfNG3/Ve4vU8",
+            "signed_curve25519:AAAAHg": {
+                "key": "zKbLg+NrIjpnagy+pIY6uPL4ZwEG2v+8F9lmgsnlZzs",
+                "signatures": {
+                    "@alice:example.org": {
+                        "ed25519:JLAFKJWSCS": "FLWxXqGbwrb8SM3Y795eB6OA8bwBcoMZFXBqnTn58AYWZSqiD45tlBVcDa2L7RwdKXebW/VzDlnfVJ+9jok1Bw"
+                    }
+                },
+                "fallback": true
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            one_time_keys["curve25519:AAAAAQ"],
+            OneTimeKey::Key("/qyvZvwjiTxGdGU0RCguDCLeR+nmsb3FfNG3/Ve4vU8".to_string())
+        );
+        match &one_time_keys["signed_curve25519:AAAAHg"] {
+            OneTimeKey::SignedKey(SignedKey { key, fallback, .. }) => {
+                assert_eq!(key, "zKbLg+NrIjpnagy+pIY6uPL4ZwEG2v+8F9lmgsnlZzs");
+                assert!(fallback);
+            }
+            _ => panic!("Wrong one-time key type, expected a signed key"),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Verification of the signatures of device and one-time keys.
+
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+
+use super::{DeviceKeys, SignedKey};
+use crate::cross_signing::{verify_json, SignatureError};
+
+impl DeviceKeys {
+    /// Verifies that the given device keys were signed by the device's own Ed25519 key.
+    ///
+    /// The device keys must be the JSON as received, since re-serializing a `DeviceKeys` would drop
+    /// any fields it doesn't know about. Use `cross_signing::verify_device_keys` to check the
+    /// signature of the user's self-signing key.
+    pub fn verify_self_signature(json: &JsonValue) -> Result<(), SignatureError> {
+        let device_keys =
+            DeviceKeys::deserialize(json).map_err(|_| SignatureError::InvalidObject)?;
+        let public_key = device_keys
+            .ed25519_key()
+            .ok_or(SignatureError::InvalidKey)?;
+
+        verify_json(
+            &device_keys.user_id,
+            &format!("ed25519:{}", device_keys.device_id),
+            public_key,
+            json,
+        )
+    }
+}
+
+impl SignedKey {
+    /// Verifies that the given signed key was signed by the Ed25519 key of the device with the
+    /// given keys.
+    ///
+    /// The signed key must be the JSON as received, since re-serializing a `SignedKey` would drop
+    /// any fields it doesn't know about, as well as an explicit `"fallback": false`.
+    pub fn verify(json: &JsonValue, device_keys: &DeviceKeys) -> Result<(), SignatureError> {
+        let public_key = device_keys
+            .ed25519_key()
+            .ok_or(SignatureError::InvalidKey)?;
+
+        verify_json(
+            &device_keys.user_id,
+            &format!("ed25519:{}", device_keys.device_id),
+            public_key,
+            json,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::{Signer, SigningKey};
+    use serde_json::{from_value as from_json_value, json, Value as JsonValue};
+
+    use crate::{
+        cross_signing::SignatureError,
+        device_keys::{DeviceKeys, SignedKey},
+        util::{encode_base64, to_canonical_json_string},
+    };
+
+    fn sign(object: &mut JsonValue, signing_key: &SigningKey) {
+        let signature = signing_key.sign(to_canonical_json_string(object).unwrap().as_bytes());
+
+        object["signatures"] = json!({
+            "@alice:example.org": {
+                "ed25519:JLAFKJWSCS": encode_base64(signature.to_bytes())
+            }
+        });
+    }
+
+    fn device_keys(signing_key: &SigningKey) -> JsonValue {
+        let mut device_keys = json!({
+            "user_id": "@alice:example.org",
+            "device_id": "JLAFKJWSCS",
+            "algorithms": ["m.olm.v1.curve25519-aes-sha2"],
+            "keys": {
+                "curve25519:JLAFKJWSCS": "3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI",
+                "ed25519:JLAFKJWSCS": encode_base64(signing_key.verifying_key().as_bytes())
+            },
+            "org.example.field": true
+        });
+        sign(&mut device_keys, signing_key);
+        device_keys["unsigned"] = json!({ "device_display_name": "Alice's phone" });
+
+        device_keys
+    }
+
+    #[test]
+    fn device_keys_self_signature() {
+        let signing_key = SigningKey::from_bytes(&[1; 32]);
+        let mut device_keys = device_keys(&signing_key);
+
+        assert!(DeviceKeys::verify_self_signature(&device_keys).is_ok());
+
+        device_keys["algorithms"] = json!(["m.olm.v1.curve25519-aes-sha2", "org.example.weak"]);
+        assert!(matches!(
+            DeviceKeys::verify_self_signature(&device_keys),
+            Err(SignatureError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn signed_one_time_key() {
+        let signing_key = SigningKey::from_bytes(&[1; 32]);
+        let device_keys: DeviceKeys = from_json_value(device_keys(&signing_key)).unwrap();
+
+        let mut one_time_key = json!({
+            "key": "zKbLg+NrIjpnagy+pIY6uPL4ZwEG2v+8F9lmgsnlZzs",
+            "fallback": false
+        });
+        sign(&mut one_time_key, &signing_key);
+        assert!(SignedKey::verify(&one_time_key, &device_keys).is_ok());
+
+        let other_device_keys: DeviceKeys =
+            from_json_value(self::device_keys(&SigningKey::from_bytes(&[2; 32]))).unwrap();
+        assert!(matches!(
+            SignedKey::verify(&one_time_key, &other_device_keys),
+            Err(SignatureError::InvalidSignature)
+        ));
+    }
+}
@@ -52,7 +52,7 @@ mod tests {
     #[test]
     fn deserialization() {
         let alice = UserId::new("ruma.io").unwrap();
-        let rooms = vec![
+        let rooms = [
             RoomId::new("ruma.io").unwrap(),
             RoomId::new("ruma.io").unwrap(),
         ];
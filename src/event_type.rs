@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 #[non_exhaustive]
 #[serde(from = "String", into = "String")]
 pub enum EventType {
+    /// m.call
+    Call,
+
     /// m.call.answer
     CallAnswer,
 
@@ -19,6 +22,27 @@ pub enum EventType {
     /// m.call.invite
     CallInvite,
 
+    /// m.call.member
+    CallMember,
+
+    /// m.call.negotiate
+    CallNegotiate,
+
+    /// m.call.reject
+    CallReject,
+
+    /// m.call.select_answer
+    CallSelectAnswer,
+
+    /// m.cross_signing.master
+    CrossSigningMaster,
+
+    /// m.cross_signing.self_signing
+    CrossSigningSelfSigning,
+
+    /// m.cross_signing.user_signing
+    CrossSigningUserSigning,
+
     /// m.direct
     Direct,
 
@@ -37,12 +61,18 @@ pub enum EventType {
     /// m.key.verification.cancel
     KeyVerificationCancel,
 
+    /// m.key.verification.done
+    KeyVerificationDone,
+
     /// m.key.verification.key
     KeyVerificationKey,
 
     /// m.key.verification.mac
     KeyVerificationMac,
 
+    /// m.key.verification.ready
+    KeyVerificationReady,
+
     /// m.key.verification.request
     KeyVerificationRequest,
 
@@ -127,6 +157,24 @@ pub enum EventType {
     /// m.room_key_request
     RoomKeyRequest,
 
+    /// m.room_key.withheld
+    RoomKeyWithheld,
+
+    /// m.secret.request
+    SecretRequest,
+
+    /// m.secret.send
+    SecretSend,
+
+    /// m.secret_storage.default_key
+    SecretStorageDefaultKey,
+
+    /// m.space.child
+    SpaceChild,
+
+    /// m.space.parent
+    SpaceParent,
+
     /// m.sticker
     Sticker,
 
@@ -143,18 +191,28 @@ pub enum EventType {
 impl Display for EventType {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         let event_type_str = match *self {
+            EventType::Call => "m.call",
             EventType::CallAnswer => "m.call.answer",
             EventType::CallCandidates => "m.call.candidates",
             EventType::CallHangup => "m.call.hangup",
             EventType::CallInvite => "m.call.invite",
+            EventType::CallMember => "m.call.member",
+            EventType::CallNegotiate => "m.call.negotiate",
+            EventType::CallReject => "m.call.reject",
+            EventType::CallSelectAnswer => "m.call.select_answer",
+            EventType::CrossSigningMaster => "m.cross_signing.master",
+            EventType::CrossSigningSelfSigning => "m.cross_signing.self_signing",
+            EventType::CrossSigningUserSigning => "m.cross_signing.user_signing",
             EventType::Direct => "m.direct",
             EventType::Dummy => "m.dummy",
             EventType::ForwardedRoomKey => "m.forwarded_room_key",
             EventType::FullyRead => "m.fully_read",
             EventType::KeyVerificationAccept => "m.key.verification.accept",
             EventType::KeyVerificationCancel => "m.key.verification.cancel",
+            EventType::KeyVerificationDone => "m.key.verification.done",
             EventType::KeyVerificationKey => "m.key.verification.key",
             EventType::KeyVerificationMac => "m.key.verification.mac",
+            EventType::KeyVerificationReady => "m.key.verification.ready",
             EventType::KeyVerificationRequest => "m.key.verification.request",
             EventType::KeyVerificationStart => "m.key.verification.start",
             EventType::IgnoredUserList => "m.ignored_user_list",
@@ -183,6 +241,12 @@ impl Display for EventType {
             EventType::RoomTopic => "m.room.topic",
             EventType::RoomKey => "m.room_key",
             EventType::RoomKeyRequest => "m.room_key_request",
+            EventType::RoomKeyWithheld => "m.room_key.withheld",
+            EventType::SecretRequest => "m.secret.request",
+            EventType::SecretSend => "m.secret.send",
+            EventType::SecretStorageDefaultKey => "m.secret_storage.default_key",
+            EventType::SpaceChild => "m.space.child",
+            EventType::SpaceParent => "m.space.parent",
             EventType::Sticker => "m.sticker",
             EventType::Tag => "m.tag",
             EventType::Typing => "m.typing",
@@ -199,18 +263,28 @@ where
 {
     fn from(s: T) -> EventType {
         match s.as_ref() {
+            "m.call" => EventType::Call,
             "m.call.answer" => EventType::CallAnswer,
             "m.call.candidates" => EventType::CallCandidates,
             "m.call.hangup" => EventType::CallHangup,
             "m.call.invite" => EventType::CallInvite,
+            "m.call.member" => EventType::CallMember,
+            "m.call.negotiate" => EventType::CallNegotiate,
+            "m.call.reject" => EventType::CallReject,
+            "m.call.select_answer" => EventType::CallSelectAnswer,
+            "m.cross_signing.master" => EventType::CrossSigningMaster,
+            "m.cross_signing.self_signing" => EventType::CrossSigningSelfSigning,
+            "m.cross_signing.user_signing" => EventType::CrossSigningUserSigning,
             "m.direct" => EventType::Direct,
             "m.dummy" => EventType::Dummy,
             "m.forwarded_room_key" => EventType::ForwardedRoomKey,
             "m.fully_read" => EventType::FullyRead,
             "m.key.verification.accept" => EventType::KeyVerificationAccept,
             "m.key.verification.cancel" => EventType::KeyVerificationCancel,
+            "m.key.verification.done" => EventType::KeyVerificationDone,
             "m.key.verification.key" => EventType::KeyVerificationKey,
             "m.key.verification.mac" => EventType::KeyVerificationMac,
+            "m.key.verification.ready" => EventType::KeyVerificationReady,
             "m.key.verification.request" => EventType::KeyVerificationRequest,
             "m.key.verification.start" => EventType::KeyVerificationStart,
             "m.ignored_user_list" => EventType::IgnoredUserList,
@@ -239,6 +313,12 @@ where
             "m.room.topic" => EventType::RoomTopic,
             "m.room_key" => EventType::RoomKey,
             "m.room_key_request" => EventType::RoomKeyRequest,
+            "m.room_key.withheld" => EventType::RoomKeyWithheld,
+            "m.secret.request" => EventType::SecretRequest,
+            "m.secret.send" => EventType::SecretSend,
+            "m.secret_storage.default_key" => EventType::SecretStorageDefaultKey,
+            "m.space.child" => EventType::SpaceChild,
+            "m.space.parent" => EventType::SpaceParent,
             "m.sticker" => EventType::Sticker,
             "m.tag" => EventType::Tag,
             "m.typing" => EventType::Typing,
@@ -263,10 +343,27 @@ mod tests {
     #[allow(clippy::cognitive_complexity)]
     #[test]
     fn serialize_and_deserialize_from_display_form() {
+        serde_json_eq(EventType::Call, json!("m.call"));
         serde_json_eq(EventType::CallAnswer, json!("m.call.answer"));
         serde_json_eq(EventType::CallCandidates, json!("m.call.candidates"));
         serde_json_eq(EventType::CallHangup, json!("m.call.hangup"));
         serde_json_eq(EventType::CallInvite, json!("m.call.invite"));
+        serde_json_eq(EventType::CallMember, json!("m.call.member"));
+        serde_json_eq(EventType::CallNegotiate, json!("m.call.negotiate"));
+        serde_json_eq(EventType::CallReject, json!("m.call.reject"));
+        serde_json_eq(EventType::CallSelectAnswer, json!("m.call.select_answer"));
+        serde_json_eq(
+            EventType::CrossSigningMaster,
+            json!("m.cross_signing.master"),
+        );
+        serde_json_eq(
+            EventType::CrossSigningSelfSigning,
+            json!("m.cross_signing.self_signing"),
+        );
+        serde_json_eq(
+            EventType::CrossSigningUserSigning,
+            json!("m.cross_signing.user_signing"),
+        );
         serde_json_eq(EventType::Direct, json!("m.direct"));
         serde_json_eq(EventType::Dummy, json!("m.dummy"));
         serde_json_eq(EventType::ForwardedRoomKey, json!("m.forwarded_room_key"));
@@ -279,6 +376,10 @@ mod tests {
             EventType::KeyVerificationCancel,
             json!("m.key.verification.cancel"),
         );
+        serde_json_eq(
+            EventType::KeyVerificationDone,
+            json!("m.key.verification.done"),
+        );
         serde_json_eq(
             EventType::KeyVerificationKey,
             json!("m.key.verification.key"),
@@ -287,6 +388,10 @@ mod tests {
             EventType::KeyVerificationMac,
             json!("m.key.verification.mac"),
         );
+        serde_json_eq(
+            EventType::KeyVerificationReady,
+            json!("m.key.verification.ready"),
+        );
         serde_json_eq(
             EventType::KeyVerificationRequest,
             json!("m.key.verification.request"),
@@ -333,6 +438,15 @@ mod tests {
         serde_json_eq(EventType::RoomTopic, json!("m.room.topic"));
         serde_json_eq(EventType::RoomKey, json!("m.room_key"));
         serde_json_eq(EventType::RoomKeyRequest, json!("m.room_key_request"));
+        serde_json_eq(EventType::RoomKeyWithheld, json!("m.room_key.withheld"));
+        serde_json_eq(EventType::SecretRequest, json!("m.secret.request"));
+        serde_json_eq(EventType::SecretSend, json!("m.secret.send"));
+        serde_json_eq(
+            EventType::SecretStorageDefaultKey,
+            json!("m.secret_storage.default_key"),
+        );
+        serde_json_eq(EventType::SpaceChild, json!("m.space.child"));
+        serde_json_eq(EventType::SpaceParent, json!("m.space.parent"));
         serde_json_eq(EventType::Sticker, json!("m.sticker"));
         serde_json_eq(EventType::Tag, json!("m.tag"));
         serde_json_eq(EventType::Typing, json!("m.typing"));
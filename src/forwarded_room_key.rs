@@ -1,10 +1,24 @@
 //! Types for the *m.forwarded_room_key* event.
+//!
+//! This module also contains the entries of the megolm session key export format, which mirror
+//! the content of the event.
+
+use std::collections::BTreeMap;
 
 use ruma_events_macros::ruma_event;
 use ruma_identifiers::RoomId;
+use serde::{Deserialize, Serialize};
 
 use super::Algorithm;
 
+#[cfg(feature = "key-export")]
+mod export;
+
+#[cfg(feature = "key-export")]
+pub use self::export::{
+    decrypt_key_export, encrypt_key_export, KeyExportError, MAX_KEY_EXPORT_ROUNDS,
+};
+
 ruma_event! {
     /// This event type is used to forward keys for end-to-end encryption.
     ///
@@ -45,3 +59,117 @@ ruma_event! {
         },
     }
 }
+
+/// A megolm session, as stored in a key export file.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ExportedRoomKey {
+    /// The encryption algorithm the session uses.
+    pub algorithm: Algorithm,
+
+    /// Chain of Curve25519 keys through which this session was forwarded, via
+    /// *m.forwarded_room_key* events.
+    pub forwarding_curve25519_key_chain: Vec<String>,
+
+    /// The room where the session is used.
+    pub room_id: RoomId,
+
+    /// The Curve25519 key of the device which initiated the session originally.
+    pub sender_key: String,
+
+    /// The Ed25519 key of the device which initiated the session originally, keyed by the key
+    /// algorithm, i.e. `ed25519`.
+    pub sender_claimed_keys: BTreeMap<String, String>,
+
+    /// The ID of the session.
+    pub session_id: String,
+
+    /// The key for the session.
+    pub session_key: String,
+}
+
+impl ExportedRoomKey {
+    /// Converts the exported session into the content of an *m.forwarded_room_key* event.
+    ///
+    /// Returns `None` if the exported session doesn't contain the claimed Ed25519 key of the
+    /// device which initiated the session.
+    pub fn to_forwarded_room_key(&self) -> Option<ForwardedRoomKeyEventContent> {
+        Some(ForwardedRoomKeyEventContent {
+            algorithm: self.algorithm.clone(),
+            room_id: self.room_id.clone(),
+            sender_key: self.sender_key.clone(),
+            session_id: self.session_id.clone(),
+            session_key: self.session_key.clone(),
+            sender_claimed_ed25519_key: self.sender_claimed_keys.get("ed25519")?.clone(),
+            forwarding_curve25519_key_chain: self.forwarding_curve25519_key_chain.clone(),
+        })
+    }
+}
+
+impl From<ForwardedRoomKeyEventContent> for ExportedRoomKey {
+    fn from(content: ForwardedRoomKeyEventContent) -> Self {
+        let mut sender_claimed_keys = BTreeMap::new();
+        sender_claimed_keys.insert("ed25519".to_string(), content.sender_claimed_ed25519_key);
+
+        Self {
+            algorithm: content.algorithm,
+            forwarding_curve25519_key_chain: content.forwarding_curve25519_key_chain,
+            room_id: content.room_id,
+            sender_key: content.sender_key,
+            sender_claimed_keys,
+            session_id: content.session_id,
+            session_key: content.session_key,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ruma_identifiers::RoomId;
+    use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
+
+    use super::{ExportedRoomKey, ForwardedRoomKeyEventContent};
+    use crate::Algorithm;
+
+    #[test]
+    fn exported_room_key_roundtrip() {
+        let content = ForwardedRoomKeyEventContent {
+            algorithm: Algorithm::MegolmV1AesSha2,
+            room_id: RoomId::try_from("!Cuyf34gef24t:localhost").unwrap(),
+            sender_key: "RF3s+E7RkTQTGF2d8Deol0FkQvgII2aJDf3/Jp5mxVU".to_string(),
+            session_id: "X3lUlvLELLYxeTx4yOVu6UDpasGEVO0Jbu+QFnm0cKQ".to_string(),
+            session_key: "AgAAAADxKHa9uFxcXzwYoNueL5Xqi69IkD4sni8Llf...".to_string(),
+            sender_claimed_ed25519_key: "aj40p+aw64yPIdsxoog8Jhlu9i+jZPiL7BNtcLyh/UE".to_string(),
+            forwarding_curve25519_key_chain: vec![
+                "hPQNcabIABgGnx3/ACv/jmMmiQHoeFfuLB17tzWp6Hw".to_string()
+            ],
+        };
+        let json_data = json!({
+            "algorithm": "m.megolm.v1.aes-sha2",
+            "forwarding_curve25519_key_chain": [
+                "hPQNcabIABgGnx3/ACv/jmMmiQHoeFfuLB17tzWp6Hw"
+            ],
+            "room_id": "!Cuyf34gef24t:localhost",
+            "sender_key": "RF3s+E7RkTQTGF2d8Deol0FkQvgII2aJDf3/Jp5mxVU",
+            "sender_claimed_keys": {
+                "ed25519": "aj40p+aw64yPIdsxoog8Jhlu9i+jZPiL7BNtcLyh/UE"
+            },
+            "session_id": "X3lUlvLELLYxeTx4yOVu6UDpasGEVO0Jbu+QFnm0cKQ",
+            "session_key": "AgAAAADxKHa9uFxcXzwYoNueL5Xqi69IkD4sni8Llf..."
+        });
+
+        let exported = ExportedRoomKey::from(content);
+        assert_eq!(to_json_value(&exported).unwrap(), json_data);
+        assert_eq!(
+            from_json_value::<ExportedRoomKey>(json_data).unwrap(),
+            exported
+        );
+
+        let content = exported.to_forwarded_room_key().unwrap();
+        assert_eq!(
+            content.sender_claimed_ed25519_key,
+            "aj40p+aw64yPIdsxoog8Jhlu9i+jZPiL7BNtcLyh/UE"
+        );
+    }
+}
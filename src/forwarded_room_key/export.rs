@@ -0,0 +1,303 @@
+//! Encryption and decryption of megolm session key export files.
+
+use std::{
+    convert::TryFrom,
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use aes::Aes256;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use ctr::{
+    cipher::{KeyIvInit, StreamCipher},
+    Ctr128BE,
+};
+use hmac::{Hmac, Mac};
+use pbkdf2::pbkdf2_hmac;
+use rand::{thread_rng, RngCore};
+use sha2::{Sha256, Sha512};
+
+use super::ExportedRoomKey;
+use crate::util::decode_base64;
+
+type Aes256Ctr = Ctr128BE<Aes256>;
+
+/// The first line of a key export file.
+const HEADER: &str = "-----BEGIN MEGOLM SESSION DATA-----";
+
+/// The last line of a key export file.
+const FOOTER: &str = "-----END MEGOLM SESSION DATA-----";
+
+/// The version of the key export format.
+const VERSION: u8 = 0x01;
+
+/// The size of the salt in bytes.
+const SALT_SIZE: usize = 16;
+
+/// The size of an AES-CTR initialization vector in bytes.
+const IV_SIZE: usize = 16;
+
+/// The size of an HMAC-SHA-256 in bytes.
+const MAC_SIZE: usize = 32;
+
+/// The length of the base64 lines between the header and footer.
+const LINE_LENGTH: usize = 96;
+
+/// The maximum number of PBKDF2 rounds accepted when decrypting a key export file.
+///
+/// Clients use a few hundred thousand rounds; the limit keeps an export file from making the
+/// decryption take arbitrarily long.
+pub const MAX_KEY_EXPORT_ROUNDS: u32 = 10_000_000;
+
+/// An error encountered while decrypting a key export file.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum KeyExportError {
+    /// The export is missing the `BEGIN` or `END MEGOLM SESSION DATA` line.
+    InvalidArmor,
+
+    /// The data between the header and the footer is not valid base64 or is too short.
+    InvalidEncoding,
+
+    /// The export uses a version of the format other than 1.
+    UnsupportedVersion,
+
+    /// The export asks for more than `MAX_KEY_EXPORT_ROUNDS` PBKDF2 rounds.
+    TooManyRounds,
+
+    /// The MAC of the export doesn't match, i.e. the passphrase is wrong or the export was
+    /// modified.
+    MacMismatch,
+
+    /// The decrypted data is not a valid list of exported sessions.
+    Json(serde_json::Error),
+}
+
+impl Display for KeyExportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyExportError::InvalidArmor => write!(f, "missing key export header or footer"),
+            KeyExportError::InvalidEncoding => write!(f, "invalid key export encoding"),
+            KeyExportError::UnsupportedVersion => write!(f, "unsupported key export version"),
+            KeyExportError::TooManyRounds => write!(f, "too many key export rounds"),
+            KeyExportError::MacMismatch => write!(f, "wrong passphrase or modified key export"),
+            KeyExportError::Json(error) => write!(f, "invalid exported sessions: {}", error),
+        }
+    }
+}
+
+impl Error for KeyExportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            KeyExportError::Json(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Encrypts the given sessions as a key export file, protected by a passphrase.
+///
+/// `rounds` is the number of PBKDF2 iterations used to derive the encryption key from the
+/// passphrase; the specification recommends at least 100,000. Exports with more than
+/// `MAX_KEY_EXPORT_ROUNDS` rounds can't be decrypted with `decrypt_key_export`.
+pub fn encrypt_key_export(sessions: &[ExportedRoomKey], passphrase: &str, rounds: u32) -> String {
+    let mut salt = [0; SALT_SIZE];
+    let mut iv = [0; IV_SIZE];
+    thread_rng().fill_bytes(&mut salt);
+    thread_rng().fill_bytes(&mut iv);
+    // Clear bit 63 of the counter so that it can't overflow into the nonce.
+    iv[8] &= 0x7f;
+
+    let (aes_key, mac_key) = derive_keys(passphrase, &salt, rounds);
+    let mut ciphertext =
+        serde_json::to_vec(sessions).expect("exported sessions can always be serialized");
+    Aes256Ctr::new(&aes_key.into(), &iv.into()).apply_keystream(&mut ciphertext);
+
+    let mut data = Vec::with_capacity(1 + SALT_SIZE + IV_SIZE + 4 + ciphertext.len() + MAC_SIZE);
+    data.push(VERSION);
+    data.extend_from_slice(&salt);
+    data.extend_from_slice(&iv);
+    data.extend_from_slice(&rounds.to_be_bytes());
+    data.extend_from_slice(&ciphertext);
+    let mac = hmac_sha256(&mac_key, &data).finalize().into_bytes();
+    data.extend_from_slice(&mac);
+
+    let encoded = STANDARD.encode(data);
+    let mut export = String::with_capacity(HEADER.len() + encoded.len() * 2 + FOOTER.len());
+    export.push_str(HEADER);
+    export.push('\n');
+    for line in encoded.as_bytes().chunks(LINE_LENGTH) {
+        export.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        export.push('\n');
+    }
+    export.push_str(FOOTER);
+    export.push('\n');
+
+    export
+}
+
+/// Decrypts a key export file with the passphrase it was protected with.
+///
+/// Exports with more than `MAX_KEY_EXPORT_ROUNDS` rounds are rejected before the key is derived.
+pub fn decrypt_key_export(
+    export: &str,
+    passphrase: &str,
+) -> Result<Vec<ExportedRoomKey>, KeyExportError> {
+    let export = export.trim();
+    if !export.starts_with(HEADER) || !export.ends_with(FOOTER) {
+        return Err(KeyExportError::InvalidArmor);
+    }
+    let encoded: String = export[HEADER.len()..export.len() - FOOTER.len()]
+        .split_whitespace()
+        .collect();
+    let data = decode_base64(encoded).map_err(|_| KeyExportError::InvalidEncoding)?;
+
+    if data.len() < 1 + SALT_SIZE + IV_SIZE + 4 + MAC_SIZE {
+        return Err(KeyExportError::InvalidEncoding);
+    }
+    if data[0] != VERSION {
+        return Err(KeyExportError::UnsupportedVersion);
+    }
+
+    let (data, mac) = data.split_at(data.len() - MAC_SIZE);
+    let salt = &data[1..1 + SALT_SIZE];
+    let iv = <[u8; IV_SIZE]>::try_from(&data[1 + SALT_SIZE..1 + SALT_SIZE + IV_SIZE])
+        .expect("slice has the size of an IV");
+    let rounds_start = 1 + SALT_SIZE + IV_SIZE;
+    let rounds = u32::from_be_bytes(
+        <[u8; 4]>::try_from(&data[rounds_start..rounds_start + 4])
+            .expect("slice has the size of a u32"),
+    );
+    if rounds > MAX_KEY_EXPORT_ROUNDS {
+        return Err(KeyExportError::TooManyRounds);
+    }
+
+    let (aes_key, mac_key) = derive_keys(passphrase, salt, rounds);
+    hmac_sha256(&mac_key, data)
+        .verify_slice(mac)
+        .map_err(|_| KeyExportError::MacMismatch)?;
+
+    let mut plaintext = data[rounds_start + 4..].to_vec();
+    Aes256Ctr::new(&aes_key.into(), &iv.into()).apply_keystream(&mut plaintext);
+
+    serde_json::from_slice(&plaintext).map_err(KeyExportError::Json)
+}
+
+/// Derives the AES and HMAC keys from the passphrase.
+fn derive_keys(passphrase: &str, salt: &[u8], rounds: u32) -> ([u8; 32], [u8; 32]) {
+    let mut keys = [0; 64];
+    pbkdf2_hmac::<Sha512>(passphrase.as_bytes(), salt, rounds, &mut keys);
+
+    let mut aes_key = [0; 32];
+    let mut mac_key = [0; 32];
+    aes_key.copy_from_slice(&keys[..32]);
+    mac_key.copy_from_slice(&keys[32..]);
+
+    (aes_key, mac_key)
+}
+
+fn hmac_sha256(key: &[u8], input: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(input);
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, convert::TryFrom};
+
+    use base64::{engine::general_purpose::STANDARD, Engine as _};
+    use ruma_identifiers::RoomId;
+
+    use super::{
+        decrypt_key_export, encrypt_key_export, KeyExportError, FOOTER, HEADER, IV_SIZE, SALT_SIZE,
+    };
+    use crate::{forwarded_room_key::ExportedRoomKey, util::decode_base64, Algorithm};
+
+    fn sessions() -> Vec<ExportedRoomKey> {
+        let mut sender_claimed_keys = BTreeMap::new();
+        sender_claimed_keys.insert(
+            "ed25519".to_string(),
+            "aj40p+aw64yPIdsxoog8Jhlu9i+jZPiL7BNtcLyh/UE".to_string(),
+        );
+
+        vec![ExportedRoomKey {
+            algorithm: Algorithm::MegolmV1AesSha2,
+            forwarding_curve25519_key_chain: vec![],
+            room_id: RoomId::try_from("!Cuyf34gef24t:localhost").unwrap(),
+            sender_key: "RF3s+E7RkTQTGF2d8Deol0FkQvgII2aJDf3/Jp5mxVU".to_string(),
+            sender_claimed_keys,
+            session_id: "X3lUlvLELLYxeTx4yOVu6UDpasGEVO0Jbu+QFnm0cKQ".to_string(),
+            session_key: "AgAAAADxKHa9uFxcXzwYoNueL5Xqi69IkD4sni8Llf".to_string(),
+        }]
+    }
+
+    #[test]
+    fn export_import_roundtrip() {
+        let export = encrypt_key_export(&sessions(), "passphrase", 10);
+
+        assert!(export.starts_with(HEADER));
+        assert!(export.trim_end().ends_with(FOOTER));
+        assert_eq!(
+            decrypt_key_export(&export, "passphrase").unwrap(),
+            sessions()
+        );
+    }
+
+    #[test]
+    fn wrong_passphrase() {
+        let export = encrypt_key_export(&sessions(), "passphrase", 10);
+
+        assert!(matches!(
+            decrypt_key_export(&export, "wrong passphrase"),
+            Err(KeyExportError::MacMismatch)
+        ));
+    }
+
+    #[test]
+    fn tampered_export() {
+        let export = encrypt_key_export(&sessions(), "passphrase", 10);
+        let mut lines: Vec<String> = export.lines().map(ToOwned::to_owned).collect();
+        // Flip a character in the middle of the ciphertext.
+        let line = &mut lines[1];
+        let flipped = if line.as_bytes()[60] == b'A' {
+            "B"
+        } else {
+            "A"
+        };
+        line.replace_range(60..61, flipped);
+
+        assert!(matches!(
+            decrypt_key_export(&lines.join("\n"), "passphrase"),
+            Err(KeyExportError::MacMismatch)
+        ));
+    }
+
+    #[test]
+    fn too_many_rounds() {
+        let export = encrypt_key_export(&sessions(), "passphrase", 10);
+        let encoded: String = export[HEADER.len()..export.trim_end().len() - FOOTER.len()]
+            .split_whitespace()
+            .collect();
+        let mut data = decode_base64(encoded).unwrap();
+        let rounds_start = 1 + SALT_SIZE + IV_SIZE;
+        data[rounds_start..rounds_start + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        let export = format!("{}\n{}\n{}\n", HEADER, STANDARD.encode(data), FOOTER);
+
+        assert!(matches!(
+            decrypt_key_export(&export, "passphrase"),
+            Err(KeyExportError::TooManyRounds)
+        ));
+    }
+
+    #[test]
+    fn missing_armor() {
+        let export = encrypt_key_export(&sessions(), "passphrase", 10);
+        let without_header = export.replacen(HEADER, "", 1);
+
+        assert!(matches!(
+            decrypt_key_export(&without_header, "passphrase"),
+            Err(KeyExportError::InvalidArmor)
+        ));
+    }
+}
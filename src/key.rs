@@ -1,3 +1,6 @@
 //! Modules for events in the *m.key* namespace.
+//!
+//! This module also contains the types used for server-side backups of room keys.
 
+pub mod backup;
 pub mod verification;
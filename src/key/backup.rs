@@ -0,0 +1,250 @@
+//! Types for server-side backups of room keys.
+//!
+//! Room keys are backed up to the homeserver with the *m.megolm_backup.v1.curve25519-aes-sha2*
+//! algorithm: each session is encrypted to the backup's Curve25519 public key, and the backup
+//! version describes that key.
+
+use std::collections::BTreeMap;
+
+use js_int::UInt;
+use ruma_identifiers::{RoomId, UserId};
+use serde::{Deserialize, Serialize};
+
+use crate::{forwarded_room_key::ForwardedRoomKeyEventContent, Algorithm};
+
+/// The algorithm and authentication data of a backup version.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "algorithm", content = "auth_data")]
+pub enum BackupAlgorithm {
+    /// The *m.megolm_backup.v1.curve25519-aes-sha2* algorithm.
+    #[serde(rename = "m.megolm_backup.v1.curve25519-aes-sha2")]
+    MegolmBackupV1Curve25519AesSha2 {
+        /// The Curve25519 public key used to encrypt the backups, encoded as unpadded base64.
+        public_key: String,
+
+        /// Signatures of the authentication data, keyed by user ID and then by key ID.
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        signatures: BTreeMap<UserId, BTreeMap<String, String>>,
+    },
+}
+
+/// The backup of all sessions of a room.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct RoomKeyBackup {
+    /// The backed up sessions, keyed by session ID.
+    pub sessions: BTreeMap<String, KeyBackupData>,
+}
+
+/// The backup of a single session.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct KeyBackupData {
+    /// The index of the first message in the session that the key can decrypt.
+    pub first_message_index: UInt,
+
+    /// The number of times this key has been forwarded via key-sharing between devices.
+    pub forwarded_count: UInt,
+
+    /// Whether the device backing up the key verified the device that the key is from.
+    pub is_verified: bool,
+
+    /// The encrypted session data.
+    pub session_data: EncryptedSessionData,
+}
+
+/// A session encrypted with the *m.megolm_backup.v1.curve25519-aes-sha2* algorithm.
+///
+/// The plaintext is the JSON encoding of a `BackedUpRoomKey`.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct EncryptedSessionData {
+    /// The ephemeral Curve25519 public key used to derive the encryption key, encoded as unpadded
+    /// base64.
+    pub ephemeral: String,
+
+    /// The encrypted session, encoded as unpadded base64.
+    pub ciphertext: String,
+
+    /// The MAC of the ciphertext, encoded as unpadded base64.
+    pub mac: String,
+}
+
+/// The decrypted `session_data` of a backed up session.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct BackedUpRoomKey {
+    /// The encryption algorithm the session uses.
+    pub algorithm: Algorithm,
+
+    /// The Curve25519 key of the device which initiated the session originally.
+    pub sender_key: String,
+
+    /// The Ed25519 key of the device which initiated the session originally, keyed by the key
+    /// algorithm, i.e. `ed25519`.
+    pub sender_claimed_keys: BTreeMap<String, String>,
+
+    /// Chain of Curve25519 keys through which this session was forwarded, via
+    /// *m.forwarded_room_key* events.
+    pub forwarding_curve25519_key_chain: Vec<String>,
+
+    /// The key for the session.
+    pub session_key: String,
+}
+
+impl BackedUpRoomKey {
+    /// Converts the backed up session into the content of an *m.forwarded_room_key* event.
+    ///
+    /// The room and session IDs are not part of the session data, they are the keys under which
+    /// the session is stored in the backup.
+    ///
+    /// Returns `None` if the session data doesn't contain the claimed Ed25519 key of the device
+    /// which initiated the session.
+    pub fn into_forwarded_room_key(
+        self,
+        room_id: RoomId,
+        session_id: String,
+    ) -> Option<ForwardedRoomKeyEventContent> {
+        let mut sender_claimed_keys = self.sender_claimed_keys;
+
+        Some(ForwardedRoomKeyEventContent {
+            algorithm: self.algorithm,
+            room_id,
+            sender_key: self.sender_key,
+            session_id,
+            session_key: self.session_key,
+            sender_claimed_ed25519_key: sender_claimed_keys.remove("ed25519")?,
+            forwarding_curve25519_key_chain: self.forwarding_curve25519_key_chain,
+        })
+    }
+}
+
+impl From<ForwardedRoomKeyEventContent> for BackedUpRoomKey {
+    fn from(content: ForwardedRoomKeyEventContent) -> Self {
+        let mut sender_claimed_keys = BTreeMap::new();
+        sender_claimed_keys.insert("ed25519".to_string(), content.sender_claimed_ed25519_key);
+
+        Self {
+            algorithm: content.algorithm,
+            sender_key: content.sender_key,
+            sender_claimed_keys,
+            forwarding_curve25519_key_chain: content.forwarding_curve25519_key_chain,
+            session_key: content.session_key,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use js_int::UInt;
+    use maplit::btreemap;
+    use ruma_identifiers::{RoomId, UserId};
+    use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
+
+    use super::{
+        BackedUpRoomKey, BackupAlgorithm, EncryptedSessionData, KeyBackupData, RoomKeyBackup,
+    };
+    use crate::{forwarded_room_key::ForwardedRoomKeyEventContent, Algorithm};
+
+    #[test]
+    fn backup_algorithm_serde() {
+        let algorithm = BackupAlgorithm::MegolmBackupV1Curve25519AesSha2 {
+            public_key: "abcdefg".to_string(),
+            signatures: btreemap! {
+                UserId::try_from("@alice:example.org").unwrap() => btreemap! {
+                    "ed25519:deviceid".to_string() => "signature".to_string(),
+                },
+            },
+        };
+        let json_data = json!({
+            "algorithm": "m.megolm_backup.v1.curve25519-aes-sha2",
+            "auth_data": {
+                "public_key": "abcdefg",
+                "signatures": {
+                    "@alice:example.org": {
+                        "ed25519:deviceid": "signature"
+                    }
+                }
+            }
+        });
+
+        assert_eq!(to_json_value(&algorithm).unwrap(), json_data);
+        assert_eq!(
+            from_json_value::<BackupAlgorithm>(json_data).unwrap(),
+            algorithm
+        );
+    }
+
+    #[test]
+    fn room_key_backup_serde() {
+        let backup = RoomKeyBackup {
+            sessions: btreemap! {
+                "sessionid".to_string() => KeyBackupData {
+                    first_message_index: UInt::from(1_u32),
+                    forwarded_count: UInt::from(0_u32),
+                    is_verified: true,
+                    session_data: EncryptedSessionData {
+                        ephemeral: "base64+ephemeral+key".to_string(),
+                        ciphertext: "base64+ciphertext+of+JSON+data".to_string(),
+                        mac: "base64+mac+of+ciphertext".to_string(),
+                    },
+                },
+            },
+        };
+        let json_data = json!({
+            "sessions": {
+                "sessionid": {
+                    "first_message_index": 1,
+                    "forwarded_count": 0,
+                    "is_verified": true,
+                    "session_data": {
+                        "ephemeral": "base64+ephemeral+key",
+                        "ciphertext": "base64+ciphertext+of+JSON+data",
+                        "mac": "base64+mac+of+ciphertext"
+                    }
+                }
+            }
+        });
+
+        assert_eq!(to_json_value(&backup).unwrap(), json_data);
+        assert_eq!(from_json_value::<RoomKeyBackup>(json_data).unwrap(), backup);
+    }
+
+    #[test]
+    fn forwarded_room_key_conversion() {
+        let room_id = RoomId::try_from("!Cuyf34gef24t:localhost").unwrap();
+        let content = ForwardedRoomKeyEventContent {
+            algorithm: Algorithm::MegolmV1AesSha2,
+            room_id: room_id.clone(),
+            sender_key: "RF3s+E7RkTQTGF2d8Deol0FkQvgII2aJDf3/Jp5mxVU".to_string(),
+            session_id: "X3lUlvLELLYxeTx4yOVu6UDpasGEVO0Jbu+QFnm0cKQ".to_string(),
+            session_key: "AgAAAADxKHa9uFxcXzwYoNueL5Xqi69IkD4sni8Llf".to_string(),
+            sender_claimed_ed25519_key: "aj40p+aw64yPIdsxoog8Jhlu9i+jZPiL7BNtcLyh/UE".to_string(),
+            forwarding_curve25519_key_chain: vec![],
+        };
+
+        let backed_up = BackedUpRoomKey::from(content);
+        assert_eq!(
+            to_json_value(&backed_up).unwrap(),
+            json!({
+                "algorithm": "m.megolm.v1.aes-sha2",
+                "sender_key": "RF3s+E7RkTQTGF2d8Deol0FkQvgII2aJDf3/Jp5mxVU",
+                "sender_claimed_keys": {
+                    "ed25519": "aj40p+aw64yPIdsxoog8Jhlu9i+jZPiL7BNtcLyh/UE"
+                },
+                "forwarding_curve25519_key_chain": [],
+                "session_key": "AgAAAADxKHa9uFxcXzwYoNueL5Xqi69IkD4sni8Llf"
+            })
+        );
+
+        let content = backed_up
+            .into_forwarded_room_key(
+                room_id.clone(),
+                "X3lUlvLELLYxeTx4yOVu6UDpasGEVO0Jbu+QFnm0cKQ".to_string(),
+            )
+            .unwrap();
+        assert_eq!(content.room_id, room_id);
+        assert_eq!(
+            content.sender_claimed_ed25519_key,
+            "aj40p+aw64yPIdsxoog8Jhlu9i+jZPiL7BNtcLyh/UE"
+        );
+    }
+}
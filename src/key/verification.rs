@@ -6,9 +6,16 @@ use serde::{Deserialize, Serialize};
 
 pub mod accept;
 pub mod cancel;
+pub mod done;
+pub mod in_room;
 pub mod key;
+pub mod lifecycle;
 pub mod mac;
+pub mod qr_code;
+pub mod ready;
 pub mod request;
+#[cfg(feature = "sas")]
+pub mod sas;
 pub mod start;
 
 /// A hash algorithm.
@@ -72,16 +79,31 @@ impl_enum! {
     }
 }
 
-/// A Short Authentication String (SAS) verification method.
+/// A key verification method.
 #[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
 pub enum VerificationMethod {
-    /// The *m.sas.v1* verification method.
+    /// The *m.sas.v1* Short Authentication String (SAS) verification method.
     #[serde(rename = "m.sas.v1")]
     MSasV1,
+
+    /// The *m.qr_code.show.v1* verification method: the device can show a QR code.
+    #[serde(rename = "m.qr_code.show.v1")]
+    QrCodeShowV1,
+
+    /// The *m.qr_code.scan.v1* verification method: the device can scan a QR code.
+    #[serde(rename = "m.qr_code.scan.v1")]
+    QrCodeScanV1,
+
+    /// The *m.reciprocate.v1* verification method, used to confirm a scanned QR code.
+    #[serde(rename = "m.reciprocate.v1")]
+    ReciprocateV1,
 }
 
 impl_enum! {
     VerificationMethod {
         MSasV1 => "m.sas.v1",
+        QrCodeShowV1 => "m.qr_code.show.v1",
+        QrCodeScanV1 => "m.qr_code.scan.v1",
+        ReciprocateV1 => "m.reciprocate.v1",
     }
 }
@@ -68,6 +68,13 @@ pub enum CancelCode {
     /// The device receiving this error can ignore the verification request.
     Accepted,
 
+    /// The hash commitment of an *m.key.verification.accept* message did not match the key
+    /// received in the *m.key.verification.key* message.
+    MismatchedCommitment,
+
+    /// The short authentication strings of the two devices did not match.
+    MismatchedSas,
+
     /// Any code that is not part of the specification.
     Custom(String),
 }
@@ -84,6 +91,8 @@ impl Display for CancelCode {
             CancelCode::UserMismatch => "m.user_mismatch",
             CancelCode::InvalidMessage => "m.invalid_message",
             CancelCode::Accepted => "m.accepted",
+            CancelCode::MismatchedCommitment => "m.mismatched_commitment",
+            CancelCode::MismatchedSas => "m.mismatched_sas",
             CancelCode::Custom(ref cancel_code) => cancel_code,
         };
 
@@ -106,6 +115,8 @@ where
             "m.user_mismatch" => CancelCode::UserMismatch,
             "m.invalid_message" => CancelCode::InvalidMessage,
             "m.accepted" => CancelCode::Accepted,
+            "m.mismatched_commitment" => CancelCode::MismatchedCommitment,
+            "m.mismatched_sas" => CancelCode::MismatchedSas,
             _ => CancelCode::Custom(s.into()),
         }
     }
@@ -117,6 +128,33 @@ impl From<CancelCode> for String {
     }
 }
 
+impl CancelEventContent {
+    /// Creates the payload of a cancel event with a generic description of the given code as the
+    /// reason.
+    pub(crate) fn with_code(transaction_id: String, code: CancelCode) -> Self {
+        let reason = match &code {
+            CancelCode::User => "The user cancelled the verification",
+            CancelCode::Timeout => "The verification process timed out",
+            CancelCode::UnknownTransaction => "Unknown transaction",
+            CancelCode::UnknownMethod => "Unknown or unsupported verification method",
+            CancelCode::UnexpectedMessage => "Unexpected message",
+            CancelCode::KeyMismatch => "The MAC of a key didn't match",
+            CancelCode::UserMismatch => "The user or device didn't match",
+            CancelCode::InvalidMessage => "Invalid message",
+            CancelCode::MismatchedCommitment => "The hash commitment didn't match",
+            CancelCode::MismatchedSas => "The short authentication strings didn't match",
+            CancelCode::Accepted => "The request was accepted by another device",
+            CancelCode::Custom(_) => "The verification was cancelled",
+        };
+
+        Self {
+            transaction_id,
+            reason: reason.to_string(),
+            code,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
@@ -131,7 +169,7 @@ mod tests {
     #[test]
     fn custom_cancel_codes_serialize_to_display_form() {
         assert_eq!(
-            to_json_value(&CancelCode::Custom("io.ruma.test".to_string())).unwrap(),
+            to_json_value(CancelCode::Custom("io.ruma.test".to_string())).unwrap(),
             json!("io.ruma.test")
         );
     }
@@ -0,0 +1,19 @@
+//! Types for the *m.key.verification.done* event.
+
+use ruma_events_macros::ruma_event;
+
+ruma_event! {
+    /// Signals that a device has finished its part of a key verification process.
+    ///
+    /// Typically sent as a to-device event.
+    DoneEvent {
+        kind: Event,
+        event_type: "m.key.verification.done",
+        content: {
+            /// An opaque identifier for the verification process.
+            ///
+            /// Must be the same as the one used for the *m.key.verification.start* message.
+            pub transaction_id: String,
+        },
+    }
+}
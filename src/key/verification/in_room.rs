@@ -0,0 +1,525 @@
+//! In-room versions of key verification events.
+//!
+//! Besides to-device messages, key verification can take place in a room shared by the two users.
+//! The verification process is then started by an *m.room.message* with the
+//! *m.key.verification.request* msgtype (see
+//! `room::message::KeyVerificationRequestMessageEventContent`), and all following events are room
+//! events that reference that message. Instead of a `transaction_id`, their content contains an
+//! `m.relates_to` field of type `m.reference` with the event ID of the request message.
+//!
+//! The content types of the to-device events are shared with the in-room events: the
+//! `transaction_id` of the content is the event ID of the request message.
+
+use std::time::SystemTime;
+
+use ruma_identifiers::{EventId, RoomId, UserId};
+use serde::{
+    de::{DeserializeOwned, Error as _},
+    ser::Error as _,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::Value as JsonValue;
+
+use super::{
+    accept::AcceptEventContent, cancel::CancelEventContent, done::DoneEventContent,
+    key::KeyEventContent, mac::MacEventContent, ready::ReadyEventContent, start::StartEventContent,
+};
+use crate::{EventType, TryFromRaw, UnsignedData};
+
+/// The content of an event that is part of a key verification process.
+pub trait VerificationEventContent {
+    /// The type of the event this content belongs to.
+    fn event_type() -> EventType;
+}
+
+/// In-room version of a key verification event.
+#[derive(Clone, Debug)]
+pub struct InRoomVerificationEvent<C> {
+    /// Data specific to the event type.
+    ///
+    /// The `transaction_id` of the content is the event ID of the *m.key.verification.request*
+    /// message that started the verification process.
+    pub content: C,
+
+    /// The unique identifier for the event.
+    pub event_id: EventId,
+
+    /// Time on originating homeserver when this event was sent.
+    pub origin_server_ts: SystemTime,
+
+    /// The unique identifier for the room associated with this event.
+    pub room_id: Option<RoomId>,
+
+    /// The unique identifier for the user who sent this event.
+    pub sender: UserId,
+
+    /// Additional key-value pairs not signed by the homeserver.
+    pub unsigned: UnsignedData,
+}
+
+/// In-room version of the *m.key.verification.start* event.
+pub type InRoomVerificationStart = InRoomVerificationEvent<StartEventContent>;
+
+/// In-room version of the *m.key.verification.accept* event.
+pub type InRoomVerificationAccept = InRoomVerificationEvent<AcceptEventContent>;
+
+/// In-room version of the *m.key.verification.key* event.
+pub type InRoomVerificationKey = InRoomVerificationEvent<KeyEventContent>;
+
+/// In-room version of the *m.key.verification.mac* event.
+pub type InRoomVerificationMac = InRoomVerificationEvent<MacEventContent>;
+
+/// In-room version of the *m.key.verification.cancel* event.
+pub type InRoomVerificationCancel = InRoomVerificationEvent<CancelEventContent>;
+
+/// In-room version of the *m.key.verification.ready* event.
+pub type InRoomVerificationReady = InRoomVerificationEvent<ReadyEventContent>;
+
+/// In-room version of the *m.key.verification.done* event.
+pub type InRoomVerificationDone = InRoomVerificationEvent<DoneEventContent>;
+
+macro_rules! impl_verification_event_content {
+    ($content_name:ty, $event_type:path) => {
+        impl VerificationEventContent for $content_name {
+            fn event_type() -> EventType {
+                $event_type
+            }
+        }
+    };
+}
+
+impl_verification_event_content!(StartEventContent, EventType::KeyVerificationStart);
+impl_verification_event_content!(AcceptEventContent, EventType::KeyVerificationAccept);
+impl_verification_event_content!(KeyEventContent, EventType::KeyVerificationKey);
+impl_verification_event_content!(MacEventContent, EventType::KeyVerificationMac);
+impl_verification_event_content!(CancelEventContent, EventType::KeyVerificationCancel);
+impl_verification_event_content!(ReadyEventContent, EventType::KeyVerificationReady);
+impl_verification_event_content!(DoneEventContent, EventType::KeyVerificationDone);
+
+impl<C> TryFromRaw for InRoomVerificationEvent<C>
+where
+    C: TryFromRaw,
+{
+    type Raw = InRoomVerificationEvent<C::Raw>;
+    type Err = C::Err;
+
+    fn try_from_raw(raw: InRoomVerificationEvent<C::Raw>) -> Result<Self, Self::Err> {
+        Ok(Self {
+            content: C::try_from_raw(raw.content)?,
+            event_id: raw.event_id,
+            origin_server_ts: raw.origin_server_ts,
+            room_id: raw.room_id,
+            sender: raw.sender,
+            unsigned: raw.unsigned,
+        })
+    }
+}
+
+impl<C> crate::Event for InRoomVerificationEvent<C>
+where
+    C: VerificationEventContent + std::fmt::Debug + Serialize + TryFromRaw,
+    C::Raw: DeserializeOwned,
+{
+    /// The type of this event's `content` field.
+    type Content = C;
+
+    /// The event's content.
+    fn content(&self) -> &Self::Content {
+        &self.content
+    }
+
+    /// The type of the event.
+    fn event_type(&self) -> EventType {
+        C::event_type()
+    }
+}
+
+impl<C> crate::RoomEvent for InRoomVerificationEvent<C>
+where
+    C: VerificationEventContent + std::fmt::Debug + Serialize + TryFromRaw,
+    C::Raw: DeserializeOwned,
+{
+    /// The unique identifier for the event.
+    fn event_id(&self) -> &EventId {
+        &self.event_id
+    }
+
+    /// Time on originating homeserver when this event was sent.
+    fn origin_server_ts(&self) -> SystemTime {
+        self.origin_server_ts
+    }
+
+    /// The unique identifier for the room associated with this event.
+    ///
+    /// This can be `None` if the event came from a context where there is
+    /// no ambiguity which room it belongs to, like a `/sync` response for example.
+    fn room_id(&self) -> Option<&RoomId> {
+        self.room_id.as_ref()
+    }
+
+    /// The unique identifier for the user who sent this event.
+    fn sender(&self) -> &UserId {
+        &self.sender
+    }
+
+    /// Additional key-value pairs not signed by the homeserver.
+    fn unsigned(&self) -> &UnsignedData {
+        &self.unsigned
+    }
+}
+
+impl<C> Serialize for InRoomVerificationEvent<C>
+where
+    C: VerificationEventContent + Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let content = to_in_room_content(&self.content).map_err(S::Error::custom)?;
+
+        SerializeHelper {
+            content,
+            event_id: &self.event_id,
+            origin_server_ts: self.origin_server_ts,
+            room_id: self.room_id.as_ref(),
+            sender: &self.sender,
+            event_type: C::event_type(),
+            unsigned: &self.unsigned,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, C> Deserialize<'de> for InRoomVerificationEvent<C>
+where
+    C: DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let DeserializeHelper {
+            mut content,
+            event_id,
+            origin_server_ts,
+            room_id,
+            sender,
+            unsigned,
+        } = DeserializeHelper::deserialize(deserializer)?;
+
+        let object = content
+            .as_object_mut()
+            .ok_or_else(|| D::Error::custom("verification event content must be an object"))?;
+        let relation = object
+            .remove("m.relates_to")
+            .ok_or_else(|| D::Error::missing_field("m.relates_to"))?;
+        let relation: Relation = serde_json::from_value(relation).map_err(D::Error::custom)?;
+        object.insert(
+            "transaction_id".to_string(),
+            JsonValue::String(relation.event_id.to_string()),
+        );
+
+        Ok(Self {
+            content: serde_json::from_value(content).map_err(D::Error::custom)?,
+            event_id,
+            origin_server_ts,
+            room_id,
+            sender,
+            unsigned,
+        })
+    }
+}
+
+/// Serializes the content of a key verification event as it is sent in a room, i.e. with the
+/// `transaction_id` replaced by an `m.relates_to` field referencing the request message.
+pub(crate) fn to_in_room_content(content: &impl Serialize) -> Result<JsonValue, String> {
+    let mut content = serde_json::to_value(content).map_err(|error| error.to_string())?;
+    let object = content
+        .as_object_mut()
+        .ok_or("verification event content must be an object")?;
+    let transaction_id = object
+        .remove("transaction_id")
+        .ok_or("verification event content lacks a transaction ID")?;
+    let relation = Relation {
+        rel_type: RelationType::Reference,
+        event_id: serde_json::from_value(transaction_id).map_err(|error| error.to_string())?,
+    };
+    object.insert(
+        "m.relates_to".to_string(),
+        serde_json::to_value(relation).map_err(|error| error.to_string())?,
+    );
+
+    Ok(content)
+}
+
+/// An in-room verification event with the content already in its serialized form.
+#[derive(Serialize)]
+struct SerializeHelper<'a> {
+    content: JsonValue,
+
+    event_id: &'a EventId,
+
+    #[serde(with = "ruma_serde::time::ms_since_unix_epoch")]
+    origin_server_ts: SystemTime,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    room_id: Option<&'a RoomId>,
+
+    sender: &'a UserId,
+
+    #[serde(rename = "type")]
+    event_type: EventType,
+
+    #[serde(skip_serializing_if = "UnsignedData::is_empty")]
+    unsigned: &'a UnsignedData,
+}
+
+/// An in-room verification event whose content has not been deserialized yet.
+#[derive(Deserialize)]
+struct DeserializeHelper {
+    content: JsonValue,
+
+    event_id: EventId,
+
+    #[serde(with = "ruma_serde::time::ms_since_unix_epoch")]
+    origin_server_ts: SystemTime,
+
+    room_id: Option<RoomId>,
+
+    sender: UserId,
+
+    #[serde(default)]
+    unsigned: UnsignedData,
+}
+
+/// The `m.relates_to` field of in-room verification events.
+#[derive(Deserialize, Serialize)]
+struct Relation {
+    rel_type: RelationType,
+    event_id: EventId,
+}
+
+/// The only relation type used by in-room verification events.
+#[derive(Deserialize, Serialize)]
+enum RelationType {
+    #[serde(rename = "m.reference")]
+    Reference,
+}
+
+pub(crate) mod raw {
+    use super::InRoomVerificationEvent;
+    use crate::key::verification::{
+        accept::raw::AcceptEventContent, cancel::raw::CancelEventContent,
+        done::raw::DoneEventContent, key::raw::KeyEventContent, mac::raw::MacEventContent,
+        ready::raw::ReadyEventContent, start::raw::StartEventContent,
+    };
+
+    /// In-room version of the *m.key.verification.start* event.
+    pub type InRoomVerificationStart = InRoomVerificationEvent<StartEventContent>;
+    /// In-room version of the *m.key.verification.accept* event.
+    pub type InRoomVerificationAccept = InRoomVerificationEvent<AcceptEventContent>;
+    /// In-room version of the *m.key.verification.key* event.
+    pub type InRoomVerificationKey = InRoomVerificationEvent<KeyEventContent>;
+    /// In-room version of the *m.key.verification.mac* event.
+    pub type InRoomVerificationMac = InRoomVerificationEvent<MacEventContent>;
+    /// In-room version of the *m.key.verification.cancel* event.
+    pub type InRoomVerificationCancel = InRoomVerificationEvent<CancelEventContent>;
+    /// In-room version of the *m.key.verification.ready* event.
+    pub type InRoomVerificationReady = InRoomVerificationEvent<ReadyEventContent>;
+    /// In-room version of the *m.key.verification.done* event.
+    pub type InRoomVerificationDone = InRoomVerificationEvent<DoneEventContent>;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::TryFrom,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use matches::assert_matches;
+    use ruma_identifiers::{EventId, RoomId, UserId};
+    use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
+
+    use super::{InRoomVerificationCancel, InRoomVerificationEvent, InRoomVerificationStart};
+    use crate::{
+        collections::all::{Event, RoomEvent},
+        key::verification::{
+            cancel::{CancelCode, CancelEventContent},
+            start::StartEventContent,
+        },
+        EventJson, UnsignedData,
+    };
+
+    #[test]
+    fn serialization() {
+        let event: InRoomVerificationCancel = InRoomVerificationEvent {
+            content: CancelEventContent {
+                transaction_id: "$request:example.org".to_string(),
+                reason: "User rejected the key verification request".to_string(),
+                code: CancelCode::User,
+            },
+            event_id: EventId::try_from("$cancel:example.org").unwrap(),
+            origin_server_ts: UNIX_EPOCH + Duration::from_millis(1),
+            room_id: Some(RoomId::try_from("!room:example.org").unwrap()),
+            sender: UserId::try_from("@alice:example.org").unwrap(),
+            unsigned: UnsignedData::default(),
+        };
+
+        assert_eq!(
+            to_json_value(&event).unwrap(),
+            json!({
+                "content": {
+                    "code": "m.user",
+                    "reason": "User rejected the key verification request",
+                    "m.relates_to": {
+                        "rel_type": "m.reference",
+                        "event_id": "$request:example.org"
+                    }
+                },
+                "event_id": "$cancel:example.org",
+                "origin_server_ts": 1,
+                "room_id": "!room:example.org",
+                "sender": "@alice:example.org",
+                "type": "m.key.verification.cancel"
+            })
+        );
+    }
+
+    #[test]
+    fn deserialization() {
+        let json_data = json!({
+            "content": {
+                "from_device": "AliceDevice",
+                "method": "m.sas.v1",
+                "key_agreement_protocols": ["curve25519"],
+                "hashes": ["sha256"],
+                "message_authentication_codes": ["hkdf-hmac-sha256"],
+                "short_authentication_string": ["decimal"],
+                "m.relates_to": {
+                    "rel_type": "m.reference",
+                    "event_id": "$request:example.org"
+                }
+            },
+            "event_id": "$start:example.org",
+            "origin_server_ts": 1,
+            "sender": "@alice:example.org",
+            "type": "m.key.verification.start"
+        });
+
+        let event = from_json_value::<EventJson<InRoomVerificationStart>>(json_data)
+            .unwrap()
+            .deserialize()
+            .unwrap();
+
+        assert_matches!(
+            &event.content,
+            StartEventContent::MSasV1(content)
+                if content.transaction_id == "$request:example.org"
+                    && content.from_device == "AliceDevice"
+        );
+        assert_eq!(
+            event.event_id,
+            EventId::try_from("$start:example.org").unwrap()
+        );
+        assert!(event.room_id.is_none());
+    }
+
+    #[test]
+    fn deserialization_without_relation() {
+        let json_data = json!({
+            "content": {
+                "transaction_id": "$request:example.org",
+                "code": "m.user",
+                "reason": "User rejected the key verification request"
+            },
+            "event_id": "$cancel:example.org",
+            "origin_server_ts": 1,
+            "sender": "@alice:example.org",
+            "type": "m.key.verification.cancel"
+        });
+
+        assert!(
+            from_json_value::<EventJson<InRoomVerificationCancel>>(json_data)
+                .unwrap()
+                .deserialize()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn deserialization_with_wrong_relation_type() {
+        let json_data = json!({
+            "content": {
+                "code": "m.user",
+                "reason": "User rejected the key verification request",
+                "m.relates_to": {
+                    "rel_type": "m.replace",
+                    "event_id": "$request:example.org"
+                }
+            },
+            "event_id": "$cancel:example.org",
+            "origin_server_ts": 1,
+            "sender": "@alice:example.org",
+            "type": "m.key.verification.cancel"
+        });
+
+        assert!(
+            from_json_value::<EventJson<InRoomVerificationCancel>>(json_data)
+                .unwrap()
+                .deserialize()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn collections_deserialization() {
+        let in_room = json!({
+            "content": {
+                "transaction_id": "should be ignored",
+                "code": "m.timeout",
+                "reason": "Timed out",
+                "m.relates_to": {
+                    "rel_type": "m.reference",
+                    "event_id": "$request:example.org"
+                }
+            },
+            "event_id": "$cancel:example.org",
+            "origin_server_ts": 1,
+            "sender": "@alice:example.org",
+            "type": "m.key.verification.cancel"
+        });
+        let to_device = json!({
+            "content": {
+                "transaction_id": "S0meUniqueAndOpaqueString",
+                "code": "m.timeout",
+                "reason": "Timed out"
+            },
+            "type": "m.key.verification.cancel"
+        });
+
+        assert_matches!(
+            from_json_value::<EventJson<RoomEvent>>(in_room.clone())
+                .unwrap()
+                .deserialize()
+                .unwrap(),
+            RoomEvent::InRoomKeyVerificationCancel(event)
+                if event.content.transaction_id == "$request:example.org"
+        );
+        assert_matches!(
+            from_json_value::<EventJson<Event>>(in_room)
+                .unwrap()
+                .deserialize()
+                .unwrap(),
+            Event::InRoomKeyVerificationCancel(_)
+        );
+        assert_matches!(
+            from_json_value::<EventJson<Event>>(to_device)
+                .unwrap()
+                .deserialize()
+                .unwrap(),
+            Event::KeyVerificationCancel(_)
+        );
+    }
+}
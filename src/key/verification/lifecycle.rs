@@ -0,0 +1,549 @@
+//! Tracking of the lifecycle of key verification requests.
+//!
+//! A key verification process usually goes through the following events, all sharing the same
+//! `transaction_id`:
+//!
+//! 1. *m.key.verification.request*, sent by the device that wants to verify.
+//! 2. *m.key.verification.ready*, sent by the device that accepts the request.
+//! 3. *m.key.verification.start*, sent by either device to begin a verification method.
+//! 4. *m.key.verification.done*, sent by both devices once the method finished successfully.
+//!
+//! Either device can send *m.key.verification.cancel* at any point. `VerificationRequests`
+//! checks that the events arrive in this order and applies the time limits of the
+//! specification. The events of the method itself, e.g. *m.key.verification.key* for
+//! *m.sas.v1*, are not tracked here.
+
+use std::{
+    collections::BTreeMap,
+    time::{Duration, SystemTime},
+};
+
+use super::{
+    cancel::{CancelCode, CancelEventContent},
+    done::DoneEventContent,
+    ready::ReadyEventContent,
+    start::StartEventContent,
+    VerificationMethod,
+};
+
+/// The time after which a verification process times out.
+const TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// How far in the future the timestamp of a request may be.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(5 * 60);
+
+/// Which device sent a key verification event.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Origin {
+    /// The event was sent by this device.
+    Own,
+
+    /// The event was sent by the other device.
+    Other,
+}
+
+/// The phase of a key verification process.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VerificationPhase {
+    /// The verification was requested and waits for the other device to be ready.
+    Requested,
+
+    /// The other device is ready, and a verification method can be started.
+    Ready,
+
+    /// A verification method was started.
+    Started,
+
+    /// Both devices sent *m.key.verification.done*.
+    Done,
+
+    /// The verification was cancelled with the given code.
+    Cancelled(CancelCode),
+}
+
+/// A key verification process tracked by `VerificationRequests`.
+#[derive(Clone, Debug)]
+pub struct Verification {
+    transaction_id: String,
+    phase: VerificationPhase,
+    requested_by: Option<Origin>,
+    started_by: Option<Origin>,
+    request_methods: Vec<VerificationMethod>,
+    ready_methods: Vec<VerificationMethod>,
+    own_done: bool,
+    other_done: bool,
+    expires_at: SystemTime,
+}
+
+impl Verification {
+    /// The opaque identifier of the verification process.
+    pub fn transaction_id(&self) -> &str {
+        &self.transaction_id
+    }
+
+    /// The current phase of the verification process.
+    pub fn phase(&self) -> &VerificationPhase {
+        &self.phase
+    }
+
+    /// Whether the verification process is done or was cancelled.
+    pub fn is_finished(&self) -> bool {
+        match self.phase {
+            VerificationPhase::Done | VerificationPhase::Cancelled(_) => true,
+            _ => false,
+        }
+    }
+
+    /// The point in time after which the verification process times out.
+    pub fn expires_at(&self) -> SystemTime {
+        self.expires_at
+    }
+
+    fn cancel(&mut self, code: CancelCode) -> CancelEventContent {
+        self.phase = VerificationPhase::Cancelled(code.clone());
+        CancelEventContent::with_code(self.transaction_id.clone(), code)
+    }
+}
+
+/// The key verification processes of a device, indexed by their transaction ID.
+///
+/// Each method takes the current time as an argument, and returns the content of the
+/// *m.key.verification.cancel* event to send if the event given to it is not valid in the current
+/// phase of the verification process.
+#[derive(Clone, Debug, Default)]
+pub struct VerificationRequests {
+    verifications: BTreeMap<String, Verification>,
+}
+
+impl VerificationRequests {
+    /// Creates an empty set of verification processes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The verification process with the given transaction ID, if any.
+    pub fn get(&self, transaction_id: &str) -> Option<&Verification> {
+        self.verifications.get(transaction_id)
+    }
+
+    /// Tracks a verification request.
+    ///
+    /// For to-device requests, `timestamp` is the `timestamp` of the
+    /// *m.key.verification.request* event. For in-room requests, the transaction ID is the event
+    /// ID of the request message and `timestamp` is its `origin_server_ts`.
+    ///
+    /// Returns `false` if the request should be ignored, because its timestamp is more than 5
+    /// minutes in the future or more than 10 minutes in the past, or because a verification
+    /// process with the same transaction ID exists.
+    pub fn request(
+        &mut self,
+        origin: Origin,
+        transaction_id: String,
+        methods: Vec<VerificationMethod>,
+        timestamp: SystemTime,
+        now: SystemTime,
+    ) -> bool {
+        let expires_at = timestamp + TIMEOUT;
+
+        if timestamp > now + MAX_CLOCK_SKEW
+            || expires_at < now
+            || self.verifications.contains_key(&transaction_id)
+        {
+            return false;
+        }
+
+        self.verifications.insert(
+            transaction_id.clone(),
+            Verification {
+                transaction_id,
+                phase: VerificationPhase::Requested,
+                requested_by: Some(origin),
+                started_by: None,
+                request_methods: methods,
+                ready_methods: Vec::new(),
+                own_done: false,
+                other_done: false,
+                expires_at,
+            },
+        );
+
+        true
+    }
+
+    /// Tracks an *m.key.verification.ready* event.
+    ///
+    /// The event must be sent by the device that did not send the request.
+    pub fn ready(
+        &mut self,
+        origin: Origin,
+        content: &ReadyEventContent,
+        now: SystemTime,
+    ) -> Result<(), CancelEventContent> {
+        let verification = self.active(&content.transaction_id, now)?;
+
+        if verification.phase != VerificationPhase::Requested
+            || verification.requested_by == Some(origin)
+        {
+            return Err(verification.cancel(CancelCode::UnexpectedMessage));
+        }
+
+        verification.ready_methods = content.methods.clone();
+        verification.phase = VerificationPhase::Ready;
+
+        Ok(())
+    }
+
+    /// Tracks an *m.key.verification.start* event.
+    ///
+    /// If no verification process with the transaction ID of the event exists, a new one is
+    /// tracked, since to-device verification may be started without a request. Otherwise, the
+    /// method of the event must have been offered by both the request and the ready event.
+    ///
+    /// If both devices start a verification method at the same time, both start events are
+    /// accepted; which one takes precedence depends on the verification method.
+    pub fn start(
+        &mut self,
+        origin: Origin,
+        content: &StartEventContent,
+        now: SystemTime,
+    ) -> Result<(), CancelEventContent> {
+        let transaction_id = content.transaction_id();
+
+        if !self.verifications.contains_key(transaction_id) {
+            self.verifications.insert(
+                transaction_id.to_string(),
+                Verification {
+                    transaction_id: transaction_id.to_string(),
+                    phase: VerificationPhase::Started,
+                    requested_by: None,
+                    started_by: Some(origin),
+                    request_methods: Vec::new(),
+                    ready_methods: Vec::new(),
+                    own_done: false,
+                    other_done: false,
+                    expires_at: now + TIMEOUT,
+                },
+            );
+
+            return Ok(());
+        }
+
+        let verification = self.active(transaction_id, now)?;
+
+        match verification.phase {
+            VerificationPhase::Ready => {
+                let method = content.method();
+
+                if !verification.request_methods.contains(&method)
+                    || !verification.ready_methods.contains(&method)
+                {
+                    return Err(verification.cancel(CancelCode::UnknownMethod));
+                }
+
+                verification.started_by = Some(origin);
+                verification.phase = VerificationPhase::Started;
+
+                Ok(())
+            }
+            VerificationPhase::Started if verification.started_by != Some(origin) => Ok(()),
+            _ => Err(verification.cancel(CancelCode::UnexpectedMessage)),
+        }
+    }
+
+    /// Tracks an *m.key.verification.done* event.
+    ///
+    /// The verification process is done once both devices sent this event.
+    pub fn done(
+        &mut self,
+        origin: Origin,
+        content: &DoneEventContent,
+        now: SystemTime,
+    ) -> Result<(), CancelEventContent> {
+        let verification = self.active(&content.transaction_id, now)?;
+
+        if verification.phase != VerificationPhase::Started {
+            return Err(verification.cancel(CancelCode::UnexpectedMessage));
+        }
+
+        match origin {
+            Origin::Own => verification.own_done = true,
+            Origin::Other => verification.other_done = true,
+        }
+
+        if verification.own_done && verification.other_done {
+            verification.phase = VerificationPhase::Done;
+        }
+
+        Ok(())
+    }
+
+    /// Tracks an *m.key.verification.cancel* event sent by either device.
+    pub fn cancel(&mut self, content: &CancelEventContent) {
+        if let Some(verification) = self.verifications.get_mut(&content.transaction_id) {
+            if !verification.is_finished() {
+                verification.phase = VerificationPhase::Cancelled(content.code.clone());
+            }
+        }
+    }
+
+    /// Cancels all verification processes that timed out at the given point in time.
+    ///
+    /// Returns the contents of the *m.key.verification.cancel* events to send.
+    pub fn expire(&mut self, now: SystemTime) -> Vec<CancelEventContent> {
+        self.verifications
+            .values_mut()
+            .filter(|verification| !verification.is_finished() && verification.expires_at < now)
+            .map(|verification| verification.cancel(CancelCode::Timeout))
+            .collect()
+    }
+
+    /// Stops tracking verification processes that are done or were cancelled.
+    pub fn remove_finished(&mut self) {
+        let finished: Vec<_> = self
+            .verifications
+            .values()
+            .filter(|verification| verification.is_finished())
+            .map(|verification| verification.transaction_id.clone())
+            .collect();
+
+        for transaction_id in finished {
+            self.verifications.remove(&transaction_id);
+        }
+    }
+
+    /// Looks up a verification process that has not finished or timed out yet.
+    fn active(
+        &mut self,
+        transaction_id: &str,
+        now: SystemTime,
+    ) -> Result<&mut Verification, CancelEventContent> {
+        let verification = match self.verifications.get_mut(transaction_id) {
+            Some(verification) => verification,
+            None => {
+                return Err(CancelEventContent::with_code(
+                    transaction_id.to_string(),
+                    CancelCode::UnknownTransaction,
+                ))
+            }
+        };
+
+        if let VerificationPhase::Cancelled(code) = &verification.phase {
+            return Err(CancelEventContent::with_code(
+                transaction_id.to_string(),
+                code.clone(),
+            ));
+        }
+
+        if verification.phase == VerificationPhase::Done {
+            return Err(CancelEventContent::with_code(
+                transaction_id.to_string(),
+                CancelCode::UnexpectedMessage,
+            ));
+        }
+
+        if verification.expires_at < now {
+            return Err(verification.cancel(CancelCode::Timeout));
+        }
+
+        Ok(verification)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::{Origin, VerificationPhase, VerificationRequests};
+    use crate::key::verification::{
+        cancel::{CancelCode, CancelEventContent},
+        done::DoneEventContent,
+        ready::ReadyEventContent,
+        start::{ReciprocateV1Content, StartEventContent},
+        VerificationMethod,
+    };
+
+    fn ready(methods: Vec<VerificationMethod>) -> ReadyEventContent {
+        ReadyEventContent {
+            from_device: "BOBDEVICE".to_string(),
+            methods,
+            transaction_id: "transaction".to_string(),
+        }
+    }
+
+    fn start(transaction_id: &str) -> StartEventContent {
+        StartEventContent::ReciprocateV1(ReciprocateV1Content {
+            from_device: "BOBDEVICE".to_string(),
+            transaction_id: transaction_id.to_string(),
+            secret: "c2hhcmVkIHNlY3JldA".to_string(),
+        })
+    }
+
+    fn done() -> DoneEventContent {
+        DoneEventContent {
+            transaction_id: "transaction".to_string(),
+        }
+    }
+
+    fn requested(now: SystemTime) -> VerificationRequests {
+        let mut requests = VerificationRequests::new();
+        assert!(requests.request(
+            Origin::Own,
+            "transaction".to_string(),
+            vec![
+                VerificationMethod::QrCodeShowV1,
+                VerificationMethod::ReciprocateV1,
+            ],
+            now,
+            now,
+        ));
+        requests
+    }
+
+    fn phase(requests: &VerificationRequests) -> &VerificationPhase {
+        requests.get("transaction").unwrap().phase()
+    }
+
+    #[test]
+    fn full_lifecycle() {
+        let now = SystemTime::now();
+        let mut requests = requested(now);
+
+        let methods = vec![
+            VerificationMethod::QrCodeScanV1,
+            VerificationMethod::ReciprocateV1,
+        ];
+        requests.ready(Origin::Other, &ready(methods), now).unwrap();
+        assert_eq!(phase(&requests), &VerificationPhase::Ready);
+
+        requests
+            .start(Origin::Other, &start("transaction"), now)
+            .unwrap();
+        assert_eq!(phase(&requests), &VerificationPhase::Started);
+
+        requests.done(Origin::Own, &done(), now).unwrap();
+        assert_eq!(phase(&requests), &VerificationPhase::Started);
+        requests.done(Origin::Other, &done(), now).unwrap();
+        assert_eq!(phase(&requests), &VerificationPhase::Done);
+
+        requests.remove_finished();
+        assert!(requests.get("transaction").is_none());
+    }
+
+    #[test]
+    fn start_without_request() {
+        let mut requests = VerificationRequests::new();
+
+        requests
+            .start(Origin::Other, &start("transaction"), SystemTime::now())
+            .unwrap();
+        assert_eq!(phase(&requests), &VerificationPhase::Started);
+    }
+
+    #[test]
+    fn request_timestamp_rules() {
+        let now = SystemTime::now();
+        let mut requests = VerificationRequests::new();
+
+        assert!(!requests.request(
+            Origin::Other,
+            "future".to_string(),
+            vec![VerificationMethod::MSasV1],
+            now + Duration::from_secs(6 * 60),
+            now,
+        ));
+        assert!(!requests.request(
+            Origin::Other,
+            "past".to_string(),
+            vec![VerificationMethod::MSasV1],
+            now - Duration::from_secs(11 * 60),
+            now,
+        ));
+        assert!(requests.request(
+            Origin::Other,
+            "recent".to_string(),
+            vec![VerificationMethod::MSasV1],
+            now - Duration::from_secs(9 * 60),
+            now,
+        ));
+    }
+
+    #[test]
+    fn unexpected_events() {
+        let now = SystemTime::now();
+        let mut requests = requested(now);
+
+        let cancel = requests
+            .ready(Origin::Own, &ready(vec![VerificationMethod::MSasV1]), now)
+            .unwrap_err();
+        assert_eq!(cancel.code, CancelCode::UnexpectedMessage);
+        assert_eq!(
+            phase(&requests),
+            &VerificationPhase::Cancelled(CancelCode::UnexpectedMessage)
+        );
+
+        let cancel = requests
+            .done(
+                Origin::Other,
+                &DoneEventContent {
+                    transaction_id: "unknown".to_string(),
+                },
+                now,
+            )
+            .unwrap_err();
+        assert_eq!(cancel.code, CancelCode::UnknownTransaction);
+        assert_eq!(cancel.transaction_id, "unknown");
+    }
+
+    #[test]
+    fn unoffered_method() {
+        let now = SystemTime::now();
+        let mut requests = requested(now);
+
+        requests
+            .ready(Origin::Other, &ready(vec![VerificationMethod::MSasV1]), now)
+            .unwrap();
+
+        let cancel = requests
+            .start(Origin::Other, &start("transaction"), now)
+            .unwrap_err();
+        assert_eq!(cancel.code, CancelCode::UnknownMethod);
+    }
+
+    #[test]
+    fn timeout() {
+        let now = SystemTime::now();
+        let mut requests = requested(now);
+        let later = now + Duration::from_secs(11 * 60);
+
+        let cancel = requests
+            .ready(
+                Origin::Other,
+                &ready(vec![VerificationMethod::ReciprocateV1]),
+                later,
+            )
+            .unwrap_err();
+        assert_eq!(cancel.code, CancelCode::Timeout);
+
+        let mut requests = requested(now);
+        let cancels = requests.expire(later);
+        assert_eq!(cancels.len(), 1);
+        assert_eq!(cancels[0].transaction_id, "transaction");
+        assert!(requests.expire(later).is_empty());
+    }
+
+    #[test]
+    fn cancel() {
+        let now = SystemTime::now();
+        let mut requests = requested(now);
+
+        requests.cancel(&CancelEventContent {
+            transaction_id: "transaction".to_string(),
+            reason: "Not now".to_string(),
+            code: CancelCode::User,
+        });
+        assert_eq!(
+            phase(&requests),
+            &VerificationPhase::Cancelled(CancelCode::User)
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Types for the binary payload of QR codes used for key verification.
+//!
+//! A device that supports the *m.qr_code.show.v1* method displays a QR code encoding a
+//! `QrCodeData`. The device that scans it checks the keys it contains and sends an
+//! *m.key.verification.start* event with the *m.reciprocate.v1* method, echoing the shared secret.
+
+use std::convert::TryFrom;
+
+use crate::InvalidInput;
+
+/// The prefix of every QR code payload.
+const PREFIX: &[u8] = b"MATRIX";
+
+/// The version of the QR code payload format.
+const VERSION: u8 = 0x02;
+
+/// The minimum length of the shared secret, in bytes.
+const MIN_SECRET_LENGTH: usize = 8;
+
+/// The mode of a QR code, which determines the meaning of the keys it contains.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QrCodeMode {
+    /// Verifying another user with cross-signing.
+    ///
+    /// The first key is the master cross-signing key of the user showing the QR code, the second
+    /// key is what that user's device thinks the master cross-signing key of the other user is.
+    VerifyingAnotherUser,
+
+    /// Verifying another device of the same user, where the device showing the QR code trusts
+    /// the master cross-signing key.
+    ///
+    /// The first key is the user's master cross-signing key, the second key is what the device
+    /// showing the QR code thinks the Ed25519 key of the other device is.
+    SelfVerificationMasterKeyTrusted,
+
+    /// Verifying another device of the same user, where the device showing the QR code does not
+    /// yet trust the master cross-signing key.
+    ///
+    /// The first key is the Ed25519 key of the device showing the QR code, the second key is
+    /// what that device thinks the user's master cross-signing key is.
+    SelfVerificationMasterKeyUntrusted,
+}
+
+impl QrCodeMode {
+    fn to_byte(self) -> u8 {
+        match self {
+            QrCodeMode::VerifyingAnotherUser => 0x00,
+            QrCodeMode::SelfVerificationMasterKeyTrusted => 0x01,
+            QrCodeMode::SelfVerificationMasterKeyUntrusted => 0x02,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0x00 => Some(QrCodeMode::VerifyingAnotherUser),
+            0x01 => Some(QrCodeMode::SelfVerificationMasterKeyTrusted),
+            0x02 => Some(QrCodeMode::SelfVerificationMasterKeyUntrusted),
+            _ => None,
+        }
+    }
+}
+
+/// The data encoded in a QR code used for key verification.
+#[derive(Clone, Debug, PartialEq)]
+pub struct QrCodeData {
+    mode: QrCodeMode,
+    transaction_id: String,
+    first_key: [u8; 32],
+    second_key: [u8; 32],
+    shared_secret: Vec<u8>,
+}
+
+impl QrCodeData {
+    /// Creates the data for a QR code.
+    ///
+    /// The keys are the raw 32-byte Ed25519 keys whose meaning depends on `mode`. For the in-room
+    /// flow, the transaction ID is the event ID of the *m.key.verification.request* message.
+    ///
+    /// # Errors
+    ///
+    /// `InvalidInput` will be returned if the transaction ID is longer than 65535 bytes or the
+    /// shared secret is shorter than 8 bytes.
+    pub fn new(
+        mode: QrCodeMode,
+        transaction_id: String,
+        first_key: [u8; 32],
+        second_key: [u8; 32],
+        shared_secret: Vec<u8>,
+    ) -> Result<Self, InvalidInput> {
+        if u16::try_from(transaction_id.len()).is_err() {
+            return Err(InvalidInput(
+                "transaction ID must not be longer than 65535 bytes".to_string(),
+            ));
+        }
+
+        if shared_secret.len() < MIN_SECRET_LENGTH {
+            return Err(InvalidInput(
+                "shared secret must be at least 8 bytes long".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            mode,
+            transaction_id,
+            first_key,
+            second_key,
+            shared_secret,
+        })
+    }
+
+    /// Decodes the binary payload of a scanned QR code.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, InvalidInput> {
+        let invalid = |reason: &str| InvalidInput(format!("invalid QR code payload: {}", reason));
+
+        if !bytes.starts_with(PREFIX) {
+            return Err(invalid("missing `MATRIX` prefix"));
+        }
+        let bytes = &bytes[PREFIX.len()..];
+
+        let (&version, bytes) = bytes
+            .split_first()
+            .ok_or_else(|| invalid("missing version"))?;
+        if version != VERSION {
+            return Err(invalid("unsupported version"));
+        }
+
+        let (&mode, bytes) = bytes.split_first().ok_or_else(|| invalid("missing mode"))?;
+        let mode = QrCodeMode::from_byte(mode).ok_or_else(|| invalid("unknown mode"))?;
+
+        if bytes.len() < 2 {
+            return Err(invalid("missing transaction ID length"));
+        }
+        let (length, bytes) = bytes.split_at(2);
+        let length = usize::from(u16::from_be_bytes([length[0], length[1]]));
+
+        if bytes.len() < length + 64 + MIN_SECRET_LENGTH {
+            return Err(invalid("payload is too short"));
+        }
+        let (transaction_id, bytes) = bytes.split_at(length);
+        let transaction_id = String::from_utf8(transaction_id.to_vec())
+            .map_err(|_| invalid("transaction ID is not valid UTF-8"))?;
+
+        let mut first_key = [0; 32];
+        first_key.copy_from_slice(&bytes[..32]);
+        let mut second_key = [0; 32];
+        second_key.copy_from_slice(&bytes[32..64]);
+
+        Ok(Self {
+            mode,
+            transaction_id,
+            first_key,
+            second_key,
+            shared_secret: bytes[64..].to_vec(),
+        })
+    }
+
+    /// Encodes the data as the binary payload of a QR code.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let transaction_id = self.transaction_id.as_bytes();
+        let length = u16::try_from(transaction_id.len())
+            .expect("transaction ID length was checked on construction");
+
+        let mut bytes = Vec::with_capacity(
+            PREFIX.len() + 4 + transaction_id.len() + 64 + self.shared_secret.len(),
+        );
+        bytes.extend_from_slice(PREFIX);
+        bytes.push(VERSION);
+        bytes.push(self.mode.to_byte());
+        bytes.extend_from_slice(&length.to_be_bytes());
+        bytes.extend_from_slice(transaction_id);
+        bytes.extend_from_slice(&self.first_key);
+        bytes.extend_from_slice(&self.second_key);
+        bytes.extend_from_slice(&self.shared_secret);
+
+        bytes
+    }
+
+    /// The mode of the QR code.
+    pub fn mode(&self) -> QrCodeMode {
+        self.mode
+    }
+
+    /// The opaque identifier of the verification process.
+    pub fn transaction_id(&self) -> &str {
+        &self.transaction_id
+    }
+
+    /// The first key, whose meaning depends on the mode.
+    pub fn first_key(&self) -> &[u8; 32] {
+        &self.first_key
+    }
+
+    /// The second key, whose meaning depends on the mode.
+    pub fn second_key(&self) -> &[u8; 32] {
+        &self.second_key
+    }
+
+    /// The shared secret, to be sent back in an *m.reciprocate.v1* start event.
+    pub fn shared_secret(&self) -> &[u8] {
+        &self.shared_secret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{QrCodeData, QrCodeMode};
+
+    fn payload() -> Vec<u8> {
+        let mut bytes = b"MATRIX\x02\x01\x00\x06$a:b.c".to_vec();
+        bytes.extend_from_slice(&[1; 32]);
+        bytes.extend_from_slice(&[2; 32]);
+        bytes.extend_from_slice(b"shared secret");
+        bytes
+    }
+
+    #[test]
+    fn encode() {
+        let data = QrCodeData::new(
+            QrCodeMode::SelfVerificationMasterKeyTrusted,
+            "$a:b.c".to_string(),
+            [1; 32],
+            [2; 32],
+            b"shared secret".to_vec(),
+        )
+        .unwrap();
+
+        assert_eq!(data.to_bytes(), payload());
+    }
+
+    #[test]
+    fn decode() {
+        let data = QrCodeData::from_bytes(&payload()).unwrap();
+
+        assert_eq!(data.mode(), QrCodeMode::SelfVerificationMasterKeyTrusted);
+        assert_eq!(data.transaction_id(), "$a:b.c");
+        assert_eq!(data.first_key(), &[1; 32]);
+        assert_eq!(data.second_key(), &[2; 32]);
+        assert_eq!(data.shared_secret(), b"shared secret");
+    }
+
+    #[test]
+    fn decode_invalid() {
+        let mut wrong_prefix = payload();
+        wrong_prefix[0] = b'N';
+        let mut wrong_version = payload();
+        wrong_version[6] = 0x01;
+        let mut wrong_mode = payload();
+        wrong_mode[7] = 0x03;
+        let mut short_secret = payload();
+        short_secret.truncate(short_secret.len() - 6);
+
+        for invalid in &[wrong_prefix, wrong_version, wrong_mode, short_secret] {
+            assert!(QrCodeData::from_bytes(invalid).is_err());
+        }
+    }
+
+    #[test]
+    fn short_secret() {
+        assert!(QrCodeData::new(
+            QrCodeMode::VerifyingAnotherUser,
+            "transaction".to_string(),
+            [1; 32],
+            [2; 32],
+            b"short".to_vec(),
+        )
+        .is_err());
+    }
+}
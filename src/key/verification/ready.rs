@@ -0,0 +1,28 @@
+//! Types for the *m.key.verification.ready* event.
+
+use ruma_events_macros::ruma_event;
+use ruma_identifiers::DeviceId;
+
+use super::VerificationMethod;
+
+ruma_event! {
+    /// Accepts a key verification request.
+    ///
+    /// Sent by a device in response to an *m.key.verification.request* event.
+    ReadyEvent {
+        kind: Event,
+        event_type: "m.key.verification.ready",
+        content: {
+            /// The device ID which is accepting the request.
+            pub from_device: DeviceId,
+
+            /// The verification methods supported by the sender.
+            pub methods: Vec<VerificationMethod>,
+
+            /// An opaque identifier for the verification request.
+            ///
+            /// Must be the same as the one used for the *m.key.verification.request* message.
+            pub transaction_id: String,
+        },
+    }
+}
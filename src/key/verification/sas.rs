@@ -0,0 +1,1071 @@
+//! A state machine for key verification using the *m.sas.v1* method.
+//!
+//! `Sas` tracks a single verification process between this device and another device. It checks
+//! that the events of the process arrive in the right order, computes and checks the hash
+//! commitment, derives the short authentication strings and calculates and checks the MACs of the
+//! devices' keys. Whenever the process has to be cancelled, it returns the content of the
+//! *m.key.verification.cancel* event that should be sent to the other device.
+//!
+//! Requires the `sas` feature.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Debug, Formatter},
+    time::{Duration, Instant},
+};
+
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use rand::rngs::OsRng;
+use ruma_identifiers::{DeviceId, UserId};
+use sha2::{Digest, Sha256};
+use x25519_dalek::{EphemeralSecret, PublicKey, SharedSecret};
+
+use super::{
+    accept::AcceptEventContent,
+    cancel::{CancelCode, CancelEventContent},
+    in_room::to_in_room_content,
+    key::KeyEventContent,
+    mac::MacEventContent,
+    start::{MSasV1Content, StartEventContent},
+    HashAlgorithm, KeyAgreementProtocol, MessageAuthenticationCode, ShortAuthenticationString,
+    VerificationMethod,
+};
+use crate::{
+    util::{decode_base64, encode_base64, to_canonical_json_string},
+    InvalidInput,
+};
+
+/// The time after which a verification process times out, counted from its start.
+const TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// The time after which a verification process times out if no event was received.
+const EVENT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The short authentication string methods supported by `Sas`.
+const SAS_METHODS: [ShortAuthenticationString; 2] = [
+    ShortAuthenticationString::Decimal,
+    ShortAuthenticationString::Emoji,
+];
+
+/// The emoji used for the emoji short authentication string, together with their English
+/// descriptions.
+const EMOJI: [(&str, &str); 64] = [
+    ("🐶", "Dog"),
+    ("🐱", "Cat"),
+    ("🦁", "Lion"),
+    ("🐎", "Horse"),
+    ("🦄", "Unicorn"),
+    ("🐷", "Pig"),
+    ("🐘", "Elephant"),
+    ("🐰", "Rabbit"),
+    ("🐼", "Panda"),
+    ("🐓", "Rooster"),
+    ("🐧", "Penguin"),
+    ("🐢", "Turtle"),
+    ("🐟", "Fish"),
+    ("🐙", "Octopus"),
+    ("🦋", "Butterfly"),
+    ("🌷", "Flower"),
+    ("🌳", "Tree"),
+    ("🌵", "Cactus"),
+    ("🍄", "Mushroom"),
+    ("🌏", "Globe"),
+    ("🌙", "Moon"),
+    ("☁️", "Cloud"),
+    ("🔥", "Fire"),
+    ("🍌", "Banana"),
+    ("🍎", "Apple"),
+    ("🍓", "Strawberry"),
+    ("🌽", "Corn"),
+    ("🍕", "Pizza"),
+    ("🎂", "Cake"),
+    ("❤️", "Heart"),
+    ("😀", "Smiley"),
+    ("🤖", "Robot"),
+    ("🎩", "Hat"),
+    ("👓", "Glasses"),
+    ("🔧", "Spanner"),
+    ("🎅", "Santa"),
+    ("👍", "Thumbs Up"),
+    ("☂️", "Umbrella"),
+    ("⌛", "Hourglass"),
+    ("⏰", "Clock"),
+    ("🎁", "Gift"),
+    ("💡", "Light Bulb"),
+    ("📕", "Book"),
+    ("✏️", "Pencil"),
+    ("📎", "Paperclip"),
+    ("✂️", "Scissors"),
+    ("🔒", "Lock"),
+    ("🔑", "Key"),
+    ("🔨", "Hammer"),
+    ("☎️", "Telephone"),
+    ("🏁", "Flag"),
+    ("🚂", "Train"),
+    ("🚲", "Bicycle"),
+    ("✈️", "Aeroplane"),
+    ("🚀", "Rocket"),
+    ("🏆", "Trophy"),
+    ("⚽", "Ball"),
+    ("🎸", "Guitar"),
+    ("🎺", "Trumpet"),
+    ("🔔", "Bell"),
+    ("⚓", "Anchor"),
+    ("🎧", "Headphones"),
+    ("📁", "Folder"),
+    ("📌", "Pin"),
+];
+
+/// How the events of a verification process are exchanged.
+///
+/// This determines the form of the *m.key.verification.start* content that the hash commitment
+/// is calculated over.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum VerificationFlow {
+    /// The events are sent as to-device events.
+    ToDevice,
+
+    /// The events are sent as room events, referencing an *m.key.verification.request* message
+    /// whose event ID is used as the transaction ID.
+    InRoom,
+}
+
+/// The state of an SAS verification process.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SasState {
+    /// This device sent *m.key.verification.start* and waits for it to be accepted.
+    Started,
+
+    /// This device received *m.key.verification.start* and has yet to accept it.
+    StartReceived,
+
+    /// This device accepted the verification and waits for the other device's key.
+    Accepted,
+
+    /// This device sent its key and waits for the other device's key.
+    KeySent,
+
+    /// The keys were exchanged and the short authentication strings can be compared.
+    ///
+    /// The process stays in this state until the user confirmed that the short authentication
+    /// strings match and the MAC of the other device has been received.
+    KeysExchanged,
+
+    /// The short authentication strings were confirmed and the MAC of the other device was valid.
+    Done,
+
+    /// The verification process was cancelled.
+    Cancelled(CancelCode),
+}
+
+/// An SAS verification process between this device and another device.
+pub struct Sas {
+    own_user_id: UserId,
+    own_device_id: DeviceId,
+    other_user_id: UserId,
+    other_device_id: DeviceId,
+    transaction_id: String,
+    we_started: bool,
+    state: SasState,
+
+    /// The canonical JSON of the *m.key.verification.start* content, as sent in the flow.
+    canonical_start: String,
+    secret: Option<EphemeralSecret>,
+    public_key: String,
+    commitment: Option<String>,
+    sas_methods: Vec<ShortAuthenticationString>,
+    shared_secret: Option<SharedSecret>,
+
+    mac_sent: bool,
+    verified_keys: Option<Vec<String>>,
+
+    started_at: Instant,
+    last_event_at: Instant,
+}
+
+impl Sas {
+    /// Starts a verification process with another device.
+    ///
+    /// Returns the state machine along with the content of the *m.key.verification.start* event
+    /// to send to the other device. For the in-room flow, the transaction ID must be the event ID
+    /// of the *m.key.verification.request* message.
+    ///
+    /// Like all methods of the state machine that take a `now` parameter, `now` is the current
+    /// point in time, which `check_timeout` measures the timeouts of the process against.
+    pub fn start(
+        own_user_id: UserId,
+        own_device_id: DeviceId,
+        other_user_id: UserId,
+        other_device_id: DeviceId,
+        transaction_id: String,
+        flow: VerificationFlow,
+        now: Instant,
+    ) -> Result<(Self, StartEventContent), InvalidInput> {
+        let content = StartEventContent::MSasV1(MSasV1Content {
+            from_device: own_device_id.clone(),
+            transaction_id: transaction_id.clone(),
+            key_agreement_protocols: vec![KeyAgreementProtocol::Curve25519],
+            hashes: vec![HashAlgorithm::Sha256],
+            message_authentication_codes: vec![MessageAuthenticationCode::HkdfHmacSha256],
+            short_authentication_string: SAS_METHODS.to_vec(),
+        });
+        let canonical_start = canonical_start(&content, flow).map_err(InvalidInput)?;
+
+        let sas = Self::new(
+            own_user_id,
+            own_device_id,
+            other_user_id,
+            other_device_id,
+            transaction_id,
+            canonical_start,
+            now,
+        );
+
+        Ok((sas, content))
+    }
+
+    /// Creates the state machine for a verification process started by another device.
+    ///
+    /// Returns the content of the *m.key.verification.cancel* event to send if the start event
+    /// doesn't use the *m.sas.v1* method or doesn't offer any supported algorithms.
+    pub fn from_start(
+        own_user_id: UserId,
+        own_device_id: DeviceId,
+        sender: UserId,
+        content: &StartEventContent,
+        flow: VerificationFlow,
+        now: Instant,
+    ) -> Result<Self, CancelEventContent> {
+        let cancel = |transaction_id: &str, code: CancelCode| {
+            CancelEventContent::with_code(transaction_id.to_string(), code)
+        };
+
+        let start = match content {
+            StartEventContent::MSasV1(start) => start,
+            StartEventContent::ReciprocateV1(reciprocate) => {
+                return Err(cancel(
+                    &reciprocate.transaction_id,
+                    CancelCode::UnknownMethod,
+                ))
+            }
+        };
+
+        if !start
+            .key_agreement_protocols
+            .contains(&KeyAgreementProtocol::Curve25519)
+            || !start.hashes.contains(&HashAlgorithm::Sha256)
+            || !start
+                .message_authentication_codes
+                .contains(&MessageAuthenticationCode::HkdfHmacSha256)
+            || !start
+                .short_authentication_string
+                .contains(&ShortAuthenticationString::Decimal)
+        {
+            return Err(cancel(&start.transaction_id, CancelCode::UnknownMethod));
+        }
+
+        let canonical_start = canonical_start(content, flow)
+            .map_err(|_| cancel(&start.transaction_id, CancelCode::InvalidMessage))?;
+
+        let mut sas = Self::new(
+            own_user_id,
+            own_device_id,
+            sender,
+            start.from_device.clone(),
+            start.transaction_id.clone(),
+            canonical_start,
+            now,
+        );
+        sas.we_started = false;
+        sas.state = SasState::StartReceived;
+        sas.sas_methods = SAS_METHODS
+            .iter()
+            .filter(|method| start.short_authentication_string.contains(method))
+            .copied()
+            .collect();
+
+        Ok(sas)
+    }
+
+    fn new(
+        own_user_id: UserId,
+        own_device_id: DeviceId,
+        other_user_id: UserId,
+        other_device_id: DeviceId,
+        transaction_id: String,
+        canonical_start: String,
+        now: Instant,
+    ) -> Self {
+        let secret = EphemeralSecret::random_from_rng(OsRng);
+        let public_key = encode_base64(PublicKey::from(&secret).as_bytes());
+
+        Self {
+            own_user_id,
+            own_device_id,
+            other_user_id,
+            other_device_id,
+            transaction_id,
+            we_started: true,
+            state: SasState::Started,
+            canonical_start,
+            secret: Some(secret),
+            public_key,
+            commitment: None,
+            sas_methods: Vec::new(),
+            shared_secret: None,
+            mac_sent: false,
+            verified_keys: None,
+            started_at: now,
+            last_event_at: now,
+        }
+    }
+
+    /// The opaque identifier of the verification process.
+    pub fn transaction_id(&self) -> &str {
+        &self.transaction_id
+    }
+
+    /// The current state of the verification process.
+    pub fn state(&self) -> &SasState {
+        &self.state
+    }
+
+    /// Accepts a verification process started by the other device.
+    ///
+    /// Returns the content of the *m.key.verification.accept* event to send to the other device,
+    /// or `None` if the process is not waiting to be accepted.
+    pub fn accept(&mut self) -> Option<AcceptEventContent> {
+        if self.state != SasState::StartReceived {
+            return None;
+        }
+
+        self.state = SasState::Accepted;
+
+        Some(AcceptEventContent {
+            transaction_id: self.transaction_id.clone(),
+            method: VerificationMethod::MSasV1,
+            key_agreement_protocol: KeyAgreementProtocol::Curve25519,
+            hash: HashAlgorithm::Sha256,
+            message_authentication_code: MessageAuthenticationCode::HkdfHmacSha256,
+            short_authentication_string: self.sas_methods.clone(),
+            commitment: commitment(&self.public_key, &self.canonical_start),
+        })
+    }
+
+    /// Handles the *m.key.verification.accept* event of the other device.
+    ///
+    /// Returns the content of the *m.key.verification.key* event to send to the other device.
+    pub fn receive_accept(
+        &mut self,
+        content: &AcceptEventContent,
+        now: Instant,
+    ) -> Result<KeyEventContent, CancelEventContent> {
+        self.receive_event(&content.transaction_id, &SasState::Started, now)?;
+
+        if content.method != VerificationMethod::MSasV1
+            || content.key_agreement_protocol != KeyAgreementProtocol::Curve25519
+            || content.hash != HashAlgorithm::Sha256
+            || content.message_authentication_code != MessageAuthenticationCode::HkdfHmacSha256
+        {
+            return Err(self.cancel_with(CancelCode::UnknownMethod));
+        }
+
+        self.sas_methods = SAS_METHODS
+            .iter()
+            .filter(|method| content.short_authentication_string.contains(method))
+            .copied()
+            .collect();
+
+        if self.sas_methods.is_empty() {
+            return Err(self.cancel_with(CancelCode::UnknownMethod));
+        }
+
+        self.commitment = Some(content.commitment.clone());
+        self.state = SasState::KeySent;
+
+        Ok(self.key_content())
+    }
+
+    /// Handles the *m.key.verification.key* event of the other device.
+    ///
+    /// If this device accepted the verification process, returns the content of the
+    /// *m.key.verification.key* event to send to the other device in response.
+    pub fn receive_key(
+        &mut self,
+        content: &KeyEventContent,
+        now: Instant,
+    ) -> Result<Option<KeyEventContent>, CancelEventContent> {
+        let expected_state = if self.we_started {
+            SasState::KeySent
+        } else {
+            SasState::Accepted
+        };
+        self.receive_event(&content.transaction_id, &expected_state, now)?;
+
+        if let Some(commitment_to_check) = &self.commitment {
+            if commitment(&content.key, &self.canonical_start) != *commitment_to_check {
+                return Err(self.cancel_with(CancelCode::MismatchedCommitment));
+            }
+        }
+
+        let their_key = match decode_public_key(&content.key) {
+            Some(key) => key,
+            None => return Err(self.cancel_with(CancelCode::InvalidMessage)),
+        };
+
+        let secret = self.secret.take().expect("the secret is only used once");
+        self.shared_secret = Some(secret.diffie_hellman(&their_key));
+        self.state = SasState::KeysExchanged;
+
+        if self.we_started {
+            Ok(None)
+        } else {
+            Ok(Some(self.key_content()))
+        }
+    }
+
+    /// The decimal short authentication string, as three numbers between 1000 and 9191.
+    ///
+    /// Returns `None` if the keys were not exchanged yet.
+    pub fn decimals(&self) -> Option<(u16, u16, u16)> {
+        self.sas_bytes().map(|bytes| sas_decimals(&bytes))
+    }
+
+    /// The emoji short authentication string, as seven pairs of an emoji and its English
+    /// description.
+    ///
+    /// Returns `None` if the keys were not exchanged yet or the other device doesn't support the
+    /// emoji method.
+    pub fn emoji(&self) -> Option<[(&'static str, &'static str); 7]> {
+        if !self.sas_methods.contains(&ShortAuthenticationString::Emoji) {
+            return None;
+        }
+
+        self.sas_bytes().map(|bytes| sas_emoji(&bytes))
+    }
+
+    /// Confirms that the short authentication strings of both devices match.
+    ///
+    /// `own_keys` maps the IDs of the keys of this device that should be verified by the other
+    /// device, e.g. `ed25519:DEVICEID`, to the keys, encoded as unpadded base64.
+    ///
+    /// Returns the content of the *m.key.verification.mac* event to send to the other device, or
+    /// `None` if the short authentication strings can't be compared or were already confirmed.
+    pub fn confirm(&mut self, own_keys: &BTreeMap<String, String>) -> Option<MacEventContent> {
+        if self.state != SasState::KeysExchanged || self.mac_sent {
+            return None;
+        }
+
+        let shared_secret = self.shared_secret.as_ref()?;
+        let info = mac_info(
+            &self.own_user_id,
+            &self.own_device_id,
+            &self.other_user_id,
+            &self.other_device_id,
+            &self.transaction_id,
+        );
+
+        let mac = own_keys
+            .iter()
+            .map(|(key_id, key)| {
+                let mac = calculate_mac(shared_secret, &format!("{}{}", info, key_id), key);
+                (key_id.clone(), mac)
+            })
+            .collect();
+        let keys = calculate_mac(
+            shared_secret,
+            &format!("{}KEY_IDS", info),
+            &key_ids(own_keys.keys()),
+        );
+
+        self.mac_sent = true;
+        self.update_done();
+
+        Some(MacEventContent {
+            transaction_id: self.transaction_id.clone(),
+            mac,
+            keys,
+        })
+    }
+
+    /// Reports that the short authentication strings of both devices don't match.
+    ///
+    /// Returns the content of the *m.key.verification.cancel* event to send to the other device.
+    pub fn mismatch(&mut self) -> CancelEventContent {
+        self.cancel_with(CancelCode::MismatchedSas)
+    }
+
+    /// Handles the *m.key.verification.mac* event of the other device.
+    ///
+    /// `other_keys` maps the IDs of the keys of the other device to the keys, encoded as unpadded
+    /// base64. Only the keys that are present in both `other_keys` and the event are verified. The
+    /// verification process is cancelled if none of the keys in the event is known.
+    pub fn receive_mac(
+        &mut self,
+        content: &MacEventContent,
+        other_keys: &BTreeMap<String, String>,
+        now: Instant,
+    ) -> Result<(), CancelEventContent> {
+        self.receive_event(&content.transaction_id, &SasState::KeysExchanged, now)?;
+
+        if self.verified_keys.is_some() {
+            return Err(self.cancel_with(CancelCode::UnexpectedMessage));
+        }
+
+        let shared_secret = self.shared_secret.as_ref().expect("keys were exchanged");
+        let info = mac_info(
+            &self.other_user_id,
+            &self.other_device_id,
+            &self.own_user_id,
+            &self.own_device_id,
+            &self.transaction_id,
+        );
+
+        let mut valid = verify_mac(
+            shared_secret,
+            &format!("{}KEY_IDS", info),
+            &key_ids(content.mac.keys()),
+            &content.keys,
+        );
+        let mut verified_keys = Vec::new();
+
+        for (key_id, mac) in &content.mac {
+            if let Some(key) = other_keys.get(key_id) {
+                valid &= verify_mac(shared_secret, &format!("{}{}", info, key_id), key, mac);
+                verified_keys.push(key_id.clone());
+            }
+        }
+
+        if !valid || verified_keys.is_empty() {
+            return Err(self.cancel_with(CancelCode::KeyMismatch));
+        }
+
+        self.verified_keys = Some(verified_keys);
+        self.update_done();
+
+        Ok(())
+    }
+
+    /// The IDs of the keys of the other device that were verified.
+    ///
+    /// Returns `None` until the verification process is done.
+    pub fn verified_keys(&self) -> Option<&[String]> {
+        if self.state != SasState::Done {
+            return None;
+        }
+
+        self.verified_keys.as_deref()
+    }
+
+    /// Cancels the verification process on behalf of the user.
+    ///
+    /// Returns the content of the *m.key.verification.cancel* event to send to the other device.
+    pub fn cancel(&mut self) -> CancelEventContent {
+        self.cancel_with(CancelCode::User)
+    }
+
+    /// Handles the *m.key.verification.cancel* event of the other device.
+    pub fn receive_cancel(&mut self, content: &CancelEventContent) {
+        if content.transaction_id == self.transaction_id && !self.is_finished() {
+            self.state = SasState::Cancelled(content.code.clone());
+        }
+    }
+
+    /// Cancels the verification process if it timed out at the given point in time.
+    ///
+    /// A verification process times out ten minutes after it was started, or one minute after
+    /// the last event of the other device was received. Returns the content of the
+    /// *m.key.verification.cancel* event to send to the other device if it timed out.
+    pub fn check_timeout(&mut self, now: Instant) -> Result<(), CancelEventContent> {
+        if self.is_finished() {
+            return Ok(());
+        }
+
+        if now.saturating_duration_since(self.started_at) > TIMEOUT
+            || now.saturating_duration_since(self.last_event_at) > EVENT_TIMEOUT
+        {
+            return Err(self.cancel_with(CancelCode::Timeout));
+        }
+
+        Ok(())
+    }
+
+    fn is_finished(&self) -> bool {
+        match self.state {
+            SasState::Done | SasState::Cancelled(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Checks that an event of the other device belongs to this process and is expected in the
+    /// current state.
+    fn receive_event(
+        &mut self,
+        transaction_id: &str,
+        expected_state: &SasState,
+        now: Instant,
+    ) -> Result<(), CancelEventContent> {
+        if transaction_id != self.transaction_id {
+            return Err(CancelEventContent::with_code(
+                transaction_id.to_string(),
+                CancelCode::UnknownTransaction,
+            ));
+        }
+
+        if let SasState::Cancelled(code) = &self.state {
+            return Err(self.cancel_content(code.clone()));
+        }
+
+        if self.state != *expected_state {
+            return Err(self.cancel_with(CancelCode::UnexpectedMessage));
+        }
+
+        self.last_event_at = now;
+
+        Ok(())
+    }
+
+    fn update_done(&mut self) {
+        if self.mac_sent && self.verified_keys.is_some() {
+            self.state = SasState::Done;
+        }
+    }
+
+    fn key_content(&self) -> KeyEventContent {
+        KeyEventContent {
+            transaction_id: self.transaction_id.clone(),
+            key: self.public_key.clone(),
+        }
+    }
+
+    fn sas_bytes(&self) -> Option<[u8; 6]> {
+        let shared_secret = self.shared_secret.as_ref()?;
+
+        let (starter, accepter) = if self.we_started {
+            (
+                (&self.own_user_id, &self.own_device_id),
+                (&self.other_user_id, &self.other_device_id),
+            )
+        } else {
+            (
+                (&self.other_user_id, &self.other_device_id),
+                (&self.own_user_id, &self.own_device_id),
+            )
+        };
+        let info = format!(
+            "MATRIX_KEY_VERIFICATION_SAS{}{}{}{}{}",
+            starter.0, starter.1, accepter.0, accepter.1, self.transaction_id
+        );
+
+        let mut bytes = [0; 6];
+        Hkdf::<Sha256>::new(None, shared_secret.as_bytes())
+            .expand(info.as_bytes(), &mut bytes)
+            .expect("6 bytes is a valid HKDF output length");
+
+        Some(bytes)
+    }
+
+    fn cancel_with(&mut self, code: CancelCode) -> CancelEventContent {
+        self.state = SasState::Cancelled(code.clone());
+        self.cancel_content(code)
+    }
+
+    fn cancel_content(&self, code: CancelCode) -> CancelEventContent {
+        CancelEventContent::with_code(self.transaction_id.clone(), code)
+    }
+}
+
+impl Debug for Sas {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sas")
+            .field("own_user_id", &self.own_user_id)
+            .field("own_device_id", &self.own_device_id)
+            .field("other_user_id", &self.other_user_id)
+            .field("other_device_id", &self.other_device_id)
+            .field("transaction_id", &self.transaction_id)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
+fn canonical_start(content: &StartEventContent, flow: VerificationFlow) -> Result<String, String> {
+    match flow {
+        VerificationFlow::ToDevice => to_canonical_json_string(content),
+        VerificationFlow::InRoom => to_canonical_json_string(&to_in_room_content(content)?),
+    }
+    .map_err(|error| error.to_string())
+}
+
+fn commitment(public_key: &str, canonical_start: &str) -> String {
+    encode_base64(Sha256::digest(
+        format!("{}{}", public_key, canonical_start).as_bytes(),
+    ))
+}
+
+fn decode_public_key(key: &str) -> Option<PublicKey> {
+    let bytes = decode_base64(key).ok()?;
+    if bytes.len() != 32 {
+        return None;
+    }
+
+    let mut key = [0; 32];
+    key.copy_from_slice(&bytes);
+
+    Some(PublicKey::from(key))
+}
+
+fn mac_info(
+    sender_user_id: &UserId,
+    sender_device_id: &str,
+    receiver_user_id: &UserId,
+    receiver_device_id: &str,
+    transaction_id: &str,
+) -> String {
+    format!(
+        "MATRIX_KEY_VERIFICATION_MAC{}{}{}{}{}",
+        sender_user_id, sender_device_id, receiver_user_id, receiver_device_id, transaction_id
+    )
+}
+
+fn key_ids<'a>(key_ids: impl Iterator<Item = &'a String>) -> String {
+    let mut key_ids: Vec<_> = key_ids.map(String::as_str).collect();
+    key_ids.sort_unstable();
+    key_ids.join(",")
+}
+
+fn mac_for(shared_secret: &SharedSecret, info: &str, input: &str) -> Hmac<Sha256> {
+    let mut key = [0; 32];
+    Hkdf::<Sha256>::new(None, shared_secret.as_bytes())
+        .expand(info.as_bytes(), &mut key)
+        .expect("32 bytes is a valid HKDF output length");
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(&key).expect("HMAC accepts keys of any size");
+    mac.update(input.as_bytes());
+    mac
+}
+
+fn calculate_mac(shared_secret: &SharedSecret, info: &str, input: &str) -> String {
+    encode_base64(mac_for(shared_secret, info, input).finalize().into_bytes())
+}
+
+fn verify_mac(shared_secret: &SharedSecret, info: &str, input: &str, expected: &str) -> bool {
+    match decode_base64(expected) {
+        Ok(expected) => mac_for(shared_secret, info, input)
+            .verify_slice(&expected)
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Splits the first 39 bits of the SAS bytes into three 13-bit numbers, offset by 1000.
+fn sas_decimals(bytes: &[u8; 6]) -> (u16, u16, u16) {
+    let bytes: Vec<u16> = bytes.iter().map(|&b| u16::from(b)).collect();
+
+    (
+        (bytes[0] << 5 | bytes[1] >> 3) + 1000,
+        ((bytes[1] & 0x7) << 10 | bytes[2] << 2 | bytes[3] >> 6) + 1000,
+        ((bytes[3] & 0x3F) << 7 | bytes[4] >> 1) + 1000,
+    )
+}
+
+/// Splits the first 42 bits of the SAS bytes into seven 6-bit indices into the emoji table.
+fn sas_emoji(bytes: &[u8; 6]) -> [(&'static str, &'static str); 7] {
+    let number = bytes
+        .iter()
+        .fold(0_u64, |number, &byte| number << 8 | u64::from(byte));
+
+    let mut emoji = [EMOJI[0]; 7];
+    for (i, slot) in emoji.iter_mut().enumerate() {
+        *slot = EMOJI[(number >> (42 - 6 * i) & 0x3F) as usize];
+    }
+
+    emoji
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        convert::TryFrom,
+        time::{Duration, Instant},
+    };
+
+    use matches::assert_matches;
+    use ruma_identifiers::UserId;
+
+    use super::{sas_decimals, sas_emoji, Sas, SasState, VerificationFlow};
+    use crate::key::verification::{
+        cancel::CancelCode, key::KeyEventContent, ShortAuthenticationString,
+    };
+
+    fn alice() -> UserId {
+        UserId::try_from("@alice:example.org").unwrap()
+    }
+
+    fn now() -> Instant {
+        Instant::now()
+    }
+
+    fn bob() -> UserId {
+        UserId::try_from("@bob:example.org").unwrap()
+    }
+
+    fn keys(device_id: &str, key: &str) -> BTreeMap<String, String> {
+        let mut keys = BTreeMap::new();
+        keys.insert(format!("ed25519:{}", device_id), key.to_string());
+        keys
+    }
+
+    /// Runs a verification process up to the key exchange.
+    fn exchange_keys(transaction_id: &str, flow: VerificationFlow) -> (Sas, Sas) {
+        let (mut alice_sas, start) = Sas::start(
+            alice(),
+            "ALICEDEVICE".to_string(),
+            bob(),
+            "BOBDEVICE".to_string(),
+            transaction_id.to_string(),
+            flow,
+            now(),
+        )
+        .unwrap();
+        let mut bob_sas =
+            Sas::from_start(bob(), "BOBDEVICE".to_string(), alice(), &start, flow, now()).unwrap();
+        assert_eq!(bob_sas.state(), &SasState::StartReceived);
+
+        let accept = bob_sas.accept().unwrap();
+        let alice_key = alice_sas.receive_accept(&accept, now()).unwrap();
+        let bob_key = bob_sas.receive_key(&alice_key, now()).unwrap().unwrap();
+        assert!(alice_sas.receive_key(&bob_key, now()).unwrap().is_none());
+
+        (alice_sas, bob_sas)
+    }
+
+    #[test]
+    fn full_verification() {
+        let (mut alice_sas, mut bob_sas) =
+            exchange_keys("S0meUniqueAndOpaqueString", VerificationFlow::ToDevice);
+
+        assert_eq!(alice_sas.state(), &SasState::KeysExchanged);
+        assert_eq!(alice_sas.decimals(), bob_sas.decimals());
+        assert_eq!(alice_sas.emoji(), bob_sas.emoji());
+        assert!(alice_sas.emoji().is_some());
+
+        let alice_keys = keys("ALICEDEVICE", "alice+ed25519+key");
+        let bob_keys = keys("BOBDEVICE", "bob+ed25519+key");
+
+        let alice_mac = alice_sas.confirm(&alice_keys).unwrap();
+        bob_sas.receive_mac(&alice_mac, &alice_keys, now()).unwrap();
+        assert_eq!(bob_sas.state(), &SasState::KeysExchanged);
+        assert!(bob_sas.verified_keys().is_none());
+
+        let bob_mac = bob_sas.confirm(&bob_keys).unwrap();
+        alice_sas.receive_mac(&bob_mac, &bob_keys, now()).unwrap();
+
+        assert_eq!(alice_sas.state(), &SasState::Done);
+        assert_eq!(bob_sas.state(), &SasState::Done);
+        assert_eq!(
+            alice_sas.verified_keys().unwrap(),
+            &["ed25519:BOBDEVICE".to_string()]
+        );
+        assert_eq!(
+            bob_sas.verified_keys().unwrap(),
+            &["ed25519:ALICEDEVICE".to_string()]
+        );
+    }
+
+    #[test]
+    fn in_room_verification() {
+        let (alice_sas, bob_sas) = exchange_keys("$request:example.org", VerificationFlow::InRoom);
+
+        assert_eq!(alice_sas.decimals(), bob_sas.decimals());
+    }
+
+    #[test]
+    fn mismatched_commitment() {
+        let (mut alice_sas, start) = Sas::start(
+            alice(),
+            "ALICEDEVICE".to_string(),
+            bob(),
+            "BOBDEVICE".to_string(),
+            "transaction".to_string(),
+            VerificationFlow::ToDevice,
+            now(),
+        )
+        .unwrap();
+        let mut bob_sas = Sas::from_start(
+            bob(),
+            "BOBDEVICE".to_string(),
+            alice(),
+            &start,
+            VerificationFlow::ToDevice,
+            now(),
+        )
+        .unwrap();
+
+        let mut accept = bob_sas.accept().unwrap();
+        accept.commitment = "bm90IHRoZSBjb21taXRtZW50".to_string();
+        let alice_key = alice_sas.receive_accept(&accept, now()).unwrap();
+        let bob_key = bob_sas.receive_key(&alice_key, now()).unwrap().unwrap();
+
+        let cancel = alice_sas.receive_key(&bob_key, now()).unwrap_err();
+        assert_eq!(cancel.code, CancelCode::MismatchedCommitment);
+        assert_eq!(cancel.transaction_id, "transaction");
+        assert_eq!(
+            alice_sas.state(),
+            &SasState::Cancelled(CancelCode::MismatchedCommitment)
+        );
+    }
+
+    #[test]
+    fn mismatched_mac() {
+        let (mut alice_sas, mut bob_sas) = exchange_keys("transaction", VerificationFlow::ToDevice);
+
+        let alice_mac = alice_sas
+            .confirm(&keys("ALICEDEVICE", "alice+ed25519+key"))
+            .unwrap();
+        let cancel = bob_sas
+            .receive_mac(
+                &alice_mac,
+                &keys("ALICEDEVICE", "mallory+ed25519+key"),
+                now(),
+            )
+            .unwrap_err();
+
+        assert_eq!(cancel.code, CancelCode::KeyMismatch);
+    }
+
+    #[test]
+    fn mac_without_known_keys() {
+        let (mut alice_sas, mut bob_sas) = exchange_keys("transaction", VerificationFlow::ToDevice);
+
+        let alice_mac = alice_sas
+            .confirm(&keys("OTHERDEVICE", "other+ed25519+key"))
+            .unwrap();
+        let cancel = bob_sas
+            .receive_mac(&alice_mac, &keys("ALICEDEVICE", "alice+ed25519+key"), now())
+            .unwrap_err();
+
+        assert_eq!(cancel.code, CancelCode::KeyMismatch);
+        assert_eq!(
+            bob_sas.state(),
+            &SasState::Cancelled(CancelCode::KeyMismatch)
+        );
+        assert!(bob_sas.verified_keys().is_none());
+    }
+
+    #[test]
+    fn unexpected_and_unknown_events() {
+        let (mut alice_sas, _) = exchange_keys("transaction", VerificationFlow::ToDevice);
+
+        let unknown = alice_sas
+            .receive_key(
+                &KeyEventContent {
+                    transaction_id: "other transaction".to_string(),
+                    key: "key".to_string(),
+                },
+                now(),
+            )
+            .unwrap_err();
+        assert_eq!(unknown.code, CancelCode::UnknownTransaction);
+        assert_eq!(unknown.transaction_id, "other transaction");
+        assert_eq!(alice_sas.state(), &SasState::KeysExchanged);
+
+        let unexpected = alice_sas
+            .receive_key(
+                &KeyEventContent {
+                    transaction_id: "transaction".to_string(),
+                    key: "key".to_string(),
+                },
+                now(),
+            )
+            .unwrap_err();
+        assert_eq!(unexpected.code, CancelCode::UnexpectedMessage);
+        assert_matches!(alice_sas.confirm(&BTreeMap::new()), None);
+    }
+
+    #[test]
+    fn timeout() {
+        let started_at = now();
+        let (mut alice_sas, _) = Sas::start(
+            alice(),
+            "ALICEDEVICE".to_string(),
+            bob(),
+            "BOBDEVICE".to_string(),
+            "transaction".to_string(),
+            VerificationFlow::ToDevice,
+            started_at,
+        )
+        .unwrap();
+
+        assert!(alice_sas
+            .check_timeout(started_at + Duration::from_secs(59))
+            .is_ok());
+
+        let cancel = alice_sas
+            .check_timeout(started_at + Duration::from_secs(61))
+            .unwrap_err();
+        assert_eq!(cancel.code, CancelCode::Timeout);
+        assert_eq!(alice_sas.state(), &SasState::Cancelled(CancelCode::Timeout));
+    }
+
+    #[test]
+    fn decimal_only() {
+        let (mut alice_sas, start) = Sas::start(
+            alice(),
+            "ALICEDEVICE".to_string(),
+            bob(),
+            "BOBDEVICE".to_string(),
+            "transaction".to_string(),
+            VerificationFlow::ToDevice,
+            now(),
+        )
+        .unwrap();
+        let mut bob_sas = Sas::from_start(
+            bob(),
+            "BOBDEVICE".to_string(),
+            alice(),
+            &start,
+            VerificationFlow::ToDevice,
+            now(),
+        )
+        .unwrap();
+
+        let mut accept = bob_sas.accept().unwrap();
+        accept.short_authentication_string = vec![ShortAuthenticationString::Decimal];
+        alice_sas.receive_accept(&accept, now()).unwrap();
+
+        assert!(alice_sas.emoji().is_none());
+    }
+
+    #[test]
+    fn sas_from_bytes() {
+        assert_eq!(sas_decimals(&[0xFF; 6]), (9191, 9191, 9191));
+        assert_eq!(sas_decimals(&[0; 6]), (1000, 1000, 1000));
+        assert_eq!(
+            sas_decimals(&[0b1010_1010, 0b1100_1100, 0x00, 0xFF, 0x01, 0x00]),
+            (6465, 5099, 9064),
+        );
+
+        assert_eq!(sas_emoji(&[0xFF; 6]), [("📌", "Pin"); 7]);
+        assert_eq!(
+            sas_emoji(&[
+                0b0000_0100,
+                0b0010_0000,
+                0b1100_0100,
+                0b0001_0100,
+                0b0110_0000,
+                0
+            ]),
+            [
+                ("🐱", "Cat"),
+                ("🦁", "Lion"),
+                ("🐎", "Horse"),
+                ("🦄", "Unicorn"),
+                ("🐷", "Pig"),
+                ("🐘", "Elephant"),
+                ("🐶", "Dog"),
+            ]
+        );
+    }
+}
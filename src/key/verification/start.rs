@@ -5,10 +5,11 @@ use serde::{Deserialize, Serialize};
 
 use super::{
     HashAlgorithm, KeyAgreementProtocol, MessageAuthenticationCode, ShortAuthenticationString,
+    VerificationMethod,
 };
 use crate::{EventType, InvalidInput, TryFromRaw};
 
-/// Begins an SAS key verification process.
+/// Begins a key verification process.
 ///
 /// Typically sent as a to-device event.
 #[derive(Clone, Debug, Serialize)]
@@ -25,6 +26,28 @@ pub enum StartEventContent {
     /// The *m.sas.v1* verification method.
     #[serde(rename = "m.sas.v1")]
     MSasV1(MSasV1Content),
+
+    /// The *m.reciprocate.v1* verification method, used after scanning a QR code.
+    #[serde(rename = "m.reciprocate.v1")]
+    ReciprocateV1(ReciprocateV1Content),
+}
+
+impl StartEventContent {
+    /// The verification method of the content.
+    pub fn method(&self) -> VerificationMethod {
+        match self {
+            StartEventContent::MSasV1(_) => VerificationMethod::MSasV1,
+            StartEventContent::ReciprocateV1(_) => VerificationMethod::ReciprocateV1,
+        }
+    }
+
+    /// The opaque identifier of the verification process.
+    pub fn transaction_id(&self) -> &str {
+        match self {
+            StartEventContent::MSasV1(content) => &content.transaction_id,
+            StartEventContent::ReciprocateV1(content) => &content.transaction_id,
+        }
+    }
 }
 
 impl TryFromRaw for StartEvent {
@@ -82,6 +105,9 @@ impl TryFromRaw for StartEventContent {
 
                 Ok(StartEventContent::MSasV1(content))
             }
+            raw::StartEventContent::ReciprocateV1(content) => {
+                Ok(StartEventContent::ReciprocateV1(content))
+            }
         }
     }
 }
@@ -89,9 +115,9 @@ impl TryFromRaw for StartEventContent {
 pub(crate) mod raw {
     use serde::Deserialize;
 
-    use super::MSasV1Content;
+    use super::{MSasV1Content, ReciprocateV1Content};
 
-    /// Begins an SAS key verification process.
+    /// Begins a key verification process.
     ///
     /// Typically sent as a to-device event.
     #[derive(Clone, Debug, Deserialize)]
@@ -107,6 +133,10 @@ pub(crate) mod raw {
         /// The *m.sas.v1* verification method.
         #[serde(rename = "m.sas.v1")]
         MSasV1(MSasV1Content),
+
+        /// The *m.reciprocate.v1* verification method, used after scanning a QR code.
+        #[serde(rename = "m.reciprocate.v1")]
+        ReciprocateV1(ReciprocateV1Content),
     }
 }
 
@@ -144,6 +174,24 @@ pub struct MSasV1Content {
     pub(crate) short_authentication_string: Vec<ShortAuthenticationString>,
 }
 
+/// The payload of an *m.key.verification.start* event using the *m.reciprocate.v1* method.
+///
+/// Sent by the device that scanned the QR code of the other device, to prove that it read the
+/// shared secret encoded in it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ReciprocateV1Content {
+    /// The device ID which is initiating the process.
+    pub from_device: DeviceId,
+
+    /// An opaque identifier for the verification process.
+    ///
+    /// Must be the same as the transaction ID encoded in the scanned QR code.
+    pub transaction_id: String,
+
+    /// The shared secret from the scanned QR code, encoded as unpadded base64.
+    pub secret: String,
+}
+
 /// Options for creating an `MSasV1Content` with `MSasV1Content::new`.
 #[derive(Clone, Debug, Deserialize)]
 pub struct MSasV1ContentOptions {
@@ -188,7 +236,7 @@ impl MSasV1Content {
     /// * `key_agreement_protocols` does not include `KeyAgreementProtocol::Curve25519`.
     /// * `hashes` does not include `HashAlgorithm::Sha256`.
     /// * `message_authentication_codes` does not include
-    /// `MessageAuthenticationCode::HkdfHmacSha256`.
+    ///   `MessageAuthenticationCode::HkdfHmacSha256`.
     /// * `short_authentication_string` does not include `ShortAuthenticationString::Decimal`.
     pub fn new(options: MSasV1ContentOptions) -> Result<Self, InvalidInput> {
         if !options
@@ -236,7 +284,8 @@ mod tests {
 
     use super::{
         HashAlgorithm, KeyAgreementProtocol, MSasV1Content, MSasV1ContentOptions,
-        MessageAuthenticationCode, ShortAuthenticationString, StartEvent, StartEventContent,
+        MessageAuthenticationCode, ReciprocateV1Content, ShortAuthenticationString, StartEvent,
+        StartEventContent,
     };
     use crate::EventJson;
 
@@ -407,6 +456,53 @@ mod tests {
         )
     }
 
+    #[test]
+    fn reciprocate_serialization() {
+        let key_verification_start = StartEvent {
+            content: StartEventContent::ReciprocateV1(ReciprocateV1Content {
+                from_device: "123".to_string(),
+                transaction_id: "456".to_string(),
+                secret: "c2hhcmVkIHNlY3JldA".to_string(),
+            }),
+        };
+
+        let json_data = json!({
+            "content": {
+                "from_device": "123",
+                "transaction_id": "456",
+                "method": "m.reciprocate.v1",
+                "secret": "c2hhcmVkIHNlY3JldA"
+            },
+            "type": "m.key.verification.start"
+        });
+
+        assert_eq!(to_json_value(&key_verification_start).unwrap(), json_data);
+    }
+
+    #[test]
+    fn reciprocate_deserialization() {
+        let json_data = json!({
+            "from_device": "123",
+            "transaction_id": "456",
+            "method": "m.reciprocate.v1",
+            "secret": "c2hhcmVkIHNlY3JldA"
+        });
+
+        assert_matches!(
+            from_json_value::<EventJson<StartEventContent>>(json_data)
+                .unwrap()
+                .deserialize()
+                .unwrap(),
+            StartEventContent::ReciprocateV1(ReciprocateV1Content {
+                from_device,
+                transaction_id,
+                secret,
+            }) if from_device == "123"
+                && transaction_id == "456"
+                && secret == "c2hhcmVkIHNlY3JldA"
+        );
+    }
+
     #[test]
     fn deserialization_failure() {
         // Ensure that invalid JSON  creates a `serde_json::Error` and not `InvalidEvent`
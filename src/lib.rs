@@ -36,7 +36,7 @@
 //!     *   `room_id`, which is a unique identifier for the room in which the event occurred
 //!     *   `sender`, which is the unique identifier of the Matrix user who created the event
 //!     *   Optionally, `unsigned`, which is a JSON object containing arbitrary additional metadata
-//!     that is not digitally signed by Matrix homeservers.
+//!         that is not digitally signed by Matrix homeservers.
 //! 3.  **State events**, which are a superset of room events and represent persistent state
 //!     specific to a room, such as the room's member list or topic.
 //!     Within a single room, state events of the same type and with the same "state key" will
@@ -47,7 +47,7 @@
 //!         You can think of a room's state events as being a `BTreeMap` where the keys are the tuple
 //!         `(event_type, state_key)`.
 //!     *   Optionally, `prev_content`, a JSON object containing the `content` object from the
-//!     previous event of the given `(event_type, state_key)` tuple in the given room.
+//!         previous event of the given `(event_type, state_key)` tuple in the given room.
 //!
 //! ruma-events represents these three event kinds as traits, allowing any Rust type to serve as a
 //! Matrix event so long as it upholds the contract expected of its kind.
@@ -108,6 +108,24 @@
 //! that event type is both an event and a room event.
 //! However, the `ruma_events::collections::only::Event` enum does *not* include *m.room.message*,
 //! because *m.room.message* implements a *more specific* event trait than `Event`.
+//!
+//! # Optional features
+//!
+//! Functionality beyond (de)serialization of events, which usually requires additional
+//! cryptographic dependencies, is gated behind the following Cargo features:
+//!
+//! *   `cross-signing`: verification of signatures made with `cross_signing::CrossSigningKey`s
+//!     and of the signatures of `device_keys::DeviceKeys` and one-time keys.
+//! *   `encrypted-attachments`: encryption and decryption of files described by
+//!     `room::EncryptedFile`.
+//! *   `key-export`: encryption and decryption of megolm session key export files containing
+//!     `forwarded_room_key::ExportedRoomKey`s.
+//! *   `sas`: the `key::verification::sas` state machine for key verification using short
+//!     authentication strings.
+//! *   `sdp`: the `call::sdp` module for parsing and rewriting the SDP session descriptions and ICE
+//!     candidates of call events.
+//! *   `secret-storage`: derivation of `secret_storage::SecretStorageKey` from a passphrase or
+//!     recovery key, and encryption and decryption of secrets stored in account data.
 
 #![recursion_limit = "1024"]
 #![warn(missing_debug_implementations, missing_docs, rust_2018_idioms)]
@@ -143,6 +161,7 @@ pub mod util;
 extern crate self as ruma_events;
 
 pub mod call;
+pub mod cross_signing;
 pub mod custom;
 /// Enums for heterogeneous collections of events.
 pub mod collections {
@@ -154,6 +173,7 @@ pub mod collections {
         pub mod only;
     }
 }
+pub mod device_keys;
 pub mod direct;
 pub mod dummy;
 pub mod forwarded_room_key;
@@ -166,6 +186,9 @@ pub mod receipt;
 pub mod room;
 pub mod room_key;
 pub mod room_key_request;
+pub mod secret;
+pub mod secret_storage;
+pub mod space;
 pub mod sticker;
 pub mod stripped;
 pub mod tag;
@@ -192,7 +192,7 @@ impl<'de> Deserialize<'de> for PushCondition {
 
                 Ok(PushCondition::SenderNotificationPermission(condition))
             }
-            unknown_kind => Err(D::Error::custom(&format!(
+            unknown_kind => Err(D::Error::custom(format!(
                 "unknown condition kind `{}`",
                 unknown_kind
             ))),
@@ -256,7 +256,7 @@ mod tests {
             "pattern": "m.notice"
         });
         assert_eq!(
-            to_json_value(&PushCondition::EventMatch(EventMatchCondition {
+            to_json_value(PushCondition::EventMatch(EventMatchCondition {
                 key: "content.msgtype".to_string(),
                 pattern: "m.notice".to_string(),
             }))
@@ -280,7 +280,7 @@ mod tests {
             "kind": "room_member_count"
         });
         assert_eq!(
-            to_json_value(&PushCondition::RoomMemberCount(RoomMemberCountCondition {
+            to_json_value(PushCondition::RoomMemberCount(RoomMemberCountCondition {
                 is: "2".to_string(),
             }))
             .unwrap(),
@@ -296,7 +296,7 @@ mod tests {
         });
         assert_eq!(
             json_data,
-            to_json_value(&PushCondition::SenderNotificationPermission(
+            to_json_value(PushCondition::SenderNotificationPermission(
                 SenderNotificationPermissionCondition {
                     key: "room".to_string(),
                 }
@@ -7,6 +7,9 @@ use std::collections::BTreeMap;
 use js_int::UInt;
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "encrypted-attachments")]
+mod attachment;
+
 pub mod aliases;
 pub mod avatar;
 pub mod canonical_alias;
@@ -27,6 +30,9 @@ pub mod third_party_invite;
 pub mod tombstone;
 pub mod topic;
 
+#[cfg(feature = "encrypted-attachments")]
+pub use self::attachment::AttachmentError;
+
 /// Metadata about an image.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ImageInfo {
@@ -80,6 +86,9 @@ pub struct ThumbnailInfo {
 }
 
 /// A file sent to a room with end-to-end encryption enabled.
+///
+/// With the `encrypted-attachments` feature, files can be encrypted and decrypted with
+/// `EncryptedFile::encrypt` and `EncryptedFile::decrypt`.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct EncryptedFile {
     /// The URL to the file.
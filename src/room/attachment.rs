@@ -0,0 +1,275 @@
+//! Encryption and decryption of attachments described by `EncryptedFile`.
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    io::{self, Read},
+};
+
+use aes::Aes256;
+use ctr::{
+    cipher::{KeyIvInit, StreamCipher},
+    Ctr128BE,
+};
+use rand::{thread_rng, RngCore};
+use sha2::{Digest, Sha256};
+
+use super::{EncryptedFile, JsonWebKey};
+use crate::util::{decode_base64, decode_base64_url, encode_base64, encode_base64_url};
+
+type Aes256Ctr = Ctr128BE<Aes256>;
+
+/// The size of an AES-256 key in bytes.
+const KEY_SIZE: usize = 32;
+
+/// The size of an AES-CTR initialization vector in bytes.
+const IV_SIZE: usize = 16;
+
+/// An error encountered while encrypting or decrypting an attachment.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum AttachmentError {
+    /// Reading the plaintext or ciphertext failed.
+    Io(io::Error),
+
+    /// The `EncryptedFile` doesn't contain a SHA-256 hash of the ciphertext.
+    MissingHash,
+
+    /// The SHA-256 hash of the ciphertext doesn't match the one in the `EncryptedFile`, or that
+    /// one is not valid base64.
+    HashMismatch,
+
+    /// The JSON Web Key is not a usable AES-256-CTR key.
+    InvalidKey,
+
+    /// The initialization vector is not valid unpadded base64 of 16 bytes.
+    InvalidIv,
+
+    /// The `v` field of the `EncryptedFile` is not `v2`.
+    UnsupportedVersion,
+}
+
+impl Display for AttachmentError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            AttachmentError::Io(error) => write!(f, "failed to read attachment: {}", error),
+            AttachmentError::MissingHash => write!(f, "missing SHA-256 hash of the ciphertext"),
+            AttachmentError::HashMismatch => write!(f, "SHA-256 hash of the ciphertext mismatch"),
+            AttachmentError::InvalidKey => write!(f, "invalid AES-256-CTR JSON web key"),
+            AttachmentError::InvalidIv => write!(f, "invalid AES-CTR initialization vector"),
+            AttachmentError::UnsupportedVersion => {
+                write!(f, "unsupported encrypted attachment version")
+            }
+        }
+    }
+}
+
+impl Error for AttachmentError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AttachmentError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AttachmentError {
+    fn from(error: io::Error) -> Self {
+        AttachmentError::Io(error)
+    }
+}
+
+impl EncryptedFile {
+    /// Decrypts the given ciphertext using the key and initialization vector of this file.
+    ///
+    /// The SHA-256 hash of the ciphertext is checked against the `sha256` entry of `hashes`
+    /// before anything is decrypted.
+    ///
+    /// Requires the `encrypted-attachments` feature.
+    pub fn decrypt(&self, mut ciphertext: impl Read) -> Result<Vec<u8>, AttachmentError> {
+        if self.v != "v2" {
+            return Err(AttachmentError::UnsupportedVersion);
+        }
+
+        let expected_hash = self
+            .hashes
+            .get("sha256")
+            .ok_or(AttachmentError::MissingHash)?;
+        let expected_hash =
+            decode_base64(expected_hash).map_err(|_| AttachmentError::HashMismatch)?;
+        let key = self.key.aes_key("decrypt")?;
+        let iv = decode_iv(&self.iv)?;
+
+        let mut data = Vec::new();
+        ciphertext.read_to_end(&mut data)?;
+
+        if Sha256::digest(&data).as_slice() != expected_hash.as_slice() {
+            return Err(AttachmentError::HashMismatch);
+        }
+
+        Aes256Ctr::new(&key.into(), &iv.into()).apply_keystream(&mut data);
+
+        Ok(data)
+    }
+
+    /// Encrypts the given plaintext with a freshly generated key and initialization vector.
+    ///
+    /// Returns the ciphertext together with an `EncryptedFile` describing how to decrypt it. The
+    /// `url` of the returned `EncryptedFile` is empty; set it to the MXC URI of the ciphertext
+    /// once it has been uploaded.
+    ///
+    /// Requires the `encrypted-attachments` feature.
+    pub fn encrypt(mut plaintext: impl Read) -> Result<(Vec<u8>, Self), AttachmentError> {
+        let mut key = [0; KEY_SIZE];
+        let mut iv = [0; IV_SIZE];
+
+        // The lower 8 bytes of the IV are the block counter, which must start at zero so that
+        // it doesn't overflow for large files.
+        thread_rng().fill_bytes(&mut key);
+        thread_rng().fill_bytes(&mut iv[..8]);
+
+        let mut data = Vec::new();
+        plaintext.read_to_end(&mut data)?;
+
+        Aes256Ctr::new(&key.into(), &iv.into()).apply_keystream(&mut data);
+
+        let file = EncryptedFile {
+            url: String::new(),
+            key: JsonWebKey {
+                kty: "oct".to_string(),
+                key_ops: vec!["encrypt".to_string(), "decrypt".to_string()],
+                alg: "A256CTR".to_string(),
+                k: encode_base64_url(key),
+                ext: true,
+            },
+            iv: encode_base64(iv),
+            hashes: vec![("sha256".to_string(), encode_base64(Sha256::digest(&data)))]
+                .into_iter()
+                .collect(),
+            v: "v2".to_string(),
+        };
+
+        Ok((data, file))
+    }
+}
+
+impl JsonWebKey {
+    /// Extracts the raw AES-256 key, checking that it may be used for the given operation.
+    fn aes_key(&self, operation: &str) -> Result<[u8; KEY_SIZE], AttachmentError> {
+        if self.kty != "oct"
+            || self.alg != "A256CTR"
+            || !self.key_ops.iter().any(|op| op == operation)
+        {
+            return Err(AttachmentError::InvalidKey);
+        }
+
+        let key = decode_base64_url(&self.k).map_err(|_| AttachmentError::InvalidKey)?;
+        if key.len() != KEY_SIZE {
+            return Err(AttachmentError::InvalidKey);
+        }
+
+        let mut bytes = [0; KEY_SIZE];
+        bytes.copy_from_slice(&key);
+
+        Ok(bytes)
+    }
+}
+
+fn decode_iv(iv: &str) -> Result<[u8; IV_SIZE], AttachmentError> {
+    let iv = decode_base64(iv).map_err(|_| AttachmentError::InvalidIv)?;
+    if iv.len() != IV_SIZE {
+        return Err(AttachmentError::InvalidIv);
+    }
+
+    let mut bytes = [0; IV_SIZE];
+    bytes.copy_from_slice(&iv);
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use matches::assert_matches;
+    use serde_json::{from_value as from_json_value, json};
+
+    use super::{AttachmentError, EncryptedFile};
+
+    #[test]
+    fn encrypt_decrypt_roundtrip() {
+        let plaintext = b"It's a secret to everybody".to_vec();
+
+        let (ciphertext, file) = EncryptedFile::encrypt(&plaintext[..]).unwrap();
+
+        assert_ne!(ciphertext, plaintext);
+        assert_eq!(file.v, "v2");
+        assert_eq!(file.key.alg, "A256CTR");
+        assert!(file.hashes.contains_key("sha256"));
+        assert_eq!(file.decrypt(&ciphertext[..]).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn decrypt_known_vector() {
+        // AES-256-CTR with an all-zero key and IV, encrypting the ASCII string "Hello".
+        let file = from_json_value::<EncryptedFile>(json!({
+            "url": "mxc://example.org/FHyPlCeYUSFFxlgbQYZmoEoe",
+            "key": {
+                "kty": "oct",
+                "key_ops": ["encrypt", "decrypt"],
+                "alg": "A256CTR",
+                "k": "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA",
+                "ext": true
+            },
+            "iv": "AAAAAAAAAAAAAAAAAAAAAA",
+            "hashes": {
+                "sha256": "YEb6qXPDm6dr0YMfNHw9jJqbOJsV1xVnBaXIkAZ2Dvo"
+            },
+            "v": "v2"
+        }))
+        .unwrap();
+
+        let ciphertext = [0x94, 0xf0, 0xac, 0x14, 0xcd];
+
+        assert_eq!(file.decrypt(&ciphertext[..]).unwrap(), b"Hello");
+    }
+
+    #[test]
+    fn decrypt_padded_hash() {
+        let (ciphertext, mut file) = EncryptedFile::encrypt(&b"attachment"[..]).unwrap();
+        file.hashes.get_mut("sha256").unwrap().push('=');
+
+        assert_eq!(file.decrypt(&ciphertext[..]).unwrap(), b"attachment");
+    }
+
+    #[test]
+    fn decrypt_tampered_ciphertext() {
+        let (mut ciphertext, file) = EncryptedFile::encrypt(&b"attachment"[..]).unwrap();
+        ciphertext[0] ^= 1;
+
+        assert_matches!(
+            file.decrypt(&ciphertext[..]),
+            Err(AttachmentError::HashMismatch)
+        );
+    }
+
+    #[test]
+    fn decrypt_missing_hash() {
+        let (ciphertext, mut file) = EncryptedFile::encrypt(&b"attachment"[..]).unwrap();
+        file.hashes.clear();
+
+        assert_matches!(
+            file.decrypt(&ciphertext[..]),
+            Err(AttachmentError::MissingHash)
+        );
+    }
+
+    #[test]
+    fn decrypt_invalid_key() {
+        let (ciphertext, mut file) = EncryptedFile::encrypt(&b"attachment"[..]).unwrap();
+        file.key.alg = "A128CTR".to_string();
+
+        assert_matches!(
+            file.decrypt(&ciphertext[..]),
+            Err(AttachmentError::InvalidKey)
+        );
+    }
+}
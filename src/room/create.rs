@@ -1,6 +1,9 @@
 //! Types for the *m.room.create* event.
 
-use std::convert::TryFrom;
+use std::{
+    convert::TryFrom,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
 
 use ruma_events_macros::ruma_event;
 use ruma_identifiers::{EventId, RoomId, RoomVersionId, UserId};
@@ -31,6 +34,10 @@ ruma_event! {
             /// A reference to the room this room replaces, if the previous room was upgraded.
             #[serde(skip_serializing_if = "Option::is_none")]
             pub predecessor: Option<PreviousRoom>,
+
+            /// The type of the room, e.g. `m.space`. Rooms without a type are regular rooms.
+            #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+            pub room_type: Option<RoomType>,
         },
     }
 }
@@ -45,6 +52,47 @@ pub struct PreviousRoom {
     pub event_id: EventId,
 }
 
+/// The type of a room.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[non_exhaustive]
+#[serde(from = "String", into = "String")]
+pub enum RoomType {
+    /// A space, which groups other rooms with *m.space.child* events.
+    Space,
+
+    /// Any room type that is not part of the specification.
+    Custom(String),
+}
+
+impl Display for RoomType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let room_type_str = match *self {
+            RoomType::Space => "m.space",
+            RoomType::Custom(ref room_type) => room_type,
+        };
+
+        write!(f, "{}", room_type_str)
+    }
+}
+
+impl<T> From<T> for RoomType
+where
+    T: Into<String> + AsRef<str>,
+{
+    fn from(s: T) -> RoomType {
+        match s.as_ref() {
+            "m.space" => RoomType::Space,
+            _ => RoomType::Custom(s.into()),
+        }
+    }
+}
+
+impl From<RoomType> for String {
+    fn from(room_type: RoomType) -> String {
+        room_type.to_string()
+    }
+}
+
 /// Used to default the `room_version` field to room version 1.
 fn default_room_version_id() -> RoomVersionId {
     RoomVersionId::try_from("1").unwrap()
@@ -58,7 +106,7 @@ mod tests {
     use ruma_identifiers::{RoomVersionId, UserId};
     use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
 
-    use super::CreateEventContent;
+    use super::{CreateEventContent, RoomType};
     use crate::EventJson;
 
     #[test]
@@ -68,6 +116,7 @@ mod tests {
             federate: false,
             room_version: RoomVersionId::version_4(),
             predecessor: None,
+            room_type: None,
         };
 
         let json = json!({
@@ -97,8 +146,41 @@ mod tests {
                 federate: true,
                 room_version,
                 predecessor: None,
+                room_type: None,
             } if creator == "@carl:example.com"
                 && room_version == RoomVersionId::version_4()
         );
     }
+
+    #[test]
+    fn space_serde() {
+        let content = CreateEventContent {
+            creator: UserId::try_from("@carl:example.com").unwrap(),
+            federate: true,
+            room_version: RoomVersionId::version_6(),
+            predecessor: None,
+            room_type: Some(RoomType::Space),
+        };
+        let json = json!({
+            "creator": "@carl:example.com",
+            "room_version": "6",
+            "type": "m.space"
+        });
+
+        assert_eq!(to_json_value(&content).unwrap(), json);
+        assert_matches!(
+            from_json_value::<EventJson<CreateEventContent>>(json)
+                .unwrap()
+                .deserialize()
+                .unwrap(),
+            CreateEventContent {
+                room_type: Some(RoomType::Space),
+                ..
+            }
+        );
+        assert_eq!(
+            RoomType::from("org.example.directory"),
+            RoomType::Custom("org.example.directory".to_string())
+        );
+    }
 }
@@ -1,6 +1,10 @@
 //! Types for the *m.room.encrypted* event.
 
-use std::{collections::BTreeMap, time::SystemTime};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter, Result as FmtResult},
+    time::SystemTime,
+};
 
 use js_int::UInt;
 use ruma_identifiers::{DeviceId, EventId, RoomId, UserId};
@@ -8,6 +12,16 @@ use serde::{Deserialize, Serialize};
 
 use crate::{EventType, FromRaw, UnsignedData};
 
+mod curve25519;
+mod decryption;
+mod olm;
+
+pub use self::{
+    curve25519::Curve25519Key,
+    decryption::{DecryptionError, Decryptor},
+    olm::{DecryptedOlmV1Event, OlmV1Keys},
+};
+
 /// This event type is used when sending encrypted events.
 ///
 /// This type is to be used within a room. For a to-device event, use `EncryptedEventContent`
@@ -141,12 +155,49 @@ pub(crate) mod raw {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OlmV1Curve25519AesSha2Content {
     /// A map from the recipient Curve25519 identity key to ciphertext information.
-    pub ciphertext: BTreeMap<String, CiphertextInfo>,
+    pub ciphertext: BTreeMap<Curve25519Key, CiphertextInfo>,
 
     /// The Curve25519 key of the sender.
     pub sender_key: String,
 }
 
+impl OlmV1Curve25519AesSha2Content {
+    /// Creates an empty payload for the given sender key, without ciphertexts.
+    ///
+    /// Use `with_ciphertext` to add the ciphertext for each of the recipient devices.
+    pub fn new(sender_key: String) -> Self {
+        Self {
+            ciphertext: BTreeMap::new(),
+            sender_key,
+        }
+    }
+
+    /// Adds the ciphertext for the recipient device with the given Curve25519 identity key.
+    ///
+    /// If a ciphertext for that device was added before, it is replaced.
+    pub fn with_ciphertext(mut self, recipient_key: Curve25519Key, info: CiphertextInfo) -> Self {
+        self.ciphertext.insert(recipient_key, info);
+        self
+    }
+
+    /// Adds a ciphertext for each of the given recipient devices.
+    ///
+    /// `encrypt` is called with the Curve25519 identity key of each device and returns the
+    /// ciphertext for it.
+    pub fn with_recipients<I, F>(mut self, recipient_keys: I, mut encrypt: F) -> Self
+    where
+        I: IntoIterator<Item = Curve25519Key>,
+        F: FnMut(&Curve25519Key) -> CiphertextInfo,
+    {
+        for recipient_key in recipient_keys {
+            let info = encrypt(&recipient_key);
+            self.ciphertext.insert(recipient_key, info);
+        }
+
+        self
+    }
+}
+
 /// Ciphertext information holding the ciphertext and message type.
 ///
 /// Used for messages encrypted with the *m.olm.v1.curve25519-aes-sha2* algorithm.
@@ -157,7 +208,54 @@ pub struct CiphertextInfo {
 
     /// The Olm message type.
     #[serde(rename = "type")]
-    pub message_type: UInt,
+    pub message_type: OlmMessageType,
+}
+
+impl CiphertextInfo {
+    /// Creates a new `CiphertextInfo` with the given body and message type.
+    pub fn new(body: String, message_type: OlmMessageType) -> Self {
+        Self { body, message_type }
+    }
+}
+
+/// The type of an Olm message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(from = "UInt", into = "UInt")]
+pub enum OlmMessageType {
+    /// A pre-key message, which establishes a new Olm session.
+    PreKey,
+
+    /// A normal message, sent in an established Olm session.
+    Normal,
+
+    /// Any message type that is not part of the specification.
+    Custom(UInt),
+}
+
+impl Display for OlmMessageType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", UInt::from(*self))
+    }
+}
+
+impl From<UInt> for OlmMessageType {
+    fn from(message_type: UInt) -> Self {
+        match u64::from(message_type) {
+            0 => OlmMessageType::PreKey,
+            1 => OlmMessageType::Normal,
+            _ => OlmMessageType::Custom(message_type),
+        }
+    }
+}
+
+impl From<OlmMessageType> for UInt {
+    fn from(message_type: OlmMessageType) -> UInt {
+        match message_type {
+            OlmMessageType::PreKey => UInt::from(0_u32),
+            OlmMessageType::Normal => UInt::from(1_u32),
+            OlmMessageType::Custom(message_type) => message_type,
+        }
+    }
 }
 
 /// The payload for `EncryptedEvent` using the *m.megolm.v1.aes-sha2* algorithm.
@@ -178,12 +276,22 @@ pub struct MegolmV1AesSha2Content {
 
 #[cfg(test)]
 mod tests {
+    use std::convert::TryFrom;
+
+    use js_int::UInt;
     use matches::assert_matches;
     use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
 
-    use super::{EncryptedEventContent, MegolmV1AesSha2Content};
+    use super::{
+        CiphertextInfo, Curve25519Key, EncryptedEventContent, MegolmV1AesSha2Content,
+        OlmMessageType, OlmV1Curve25519AesSha2Content,
+    };
     use crate::EventJson;
 
+    fn curve25519_key() -> Curve25519Key {
+        Curve25519Key::try_from("3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI").unwrap()
+    }
+
     #[test]
     fn serialization() {
         let key_verification_start_content =
@@ -240,7 +348,7 @@ mod tests {
         let json_data = json!({
             "sender_key": "test_key",
             "ciphertext": {
-                "test_curve_key": {
+                "3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI": {
                     "body": "encrypted_body",
                     "type": 1
                 }
@@ -256,8 +364,9 @@ mod tests {
             EncryptedEventContent::OlmV1Curve25519AesSha2(c) => {
                 assert_eq!(c.sender_key, "test_key");
                 assert_eq!(c.ciphertext.len(), 1);
-                assert_eq!(c.ciphertext["test_curve_key"].body, "encrypted_body");
-                assert_eq!(c.ciphertext["test_curve_key"].message_type, 1u16.into());
+                let info = &c.ciphertext[&curve25519_key()];
+                assert_eq!(info.body, "encrypted_body");
+                assert_eq!(info.message_type, OlmMessageType::Normal);
             }
             _ => panic!("Wrong content type, expected a OlmV1 content"),
         }
@@ -272,4 +381,62 @@ mod tests {
         .deserialize()
         .is_err());
     }
+
+    #[test]
+    fn message_type_serde() {
+        assert_eq!(to_json_value(OlmMessageType::PreKey).unwrap(), json!(0));
+        assert_eq!(
+            from_json_value::<OlmMessageType>(json!(1)).unwrap(),
+            OlmMessageType::Normal
+        );
+        assert_eq!(
+            from_json_value::<OlmMessageType>(json!(2)).unwrap(),
+            OlmMessageType::Custom(UInt::from(2_u32))
+        );
+    }
+
+    #[test]
+    fn olm_content_for_multiple_recipients() {
+        let other_key =
+            Curve25519Key::try_from("lEuiRJBit0IG6nUf5pUzWTUEsRVVe/HJkoKuEww9ULI").unwrap();
+        let content = OlmV1Curve25519AesSha2Content::new("sender_key".to_string())
+            .with_recipients(vec![curve25519_key(), other_key], |key| {
+                CiphertextInfo::new(format!("ciphertext for {}", key), OlmMessageType::PreKey)
+            });
+
+        assert_eq!(
+            to_json_value(EncryptedEventContent::OlmV1Curve25519AesSha2(content)).unwrap(),
+            json!({
+                "algorithm": "m.olm.v1.curve25519-aes-sha2",
+                "sender_key": "sender_key",
+                "ciphertext": {
+                    "3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI": {
+                        "body": "ciphertext for 3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI",
+                        "type": 0
+                    },
+                    "lEuiRJBit0IG6nUf5pUzWTUEsRVVe/HJkoKuEww9ULI": {
+                        "body": "ciphertext for lEuiRJBit0IG6nUf5pUzWTUEsRVVe/HJkoKuEww9ULI",
+                        "type": 0
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn olm_content_invalid_recipient_key() {
+        assert!(from_json_value::<EventJson<EncryptedEventContent>>(json!({
+            "algorithm": "m.olm.v1.curve25519-aes-sha2",
+            "sender_key": "sender_key",
+            "ciphertext": {
+                "not_a_curve25519_key": {
+                    "body": "encrypted_body",
+                    "type": 1
+                }
+            }
+        }))
+        .unwrap()
+        .deserialize()
+        .is_err());
+    }
 }
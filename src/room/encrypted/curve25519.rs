@@ -0,0 +1,114 @@
+//! A validated Curve25519 public key.
+
+use std::{
+    convert::TryFrom,
+    fmt::{self, Display, Formatter},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::InvalidInput;
+
+/// The length of an unpadded base64 encoding of 32 bytes.
+const ENCODED_LENGTH: usize = 43;
+
+/// A Curve25519 public key, encoded as unpadded base64, e.g. a device's identity key.
+///
+/// The key is validated to be the canonical unpadded base64 encoding of 32 bytes when parsing or
+/// deserializing a `Curve25519Key`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Curve25519Key(String);
+
+impl Curve25519Key {
+    /// Returns the base64 encoding of the key.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Curve25519Key {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for Curve25519Key {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for Curve25519Key {
+    type Error = InvalidInput;
+
+    fn try_from(key: String) -> Result<Self, InvalidInput> {
+        let is_base64 = |c: u8| c.is_ascii_alphanumeric() || c == b'+' || c == b'/';
+
+        if key.len() != ENCODED_LENGTH || !key.bytes().all(is_base64) {
+            return Err(InvalidInput(
+                "Curve25519 key must be 32 bytes encoded as unpadded base64".to_string(),
+            ));
+        }
+
+        // The last character only encodes 4 bits of the key, the remaining 2 bits must be zero.
+        if !b"AEIMQUYcgkosw048".contains(&key.as_bytes()[ENCODED_LENGTH - 1]) {
+            return Err(InvalidInput(
+                "Curve25519 key is not canonically encoded".to_string(),
+            ));
+        }
+
+        Ok(Self(key))
+    }
+}
+
+impl<'a> TryFrom<&'a str> for Curve25519Key {
+    type Error = InvalidInput;
+
+    fn try_from(key: &'a str) -> Result<Self, InvalidInput> {
+        Self::try_from(key.to_string())
+    }
+}
+
+impl From<Curve25519Key> for String {
+    fn from(key: Curve25519Key) -> String {
+        key.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
+
+    use super::Curve25519Key;
+
+    #[test]
+    fn valid_key() {
+        let key = Curve25519Key::try_from("3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI").unwrap();
+
+        assert_eq!(
+            to_json_value(&key).unwrap(),
+            json!("3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI")
+        );
+        assert_eq!(
+            from_json_value::<Curve25519Key>(json!("3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI"))
+                .unwrap(),
+            key
+        );
+    }
+
+    #[test]
+    fn invalid_keys() {
+        // Padded.
+        assert!(Curve25519Key::try_from("3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI=").is_err());
+        // Too short.
+        assert!(Curve25519Key::try_from("3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgy").is_err());
+        // URL-safe alphabet.
+        assert!(Curve25519Key::try_from("3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArz_yI").is_err());
+        // Non-zero trailing bits.
+        assert!(Curve25519Key::try_from("3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyJ").is_err());
+        assert!(from_json_value::<Curve25519Key>(json!("sender_key")).is_err());
+    }
+}
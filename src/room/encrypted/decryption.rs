@@ -0,0 +1,423 @@
+//! Decryption of *m.room.encrypted* events into the events they contain.
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use ruma_identifiers::{RoomId, UserId};
+use serde::Deserialize;
+use serde_json::{value::RawValue, Map as JsonMap, Value as JsonValue};
+
+use super::{
+    DecryptedOlmV1Event, EncryptedEvent, EncryptedEventContent, MegolmV1AesSha2Content,
+    OlmV1Curve25519AesSha2Content,
+};
+use crate::{
+    collections::all::RoomEvent, to_device::ToDeviceEncrypted, EventJson, InvalidEvent, TryFromRaw,
+};
+
+/// A cryptographic library that can decrypt the ciphertext of *m.room.encrypted* events.
+///
+/// Implementations only decrypt the ciphertext and return the plaintext, which is the JSON
+/// encoding of the event. Parsing the plaintext and checking it against the envelope it arrived
+/// in is done by `EncryptedEvent::decrypt` and `ToDeviceEncrypted::decrypt`.
+pub trait Decryptor {
+    /// Decrypts a payload encrypted with *m.megolm.v1.aes-sha2* in the given room.
+    fn decrypt_megolm(
+        &self,
+        room_id: &RoomId,
+        content: &MegolmV1AesSha2Content,
+    ) -> Result<String, Box<dyn Error + Send + Sync>>;
+
+    /// Decrypts a payload encrypted with *m.olm.v1.curve25519-aes-sha2* by the given sender.
+    ///
+    /// Implementations are expected to pick the ciphertext addressed to the own device's identity
+    /// key.
+    fn decrypt_olm(
+        &self,
+        sender: &UserId,
+        content: &OlmV1Curve25519AesSha2Content,
+    ) -> Result<String, Box<dyn Error + Send + Sync>>;
+}
+
+/// An error encountered while decrypting an *m.room.encrypted* event.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DecryptionError {
+    /// The `Decryptor` failed to decrypt the ciphertext.
+    Decryptor(Box<dyn Error + Send + Sync>),
+
+    /// The event was encrypted with an algorithm that can't be used for its kind of event, e.g.
+    /// a to-device event encrypted with *m.megolm.v1.aes-sha2*.
+    UnsupportedAlgorithm,
+
+    /// The room event doesn't contain the ID of the room it was sent in.
+    MissingRoomId,
+
+    /// The plaintext is not a JSON object with `type` and `content` fields, or an Olm payload
+    /// without the `sender`, `recipient`, `keys` and `recipient_keys` fields.
+    InvalidPayload(serde_json::Error),
+
+    /// The `room_id` of the plaintext is missing or doesn't match the room the event was sent in.
+    RoomIdMismatch,
+
+    /// The `sender` of the plaintext is missing or doesn't match the sender of the event.
+    SenderMismatch,
+
+    /// The Ed25519 key of the sender in the plaintext doesn't match the device that owns the
+    /// envelope's `sender_key`.
+    SenderKeyMismatch,
+
+    /// The plaintext is addressed to a different user.
+    RecipientMismatch,
+
+    /// The plaintext is addressed to a different device.
+    RecipientKeyMismatch,
+
+    /// The decrypted event is invalid.
+    InvalidEvent(InvalidEvent),
+}
+
+impl Display for DecryptionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            DecryptionError::Decryptor(error) => write!(f, "failed to decrypt event: {}", error),
+            DecryptionError::UnsupportedAlgorithm => write!(f, "unsupported algorithm"),
+            DecryptionError::MissingRoomId => write!(f, "missing room ID"),
+            DecryptionError::InvalidPayload(error) => {
+                write!(f, "invalid decrypted payload: {}", error)
+            }
+            DecryptionError::RoomIdMismatch => write!(f, "room ID of decrypted payload mismatch"),
+            DecryptionError::SenderMismatch => write!(f, "sender of decrypted payload mismatch"),
+            DecryptionError::SenderKeyMismatch => {
+                write!(f, "sender key of decrypted payload mismatch")
+            }
+            DecryptionError::RecipientMismatch => {
+                write!(f, "recipient of decrypted payload mismatch")
+            }
+            DecryptionError::RecipientKeyMismatch => {
+                write!(f, "recipient key of decrypted payload mismatch")
+            }
+            DecryptionError::InvalidEvent(error) => write!(f, "invalid decrypted event: {}", error),
+        }
+    }
+}
+
+impl Error for DecryptionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DecryptionError::Decryptor(error) => Some(&**error),
+            DecryptionError::InvalidPayload(error) => Some(error),
+            DecryptionError::InvalidEvent(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// The fields of a decrypted payload that are checked against the envelope.
+#[derive(Deserialize)]
+struct DecryptedPayload {
+    #[serde(rename = "type")]
+    event_type: String,
+    content: JsonValue,
+    room_id: Option<RoomId>,
+    sender: Option<UserId>,
+}
+
+impl EncryptedEvent {
+    /// Decrypts the event and returns the room event it contains.
+    ///
+    /// The `event_id`, `sender`, `origin_server_ts`, `room_id` and `unsigned` fields are taken
+    /// from this event. For *m.megolm.v1.aes-sha2*, the `room_id` of the plaintext must match the
+    /// room of this event; for *m.olm.v1.curve25519-aes-sha2*, the `sender` of the plaintext must
+    /// match the sender of this event.
+    pub fn decrypt(&self, decryptor: &dyn Decryptor) -> Result<RoomEvent, DecryptionError> {
+        let room_id = self
+            .room_id
+            .as_ref()
+            .ok_or(DecryptionError::MissingRoomId)?;
+
+        let payload = match &self.content {
+            EncryptedEventContent::MegolmV1AesSha2(content) => {
+                let payload = parse(decryptor.decrypt_megolm(room_id, content))?;
+                if payload.room_id.as_ref() != Some(room_id) {
+                    return Err(DecryptionError::RoomIdMismatch);
+                }
+                payload
+            }
+            EncryptedEventContent::OlmV1Curve25519AesSha2(content) => {
+                let payload = parse(decryptor.decrypt_olm(&self.sender, content))?;
+                if payload.sender.as_ref() != Some(&self.sender) {
+                    return Err(DecryptionError::SenderMismatch);
+                }
+                payload
+            }
+        };
+
+        let mut event = match serde_json::to_value(self) {
+            Ok(JsonValue::Object(event)) => event,
+            _ => unreachable!("EncryptedEvent always serializes to a JSON object"),
+        };
+        event.insert("type".to_string(), payload.event_type.into());
+        event.insert("content".to_string(), payload.content);
+
+        to_event(event)
+    }
+}
+
+impl ToDeviceEncrypted {
+    /// Decrypts the to-device event and returns the Olm payload, which contains the to-device
+    /// event.
+    ///
+    /// To-device events can only be encrypted with *m.olm.v1.curve25519-aes-sha2*. The payload is
+    /// checked with `DecryptedOlmV1Event::validate`: `sender_ed25519_key` is the Ed25519 key of the
+    /// device that owns the `sender_key` of this event, and `own_user_id` and `own_ed25519_key`
+    /// identify the device that decrypts it.
+    pub fn decrypt(
+        &self,
+        decryptor: &dyn Decryptor,
+        sender_ed25519_key: &str,
+        own_user_id: &UserId,
+        own_ed25519_key: &str,
+    ) -> Result<DecryptedOlmV1Event, DecryptionError> {
+        let content = match &self.content {
+            EncryptedEventContent::OlmV1Curve25519AesSha2(content) => content,
+            EncryptedEventContent::MegolmV1AesSha2(_) => {
+                return Err(DecryptionError::UnsupportedAlgorithm)
+            }
+        };
+
+        let plaintext = decryptor
+            .decrypt_olm(&self.sender, content)
+            .map_err(DecryptionError::Decryptor)?;
+        let payload: DecryptedOlmV1Event =
+            serde_json::from_str(&plaintext).map_err(DecryptionError::InvalidPayload)?;
+        payload.validate(self, sender_ed25519_key, own_user_id, own_ed25519_key)?;
+
+        Ok(payload)
+    }
+}
+
+fn parse(
+    plaintext: Result<String, Box<dyn Error + Send + Sync>>,
+) -> Result<DecryptedPayload, DecryptionError> {
+    let plaintext = plaintext.map_err(DecryptionError::Decryptor)?;
+    serde_json::from_str(&plaintext).map_err(DecryptionError::InvalidPayload)
+}
+
+fn to_event<T: TryFromRaw>(event: JsonMap<String, JsonValue>) -> Result<T, DecryptionError> {
+    let json = RawValue::from_string(JsonValue::Object(event).to_string())
+        .expect("serialized JSON value is valid JSON");
+
+    EventJson::<T>::from(json)
+        .deserialize()
+        .map_err(DecryptionError::InvalidEvent)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::TryFrom,
+        error::Error,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use maplit::btreemap;
+    use matches::assert_matches;
+    use ruma_identifiers::{EventId, RoomId, UserId};
+    use serde_json::json;
+
+    use super::{DecryptionError, Decryptor};
+    use crate::{
+        collections::all::RoomEvent,
+        room::encrypted::{
+            CiphertextInfo, Curve25519Key, EncryptedEvent, EncryptedEventContent,
+            MegolmV1AesSha2Content, OlmMessageType, OlmV1Curve25519AesSha2Content,
+        },
+        to_device::{AnyToDeviceEvent, ToDeviceEncrypted},
+        RoomEvent as _, UnsignedData,
+    };
+
+    fn bob() -> UserId {
+        UserId::try_from("@bob:example.org").unwrap()
+    }
+
+    fn own_identity_key() -> Curve25519Key {
+        Curve25519Key::try_from("3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI").unwrap()
+    }
+
+    /// A decryptor whose ciphertexts are the plaintexts.
+    struct PlaintextDecryptor;
+
+    impl Decryptor for PlaintextDecryptor {
+        fn decrypt_megolm(
+            &self,
+            _room_id: &RoomId,
+            content: &MegolmV1AesSha2Content,
+        ) -> Result<String, Box<dyn Error + Send + Sync>> {
+            Ok(content.ciphertext.clone())
+        }
+
+        fn decrypt_olm(
+            &self,
+            _sender: &UserId,
+            content: &OlmV1Curve25519AesSha2Content,
+        ) -> Result<String, Box<dyn Error + Send + Sync>> {
+            content
+                .ciphertext
+                .get(&own_identity_key())
+                .map(|info| info.body.clone())
+                .ok_or_else(|| "no ciphertext for this device".into())
+        }
+    }
+
+    fn megolm_event(plaintext: serde_json::Value) -> EncryptedEvent {
+        EncryptedEvent {
+            content: EncryptedEventContent::MegolmV1AesSha2(MegolmV1AesSha2Content {
+                ciphertext: plaintext.to_string(),
+                sender_key: "sender_key".to_string(),
+                device_id: "device_id".to_string(),
+                session_id: "session_id".to_string(),
+            }),
+            event_id: EventId::try_from("$h29iv0s8:example.com").unwrap(),
+            origin_server_ts: UNIX_EPOCH + Duration::from_millis(1),
+            room_id: Some(RoomId::try_from("!roomid:room.com").unwrap()),
+            sender: UserId::try_from("@carl:example.com").unwrap(),
+            unsigned: UnsignedData::default(),
+        }
+    }
+
+    fn olm_to_device_event(plaintext: serde_json::Value) -> ToDeviceEncrypted {
+        ToDeviceEncrypted {
+            sender: UserId::try_from("@alice:example.org").unwrap(),
+            content: EncryptedEventContent::OlmV1Curve25519AesSha2(OlmV1Curve25519AesSha2Content {
+                ciphertext: btreemap! {
+                    own_identity_key() => CiphertextInfo {
+                        body: plaintext.to_string(),
+                        message_type: OlmMessageType::PreKey,
+                    },
+                },
+                sender_key: "sender_key".to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn decrypt_megolm_room_event() {
+        let event = megolm_event(json!({
+            "type": "m.room.message",
+            "content": {
+                "msgtype": "m.text",
+                "body": "It's a secret to everybody"
+            },
+            "room_id": "!roomid:room.com"
+        }));
+
+        let decrypted = event.decrypt(&PlaintextDecryptor).unwrap();
+        assert_matches!(&decrypted, RoomEvent::RoomMessage(_));
+        if let RoomEvent::RoomMessage(message) = decrypted {
+            assert_eq!(message.event_id(), &event.event_id);
+            assert_eq!(message.sender(), &event.sender);
+            assert_eq!(message.origin_server_ts(), event.origin_server_ts);
+            assert_eq!(message.room_id(), event.room_id.as_ref());
+        }
+    }
+
+    #[test]
+    fn decrypt_megolm_room_id_mismatch() {
+        let event = megolm_event(json!({
+            "type": "m.room.message",
+            "content": {
+                "msgtype": "m.text",
+                "body": "It's a secret to everybody"
+            },
+            "room_id": "!otherroom:room.com"
+        }));
+
+        assert_matches!(
+            event.decrypt(&PlaintextDecryptor),
+            Err(DecryptionError::RoomIdMismatch)
+        );
+    }
+
+    #[test]
+    fn decrypt_olm_to_device_event() {
+        let event = olm_to_device_event(json!({
+            "type": "m.room_key",
+            "content": {
+                "algorithm": "m.megolm.v1.aes-sha2",
+                "room_id": "!roomid:room.com",
+                "session_id": "session_id",
+                "session_key": "session_key"
+            },
+            "sender": "@alice:example.org",
+            "recipient": "@bob:example.org",
+            "recipient_keys": { "ed25519": "bob_ed25519_key" },
+            "keys": { "ed25519": "alice_ed25519_key" }
+        }));
+
+        let payload = event
+            .decrypt(
+                &PlaintextDecryptor,
+                "alice_ed25519_key",
+                &bob(),
+                "bob_ed25519_key",
+            )
+            .unwrap();
+        assert_matches!(
+            payload.event,
+            AnyToDeviceEvent::RoomKey(room_key) if room_key.sender == event.sender
+        );
+        assert_matches!(
+            event.decrypt(
+                &PlaintextDecryptor,
+                "alice_ed25519_key",
+                &bob(),
+                "other_ed25519_key"
+            ),
+            Err(DecryptionError::RecipientKeyMismatch)
+        );
+    }
+
+    #[test]
+    fn decrypt_olm_sender_mismatch() {
+        let event = olm_to_device_event(json!({
+            "type": "m.dummy",
+            "content": {},
+            "sender": "@mallory:example.org",
+            "recipient": "@bob:example.org",
+            "recipient_keys": { "ed25519": "bob_ed25519_key" },
+            "keys": { "ed25519": "alice_ed25519_key" }
+        }));
+
+        assert_matches!(
+            event.decrypt(
+                &PlaintextDecryptor,
+                "alice_ed25519_key",
+                &bob(),
+                "bob_ed25519_key"
+            ),
+            Err(DecryptionError::SenderMismatch)
+        );
+    }
+
+    #[test]
+    fn decrypt_olm_without_recipient() {
+        let event = olm_to_device_event(json!({
+            "type": "m.dummy",
+            "content": {},
+            "sender": "@alice:example.org"
+        }));
+
+        assert_matches!(
+            event.decrypt(
+                &PlaintextDecryptor,
+                "alice_ed25519_key",
+                &bob(),
+                "bob_ed25519_key"
+            ),
+            Err(DecryptionError::InvalidPayload(_))
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! The plaintext payload of *m.olm.v1.curve25519-aes-sha2* encrypted events.
+
+use ruma_identifiers::UserId;
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize};
+use serde_json::{value::RawValue, Value as JsonValue};
+
+use super::{DecryptionError, EncryptedEventContent};
+use crate::{
+    to_device::{AnyToDeviceEvent, ToDeviceEncrypted},
+    EventJson,
+};
+
+/// The plaintext of an *m.olm.v1.curve25519-aes-sha2* ciphertext.
+///
+/// Besides the event itself, the payload names the sender and the recipient along with their
+/// Ed25519 keys, so that an attacker can't forward a message to a different device or claim a
+/// message from another device as their own. Use `validate` to check them.
+#[derive(Clone, Debug)]
+pub struct DecryptedOlmV1Event {
+    /// The user ID of the sender.
+    pub sender: UserId,
+
+    /// The user ID of the intended recipient.
+    pub recipient: UserId,
+
+    /// The Ed25519 key of the intended recipient's device.
+    pub recipient_keys: OlmV1Keys,
+
+    /// The Ed25519 key of the sender's device.
+    pub keys: OlmV1Keys,
+
+    /// The encrypted to-device event.
+    pub event: AnyToDeviceEvent,
+}
+
+/// The public keys of a device named in an Olm payload.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct OlmV1Keys {
+    /// The Ed25519 fingerprint key of the device.
+    pub ed25519: String,
+}
+
+impl DecryptedOlmV1Event {
+    /// Checks the payload against the encrypted event it was decrypted from and the device that
+    /// decrypted it.
+    ///
+    /// `sender_ed25519_key` is the Ed25519 key of the device that owns the Curve25519 identity key
+    /// in the `sender_key` of `envelope`, as published in its device keys. `own_user_id` and
+    /// `own_ed25519_key` identify the device that decrypted the payload.
+    pub fn validate(
+        &self,
+        envelope: &ToDeviceEncrypted,
+        sender_ed25519_key: &str,
+        own_user_id: &UserId,
+        own_ed25519_key: &str,
+    ) -> Result<(), DecryptionError> {
+        if let EncryptedEventContent::MegolmV1AesSha2(_) = envelope.content {
+            return Err(DecryptionError::UnsupportedAlgorithm);
+        }
+
+        if self.sender != envelope.sender {
+            return Err(DecryptionError::SenderMismatch);
+        }
+
+        if self.keys.ed25519 != sender_ed25519_key {
+            return Err(DecryptionError::SenderKeyMismatch);
+        }
+
+        if self.recipient != *own_user_id {
+            return Err(DecryptionError::RecipientMismatch);
+        }
+
+        if self.recipient_keys.ed25519 != own_ed25519_key {
+            return Err(DecryptionError::RecipientKeyMismatch);
+        }
+
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for DecryptedOlmV1Event {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Payload {
+            sender: UserId,
+            recipient: UserId,
+            recipient_keys: OlmV1Keys,
+            keys: OlmV1Keys,
+            #[serde(rename = "type")]
+            event_type: String,
+            content: JsonValue,
+        }
+
+        let payload = Payload::deserialize(deserializer)?;
+
+        let event = serde_json::json!({
+            "sender": payload.sender,
+            "type": payload.event_type,
+            "content": payload.content,
+        });
+        let event = RawValue::from_string(event.to_string()).map_err(D::Error::custom)?;
+        let event = EventJson::<AnyToDeviceEvent>::from(event)
+            .deserialize()
+            .map_err(D::Error::custom)?;
+
+        Ok(Self {
+            sender: payload.sender,
+            recipient: payload.recipient,
+            recipient_keys: payload.recipient_keys,
+            keys: payload.keys,
+            event,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use maplit::btreemap;
+    use matches::assert_matches;
+    use ruma_identifiers::UserId;
+    use serde_json::{from_value as from_json_value, json};
+
+    use super::DecryptedOlmV1Event;
+    use crate::{
+        room::encrypted::{
+            CiphertextInfo, Curve25519Key, DecryptionError, EncryptedEventContent, OlmMessageType,
+            OlmV1Curve25519AesSha2Content,
+        },
+        to_device::{AnyToDeviceEvent, ToDeviceEncrypted},
+    };
+
+    fn payload() -> DecryptedOlmV1Event {
+        from_json_value(json!({
+            "type": "m.room_key",
+            "content": {
+                "algorithm": "m.megolm.v1.aes-sha2",
+                "room_id": "!roomid:room.com",
+                "session_id": "session_id",
+                "session_key": "session_key"
+            },
+            "sender": "@alice:example.org",
+            "recipient": "@bob:example.org",
+            "recipient_keys": { "ed25519": "bob_ed25519_key" },
+            "keys": { "ed25519": "alice_ed25519_key" }
+        }))
+        .unwrap()
+    }
+
+    fn envelope() -> ToDeviceEncrypted {
+        ToDeviceEncrypted {
+            sender: UserId::try_from("@alice:example.org").unwrap(),
+            content: EncryptedEventContent::OlmV1Curve25519AesSha2(OlmV1Curve25519AesSha2Content {
+                ciphertext: btreemap! {
+                    Curve25519Key::try_from("3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI").unwrap() => CiphertextInfo {
+                        body: "ciphertext".to_string(),
+                        message_type: OlmMessageType::Normal,
+                    },
+                },
+                sender_key: "alice_curve25519_key".to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn deserialize_payload() {
+        let payload = payload();
+
+        assert_eq!(payload.sender.to_string(), "@alice:example.org");
+        assert_eq!(payload.recipient.to_string(), "@bob:example.org");
+        assert_eq!(payload.recipient_keys.ed25519, "bob_ed25519_key");
+        assert_eq!(payload.keys.ed25519, "alice_ed25519_key");
+        assert_matches!(
+            payload.event,
+            AnyToDeviceEvent::RoomKey(room_key) if room_key.sender == payload.sender
+        );
+    }
+
+    #[test]
+    fn validate_payload() {
+        let payload = payload();
+        let bob = UserId::try_from("@bob:example.org").unwrap();
+        let envelope = envelope();
+
+        assert!(payload
+            .validate(&envelope, "alice_ed25519_key", &bob, "bob_ed25519_key")
+            .is_ok());
+        assert_matches!(
+            payload.validate(&envelope, "mallory_ed25519_key", &bob, "bob_ed25519_key"),
+            Err(DecryptionError::SenderKeyMismatch)
+        );
+        assert_matches!(
+            payload.validate(
+                &envelope,
+                "alice_ed25519_key",
+                &UserId::try_from("@carl:example.org").unwrap(),
+                "bob_ed25519_key"
+            ),
+            Err(DecryptionError::RecipientMismatch)
+        );
+        assert_matches!(
+            payload.validate(&envelope, "alice_ed25519_key", &bob, "other_ed25519_key"),
+            Err(DecryptionError::RecipientKeyMismatch)
+        );
+    }
+}
@@ -1,10 +1,19 @@
 //! Types for the *m.room.encryption* event.
 
+use std::time::{Duration, SystemTime};
+
 use js_int::UInt;
 use ruma_events_macros::ruma_event;
 
+use super::encrypted::{EncryptedEvent, EncryptedEventContent};
 use crate::Algorithm;
 
+/// The recommended time after which a megolm session should be replaced, one week.
+pub const DEFAULT_ROTATION_PERIOD: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// The recommended number of messages after which a megolm session should be replaced.
+pub const DEFAULT_ROTATION_PERIOD_MESSAGES: u64 = 100;
+
 ruma_event! {
     /// Defines how messages sent in this room should be encrypted.
     EncryptionEvent {
@@ -28,3 +37,127 @@ ruma_event! {
         },
     }
 }
+
+impl EncryptionEventContent {
+    /// How long a megolm session should be used before replacing it.
+    ///
+    /// Defaults to `DEFAULT_ROTATION_PERIOD` if `rotation_period_ms` is not set.
+    pub fn rotation_period(&self) -> Duration {
+        self.rotation_period_ms
+            .map_or(DEFAULT_ROTATION_PERIOD, |ms| {
+                Duration::from_millis(ms.into())
+            })
+    }
+
+    /// How many messages should be sent with a megolm session before replacing it.
+    ///
+    /// Defaults to `DEFAULT_ROTATION_PERIOD_MESSAGES` if `rotation_period_msgs` is not set.
+    pub fn rotation_period_messages(&self) -> u64 {
+        self.rotation_period_msgs
+            .map_or(DEFAULT_ROTATION_PERIOD_MESSAGES, Into::into)
+    }
+
+    /// Whether an outbound megolm session that was created at `session_created_at` and has been
+    /// used for `messages_sent` messages should be replaced before sending the next message at
+    /// `now`.
+    pub fn should_rotate(
+        &self,
+        session_created_at: SystemTime,
+        messages_sent: u64,
+        now: SystemTime,
+    ) -> bool {
+        // A session from the future, e.g. after the clock was adjusted, is treated as new.
+        let age = now.duration_since(session_created_at).unwrap_or_default();
+
+        age >= self.rotation_period() || messages_sent >= self.rotation_period_messages()
+    }
+
+    /// Whether the given event was encrypted with the algorithm configured for the room.
+    ///
+    /// Clients should flag events for which this returns `false`, since they may have been sent
+    /// by an attacker trying to downgrade the encryption of the room.
+    pub fn matches_algorithm(&self, event: &EncryptedEvent) -> bool {
+        let algorithm = match event.content {
+            EncryptedEventContent::OlmV1Curve25519AesSha2(_) => Algorithm::OlmV1Curve25519AesSha2,
+            EncryptedEventContent::MegolmV1AesSha2(_) => Algorithm::MegolmV1AesSha2,
+        };
+
+        self.algorithm == algorithm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::BTreeMap,
+        convert::TryFrom,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use js_int::UInt;
+    use ruma_identifiers::{EventId, RoomId, UserId};
+
+    use super::{EncryptionEventContent, DEFAULT_ROTATION_PERIOD};
+    use crate::{
+        room::encrypted::{
+            EncryptedEvent, EncryptedEventContent, MegolmV1AesSha2Content,
+            OlmV1Curve25519AesSha2Content,
+        },
+        Algorithm, UnsignedData,
+    };
+
+    fn content() -> EncryptionEventContent {
+        EncryptionEventContent {
+            algorithm: Algorithm::MegolmV1AesSha2,
+            rotation_period_ms: None,
+            rotation_period_msgs: None,
+        }
+    }
+
+    #[test]
+    fn rotation() {
+        let content = content();
+        let now = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+
+        assert_eq!(content.rotation_period(), DEFAULT_ROTATION_PERIOD);
+        assert_eq!(content.rotation_period_messages(), 100);
+        assert!(!content.should_rotate(now, 99, now));
+        assert!(content.should_rotate(now, 100, now));
+        assert!(content.should_rotate(now - Duration::from_secs(8 * 24 * 60 * 60), 0, now));
+        assert!(!content.should_rotate(now + Duration::from_secs(60), 0, now));
+
+        let content = EncryptionEventContent {
+            rotation_period_ms: Some(UInt::from(60_000_u32)),
+            rotation_period_msgs: Some(UInt::from(10_u32)),
+            ..content
+        };
+        assert!(!content.should_rotate(now, 9, now));
+        assert!(content.should_rotate(now, 10, now));
+        assert!(content.should_rotate(now - Duration::from_secs(2 * 60), 0, now));
+    }
+
+    #[test]
+    fn algorithm_downgrade() {
+        let mut event = EncryptedEvent {
+            content: EncryptedEventContent::MegolmV1AesSha2(MegolmV1AesSha2Content {
+                ciphertext: "ciphertext".to_string(),
+                sender_key: "sender_key".to_string(),
+                device_id: "device_id".to_string(),
+                session_id: "session_id".to_string(),
+            }),
+            event_id: EventId::try_from("$h29iv0s8:example.com").unwrap(),
+            origin_server_ts: UNIX_EPOCH + Duration::from_millis(1),
+            room_id: Some(RoomId::try_from("!roomid:room.com").unwrap()),
+            sender: UserId::try_from("@carl:example.com").unwrap(),
+            unsigned: UnsignedData::default(),
+        };
+        assert!(content().matches_algorithm(&event));
+
+        event.content =
+            EncryptedEventContent::OlmV1Curve25519AesSha2(OlmV1Curve25519AesSha2Content {
+                ciphertext: BTreeMap::new(),
+                sender_key: "sender_key".to_string(),
+            });
+        assert!(!content().matches_algorithm(&event));
+    }
+}
@@ -1,15 +1,21 @@
 //! Types for the *m.room.message* event.
 
-use std::time::SystemTime;
+use std::{collections::BTreeMap, time::SystemTime};
 
 use js_int::UInt;
-use ruma_identifiers::{EventId, RoomId, UserId};
-use serde::{Deserialize, Serialize};
+use ruma_identifiers::{DeviceId, EventId, RoomId, UserId};
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value as JsonValue;
 
 use super::{EncryptedFile, ImageInfo, ThumbnailInfo};
-use crate::{EventType, FromRaw, UnsignedData};
+use crate::{
+    key::verification::VerificationMethod, EventType, FromRaw, InvalidInput, UnsignedData,
+};
 
 pub mod feedback;
+mod geo_uri;
+
+pub use self::geo_uri::{Crs, GeoUri};
 
 /// A message sent to a room.
 #[derive(Clone, Debug, Serialize)]
@@ -39,44 +45,82 @@ pub struct MessageEvent {
 
 /// The payload for `MessageEvent`.
 #[allow(clippy::large_enum_variant)]
-#[derive(Clone, Debug, Serialize)]
-#[serde(tag = "msgtype")]
+#[derive(Clone, Debug)]
 pub enum MessageEventContent {
     /// An audio message.
-    #[serde(rename = "m.audio")]
     Audio(AudioMessageEventContent),
 
     /// An emote message.
-    #[serde(rename = "m.emote")]
     Emote(EmoteMessageEventContent),
 
     /// A file message.
-    #[serde(rename = "m.file")]
     File(FileMessageEventContent),
 
     /// An image message.
-    #[serde(rename = "m.image")]
     Image(ImageMessageEventContent),
 
+    /// A request to verify another user's devices.
+    KeyVerificationRequest(KeyVerificationRequestMessageEventContent),
+
     /// A location message.
-    #[serde(rename = "m.location")]
     Location(LocationMessageEventContent),
 
     /// A notice message.
-    #[serde(rename = "m.notice")]
     Notice(NoticeMessageEventContent),
 
     /// A server notice message.
-    #[serde(rename = "m.server_notice")]
     ServerNotice(ServerNoticeMessageEventContent),
 
     /// A text message.
-    #[serde(rename = "m.text")]
     Text(TextMessageEventContent),
 
     /// A video message.
-    #[serde(rename = "m.video")]
     Video(VideoMessageEventContent),
+
+    /// A message with a `msgtype` that is not part of the specification.
+    Custom(CustomMessageEventContent),
+}
+
+impl Serialize for MessageEventContent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        use MessageEventContent::*;
+
+        match self {
+            Audio(content) => serialize_tagged("m.audio", content, serializer),
+            Emote(content) => serialize_tagged("m.emote", content, serializer),
+            File(content) => serialize_tagged("m.file", content, serializer),
+            Image(content) => serialize_tagged("m.image", content, serializer),
+            KeyVerificationRequest(content) => {
+                serialize_tagged("m.key.verification.request", content, serializer)
+            }
+            Location(content) => serialize_tagged("m.location", content, serializer),
+            Notice(content) => serialize_tagged("m.notice", content, serializer),
+            ServerNotice(content) => serialize_tagged("m.server_notice", content, serializer),
+            Text(content) => serialize_tagged("m.text", content, serializer),
+            Video(content) => serialize_tagged("m.video", content, serializer),
+            Custom(content) => content.serialize(serializer),
+        }
+    }
+}
+
+/// Serializes a message content along with its `msgtype`.
+fn serialize_tagged<T, S>(msgtype: &str, content: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    #[derive(Serialize)]
+    struct Tagged<'a, T> {
+        msgtype: &'a str,
+
+        #[serde(flatten)]
+        content: &'a T,
+    }
+
+    Tagged { msgtype, content }.serialize(serializer)
 }
 
 impl FromRaw for MessageEvent {
@@ -105,11 +149,13 @@ impl FromRaw for MessageEventContent {
             Emote(content) => MessageEventContent::Emote(content),
             File(content) => MessageEventContent::File(content),
             Image(content) => MessageEventContent::Image(content),
+            KeyVerificationRequest(content) => MessageEventContent::KeyVerificationRequest(content),
             Location(content) => MessageEventContent::Location(content),
             Notice(content) => MessageEventContent::Notice(content),
             ServerNotice(content) => MessageEventContent::ServerNotice(content),
             Text(content) => MessageEventContent::Text(content),
             Video(content) => MessageEventContent::Video(content),
+            Custom(content) => MessageEventContent::Custom(content),
         }
     }
 }
@@ -120,14 +166,17 @@ pub(crate) mod raw {
     use std::time::SystemTime;
 
     use ruma_identifiers::{EventId, RoomId, UserId};
-    use serde::Deserialize;
+    use serde::{Deserialize, Deserializer};
+    use serde_json::Value as JsonValue;
 
     use super::{
-        AudioMessageEventContent, EmoteMessageEventContent, FileMessageEventContent,
-        ImageMessageEventContent, LocationMessageEventContent, NoticeMessageEventContent,
-        ServerNoticeMessageEventContent, TextMessageEventContent, VideoMessageEventContent,
+        AudioMessageEventContent, CustomMessageEventContent, EmoteMessageEventContent,
+        FileMessageEventContent, ImageMessageEventContent,
+        KeyVerificationRequestMessageEventContent, LocationMessageEventContent,
+        NoticeMessageEventContent, ServerNoticeMessageEventContent, TextMessageEventContent,
+        VideoMessageEventContent,
     };
-    use crate::UnsignedData;
+    use crate::{util::get_field, UnsignedData};
 
     /// A message sent to a room.
     #[derive(Clone, Debug, Deserialize)]
@@ -155,44 +204,68 @@ pub(crate) mod raw {
 
     /// The payload for `MessageEvent`.
     #[allow(clippy::large_enum_variant)]
-    #[derive(Clone, Debug, Deserialize)]
-    #[serde(tag = "msgtype")]
+    #[derive(Clone, Debug)]
     pub enum MessageEventContent {
         /// An audio message.
-        #[serde(rename = "m.audio")]
         Audio(AudioMessageEventContent),
 
         /// An emote message.
-        #[serde(rename = "m.emote")]
         Emote(EmoteMessageEventContent),
 
         /// A file message.
-        #[serde(rename = "m.file")]
         File(FileMessageEventContent),
 
         /// An image message.
-        #[serde(rename = "m.image")]
         Image(ImageMessageEventContent),
 
+        /// A request to verify another user's devices.
+        KeyVerificationRequest(KeyVerificationRequestMessageEventContent),
+
         /// A location message.
-        #[serde(rename = "m.location")]
         Location(LocationMessageEventContent),
 
         /// A notice message.
-        #[serde(rename = "m.notice")]
         Notice(NoticeMessageEventContent),
 
         /// A server notice message.
-        #[serde(rename = "m.server_notice")]
         ServerNotice(ServerNoticeMessageEventContent),
 
         /// An text message.
-        #[serde(rename = "m.text")]
         Text(TextMessageEventContent),
 
         /// A video message.
-        #[serde(rename = "m.video")]
         Video(VideoMessageEventContent),
+
+        /// A message with a `msgtype` that is not part of the specification.
+        Custom(CustomMessageEventContent),
+    }
+
+    impl<'de> Deserialize<'de> for MessageEventContent {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            use crate::util::try_variant_from_value as from_value;
+
+            let value = JsonValue::deserialize(deserializer)?;
+            let message_type: String = get_field(&value, "msgtype")?;
+
+            match message_type.as_str() {
+                "m.audio" => from_value(value, MessageEventContent::Audio),
+                "m.emote" => from_value(value, MessageEventContent::Emote),
+                "m.file" => from_value(value, MessageEventContent::File),
+                "m.image" => from_value(value, MessageEventContent::Image),
+                "m.key.verification.request" => {
+                    from_value(value, MessageEventContent::KeyVerificationRequest)
+                }
+                "m.location" => from_value(value, MessageEventContent::Location),
+                "m.notice" => from_value(value, MessageEventContent::Notice),
+                "m.server_notice" => from_value(value, MessageEventContent::ServerNotice),
+                "m.text" => from_value(value, MessageEventContent::Text),
+                "m.video" => from_value(value, MessageEventContent::Video),
+                _ => from_value(value, MessageEventContent::Custom),
+            }
+        }
     }
 }
 
@@ -326,13 +399,30 @@ pub struct LocationMessageEventContent {
     pub body: String,
 
     /// A geo URI representing the location.
-    pub geo_uri: String,
+    pub geo_uri: GeoUri,
 
     /// Info about the location being represented.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub info: Option<LocationInfo>,
 }
 
+impl LocationMessageEventContent {
+    /// Creates a location message for the given WGS-84 coordinates, in decimal degrees.
+    ///
+    /// Returns an error if the coordinates are out of range.
+    pub fn from_coordinates(
+        body: impl Into<String>,
+        latitude: f64,
+        longitude: f64,
+    ) -> Result<Self, InvalidInput> {
+        Ok(Self {
+            body: body.into(),
+            geo_uri: GeoUri::new(latitude, longitude)?,
+            info: None,
+        })
+    }
+}
+
 /// Thumbnail info associated with a location.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct LocationInfo {
@@ -494,6 +584,46 @@ pub struct VideoInfo {
     pub thumbnail_file: Option<EncryptedFile>,
 }
 
+/// The payload for a key verification request message.
+///
+/// This is the in-room counterpart of the *m.key.verification.request* to-device event. The
+/// event ID of the message is used as the transaction ID of the verification process that
+/// follows it.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct KeyVerificationRequestMessageEventContent {
+    /// A fallback message to alert users that their client does not support the key verification
+    /// framework.
+    pub body: String,
+
+    /// The verification methods supported by the sender.
+    pub methods: Vec<VerificationMethod>,
+
+    /// The device ID which is initiating the request.
+    pub from_device: DeviceId,
+
+    /// The user ID which should receive the request.
+    ///
+    /// Users should only respond to verification requests if they are named in this field.
+    pub to: UserId,
+}
+
+/// The payload for a message with a `msgtype` that is not part of the specification.
+///
+/// Messages with a `msgtype` known to ruma-events are never deserialized into this type, so it
+/// should not be used to send them either.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CustomMessageEventContent {
+    /// The type of the message, e.g. "org.example.poll".
+    pub msgtype: String,
+
+    /// The textual representation of this message.
+    pub body: String,
+
+    /// The remaining fields of the message content.
+    #[serde(flatten)]
+    pub data: BTreeMap<String, JsonValue>,
+}
+
 /// Information about related messages for
 /// [rich replies](https://matrix.org/docs/spec/client_server/r0.5.0#rich-replies).
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -533,9 +663,13 @@ mod tests {
     use ruma_identifiers::{EventId, RoomId, UserId};
     use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
 
-    use super::{AudioMessageEventContent, MessageEvent, MessageEventContent};
+    use super::{
+        AudioMessageEventContent, CustomMessageEventContent,
+        KeyVerificationRequestMessageEventContent, LocationMessageEventContent, MessageEvent,
+        MessageEventContent,
+    };
     use crate::room::message::{InReplyTo, RelatesTo, TextMessageEventContent};
-    use crate::{EventJson, UnsignedData};
+    use crate::{key::verification::VerificationMethod, EventJson, UnsignedData};
 
     #[test]
     fn serialization() {
@@ -652,6 +786,154 @@ mod tests {
         );
     }
 
+    #[test]
+    fn custom_content_serialization() {
+        let message_event_content = MessageEventContent::Custom(CustomMessageEventContent {
+            msgtype: "io.ruma.poll".to_string(),
+            body: "Which one?".to_string(),
+            data: vec![("answers".to_string(), json!(["this", "that"]))]
+                .into_iter()
+                .collect(),
+        });
+
+        assert_eq!(
+            to_json_value(&message_event_content).unwrap(),
+            json!({
+                "msgtype": "io.ruma.poll",
+                "body": "Which one?",
+                "answers": ["this", "that"]
+            })
+        );
+    }
+
+    #[test]
+    fn custom_content_deserialization() {
+        let json_data = json!({
+            "msgtype": "io.ruma.poll",
+            "body": "Which one?",
+            "answers": ["this", "that"]
+        });
+
+        assert_matches!(
+            from_json_value::<EventJson<MessageEventContent>>(json_data.clone())
+                .unwrap()
+                .deserialize()
+                .unwrap(),
+            MessageEventContent::Custom(CustomMessageEventContent {
+                msgtype,
+                body,
+                data,
+            }) if msgtype == "io.ruma.poll"
+                && body == "Which one?"
+                && data["answers"] == json!(["this", "that"])
+                && data.len() == 1
+        );
+
+        let content = from_json_value::<EventJson<MessageEventContent>>(json_data.clone())
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        assert_eq!(to_json_value(&content).unwrap(), json_data);
+    }
+
+    #[test]
+    fn key_verification_request_content_serialization() {
+        let message_event_content = MessageEventContent::KeyVerificationRequest(
+            KeyVerificationRequestMessageEventContent {
+                body: "@alice:example.org is requesting to verify your key".to_string(),
+                methods: vec![VerificationMethod::MSasV1],
+                from_device: "AliceDevice".to_string(),
+                to: UserId::try_from("@bob:example.org").unwrap(),
+            },
+        );
+
+        assert_eq!(
+            to_json_value(&message_event_content).unwrap(),
+            json!({
+                "msgtype": "m.key.verification.request",
+                "body": "@alice:example.org is requesting to verify your key",
+                "methods": ["m.sas.v1"],
+                "from_device": "AliceDevice",
+                "to": "@bob:example.org"
+            })
+        );
+    }
+
+    #[test]
+    fn key_verification_request_content_deserialization() {
+        let json_data = json!({
+            "msgtype": "m.key.verification.request",
+            "body": "@alice:example.org is requesting to verify your key",
+            "methods": ["m.sas.v1"],
+            "from_device": "AliceDevice",
+            "to": "@bob:example.org"
+        });
+
+        assert_matches!(
+            from_json_value::<EventJson<MessageEventContent>>(json_data)
+                .unwrap()
+                .deserialize()
+                .unwrap(),
+            MessageEventContent::KeyVerificationRequest(KeyVerificationRequestMessageEventContent {
+                methods,
+                from_device,
+                to,
+                ..
+            }) if methods == vec![VerificationMethod::MSasV1]
+                && from_device == "AliceDevice"
+                && to == UserId::try_from("@bob:example.org").unwrap()
+        );
+    }
+
+    #[test]
+    fn location_content_serialization() {
+        let message_event_content = MessageEventContent::Location(
+            LocationMessageEventContent::from_coordinates("Big Ben, London, UK", 51.5008, -0.1247)
+                .unwrap(),
+        );
+
+        assert_eq!(
+            to_json_value(&message_event_content).unwrap(),
+            json!({
+                "body": "Big Ben, London, UK",
+                "geo_uri": "geo:51.5008,-0.1247",
+                "msgtype": "m.location"
+            })
+        );
+        assert!(LocationMessageEventContent::from_coordinates("Nowhere", 100.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn location_content_deserialization() {
+        let json_data = json!({
+            "body": "Big Ben, London, UK",
+            "geo_uri": "geo:51.5008,-0.1247;u=35",
+            "msgtype": "m.location"
+        });
+
+        assert_matches!(
+            from_json_value::<EventJson<MessageEventContent>>(json_data)
+                .unwrap()
+                .deserialize()
+                .unwrap(),
+            MessageEventContent::Location(LocationMessageEventContent { geo_uri, .. })
+                if geo_uri.latitude() == 51.5008
+                    && geo_uri.longitude() == -0.1247
+                    && geo_uri.uncertainty() == Some(35.0)
+        );
+
+        let json_data = json!({
+            "body": "Nowhere",
+            "geo_uri": "geo:151.5008,-0.1247",
+            "msgtype": "m.location"
+        });
+
+        assert!(from_json_value::<EventJson<MessageEventContent>>(json_data)
+            .unwrap()
+            .deserialize()
+            .is_err());
+    }
+
     #[test]
     fn content_deserialization_failure() {
         let json_data = json!({
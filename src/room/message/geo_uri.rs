@@ -0,0 +1,382 @@
+//! Types for [RFC 5870](https://tools.ietf.org/html/rfc5870) geo URIs.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use serde::{
+    de::{Error as _, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::InvalidInput;
+
+/// A location given as a *geo* URI, e.g. `geo:51.5008,0.1247;u=35`.
+///
+/// Coordinates in the default WGS-84 coordinate reference system are validated to be in range
+/// when parsing or constructing a `GeoUri`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeoUri {
+    latitude: f64,
+    longitude: f64,
+    altitude: Option<f64>,
+    crs: Crs,
+    uncertainty: Option<f64>,
+}
+
+/// The coordinate reference system of a `GeoUri`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Crs {
+    /// The World Geodetic System 1984, the default coordinate reference system.
+    Wgs84,
+
+    /// A coordinate reference system that is not registered with IANA.
+    Custom(String),
+}
+
+impl GeoUri {
+    /// Creates a `GeoUri` for the given WGS-84 coordinates, in decimal degrees.
+    ///
+    /// Returns an error if the latitude is not in the range [-90, 90] or the longitude is not in
+    /// the range [-180, 180].
+    pub fn new(latitude: f64, longitude: f64) -> Result<Self, InvalidInput> {
+        validate_wgs84(latitude, longitude)?;
+
+        Ok(Self {
+            latitude,
+            longitude,
+            altitude: None,
+            crs: Crs::Wgs84,
+            uncertainty: None,
+        })
+    }
+
+    /// Sets the altitude of the location, in meters.
+    ///
+    /// Returns an error if the altitude is not a finite number.
+    pub fn with_altitude(mut self, altitude: f64) -> Result<Self, InvalidInput> {
+        if !altitude.is_finite() {
+            return Err(InvalidInput("altitude must be a finite number".to_string()));
+        }
+
+        self.altitude = Some(altitude);
+        Ok(self)
+    }
+
+    /// Sets the uncertainty of the location, in meters.
+    ///
+    /// Returns an error if the uncertainty is negative or not a finite number.
+    pub fn with_uncertainty(mut self, uncertainty: f64) -> Result<Self, InvalidInput> {
+        if !uncertainty.is_finite() || uncertainty < 0.0 {
+            return Err(InvalidInput(
+                "uncertainty must be a non-negative number".to_string(),
+            ));
+        }
+
+        self.uncertainty = Some(uncertainty);
+        Ok(self)
+    }
+
+    /// The latitude of the location.
+    ///
+    /// For the WGS-84 coordinate reference system, this is in decimal degrees.
+    pub fn latitude(&self) -> f64 {
+        self.latitude
+    }
+
+    /// The longitude of the location.
+    ///
+    /// For the WGS-84 coordinate reference system, this is in decimal degrees.
+    pub fn longitude(&self) -> f64 {
+        self.longitude
+    }
+
+    /// The altitude of the location, if any.
+    ///
+    /// For the WGS-84 coordinate reference system, this is in meters.
+    pub fn altitude(&self) -> Option<f64> {
+        self.altitude
+    }
+
+    /// The coordinate reference system of the coordinates.
+    pub fn crs(&self) -> &Crs {
+        &self.crs
+    }
+
+    /// The uncertainty of the location in meters, if any.
+    pub fn uncertainty(&self) -> Option<f64> {
+        self.uncertainty
+    }
+}
+
+impl Display for GeoUri {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "geo:{},{}", self.latitude, self.longitude)?;
+
+        if let Some(altitude) = self.altitude {
+            write!(f, ",{}", altitude)?;
+        }
+
+        if let Crs::Custom(crs) = &self.crs {
+            write!(f, ";crs={}", crs)?;
+        }
+
+        if let Some(uncertainty) = self.uncertainty {
+            write!(f, ";u={}", uncertainty)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for GeoUri {
+    type Err = InvalidInput;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: &str| InvalidInput(format!("invalid geo URI: {}", reason));
+
+        let rest = match (s.get(..4), s.get(4..)) {
+            (Some(scheme), Some(rest)) if scheme.eq_ignore_ascii_case("geo:") => rest,
+            _ => return Err(invalid("missing `geo:` scheme")),
+        };
+
+        let mut parts = rest.split(';');
+        let coordinates = parts.next().unwrap_or_default();
+        let mut parameters = parts.map(split_parameter).peekable();
+
+        // `crs` may only be the first parameter, and `u` may only follow it.
+        let mut crs = Crs::Wgs84;
+        if let Some((name, value)) = parameters.peek().copied() {
+            if name.eq_ignore_ascii_case("crs") {
+                parameters.next();
+
+                let value = value.ok_or_else(|| invalid("missing `crs` value"))?;
+                if !value.eq_ignore_ascii_case("wgs84") {
+                    crs = Crs::Custom(value.to_string());
+                }
+            }
+        }
+
+        let mut uncertainty = None;
+        if let Some((name, value)) = parameters.peek().copied() {
+            if name.eq_ignore_ascii_case("u") {
+                parameters.next();
+
+                let value = value.ok_or_else(|| invalid("missing `u` value"))?;
+                uncertainty =
+                    Some(parse_number(value, false).ok_or_else(|| invalid("bad uncertainty"))?);
+            }
+        }
+
+        for (name, _) in parameters {
+            if name.is_empty() {
+                return Err(invalid("empty parameter name"));
+            }
+
+            if name.eq_ignore_ascii_case("crs") || name.eq_ignore_ascii_case("u") {
+                return Err(invalid("misplaced `crs` or `u` parameter"));
+            }
+        }
+
+        let coordinates = coordinates
+            .split(',')
+            .map(|coordinate| parse_number(coordinate, true))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| invalid("bad coordinate"))?;
+
+        let (latitude, longitude, altitude) = match coordinates[..] {
+            [latitude, longitude] => (latitude, longitude, None),
+            [latitude, longitude, altitude] => (latitude, longitude, Some(altitude)),
+            _ => return Err(invalid("expected two or three coordinates")),
+        };
+
+        if crs == Crs::Wgs84 {
+            validate_wgs84(latitude, longitude)?;
+        }
+
+        Ok(Self {
+            latitude,
+            longitude,
+            altitude,
+            crs,
+            uncertainty,
+        })
+    }
+}
+
+impl Serialize for GeoUri {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for GeoUri {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+
+        s.parse()
+            .map_err(|_| D::Error::invalid_value(Unexpected::Str(&s), &"a valid geo URI"))
+    }
+}
+
+impl Crs {
+    /// The label of the coordinate reference system, as used in the `crs` parameter.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Crs::Wgs84 => "wgs84",
+            Crs::Custom(crs) => crs,
+        }
+    }
+}
+
+/// Splits a parameter of a geo URI into its name and value.
+fn split_parameter(parameter: &str) -> (&str, Option<&str>) {
+    let mut split = parameter.splitn(2, '=');
+    (split.next().unwrap_or_default(), split.next())
+}
+
+/// Parses a number as allowed by the grammar of RFC 5870: digits with an optional fractional
+/// part, and optionally a leading minus sign. Exponents, `inf` and `NaN` are not allowed.
+fn parse_number(s: &str, allow_sign: bool) -> Option<f64> {
+    let digits = if allow_sign && s.starts_with('-') {
+        &s[1..]
+    } else {
+        s
+    };
+
+    let mut split = digits.splitn(2, '.');
+    let integer = split.next().unwrap_or_default();
+    let is_digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    let is_valid = match split.next() {
+        Some(fraction) => is_digits(integer) && is_digits(fraction),
+        None => is_digits(integer),
+    };
+
+    if !is_valid {
+        return None;
+    }
+
+    s.parse().ok()
+}
+
+fn validate_wgs84(latitude: f64, longitude: f64) -> Result<(), InvalidInput> {
+    if !(-90.0..=90.0).contains(&latitude) {
+        return Err(InvalidInput(
+            "latitude must be in the range [-90, 90]".to_string(),
+        ));
+    }
+
+    if !(-180.0..=180.0).contains(&longitude) {
+        return Err(InvalidInput(
+            "longitude must be in the range [-180, 180]".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
+
+    use super::{Crs, GeoUri};
+
+    #[test]
+    fn parse_simple() {
+        let geo_uri = "geo:51.5008,0.1247".parse::<GeoUri>().unwrap();
+
+        assert_eq!(geo_uri.latitude(), 51.5008);
+        assert_eq!(geo_uri.longitude(), 0.1247);
+        assert_eq!(geo_uri.altitude(), None);
+        assert_eq!(geo_uri.crs(), &Crs::Wgs84);
+        assert_eq!(geo_uri.uncertainty(), None);
+    }
+
+    #[test]
+    fn parse_full() {
+        let geo_uri = "GEO:-48.198634,-16.371648,3.4;crs=wgs84;u=40.0;foo=bar"
+            .parse::<GeoUri>()
+            .unwrap();
+
+        assert_eq!(geo_uri.latitude(), -48.198634);
+        assert_eq!(geo_uri.longitude(), -16.371648);
+        assert_eq!(geo_uri.altitude(), Some(3.4));
+        assert_eq!(geo_uri.crs(), &Crs::Wgs84);
+        assert_eq!(geo_uri.uncertainty(), Some(40.0));
+    }
+
+    #[test]
+    fn parse_custom_crs_skips_range_validation() {
+        let geo_uri = "geo:400,-1000;crs=Moon-2011;u=35"
+            .parse::<GeoUri>()
+            .unwrap();
+
+        assert_eq!(geo_uri.crs(), &Crs::Custom("Moon-2011".to_string()));
+        assert_eq!(geo_uri.latitude(), 400.0);
+        assert_eq!(geo_uri.to_string(), "geo:400,-1000;crs=Moon-2011;u=35");
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for invalid in &[
+            "51.5008,0.1247",
+            "geo:",
+            "geo:51.5008",
+            "geo:51.5008,0.1247,1,2",
+            "geo:91,0",
+            "geo:0,180.5",
+            "geo:+51,0",
+            "geo:1e2,0",
+            "geo:NaN,0",
+            "geo:51.,0",
+            "geo:51.5008,0.1247;u=-1",
+            "geo:51.5008,0.1247;u=1;crs=wgs84",
+            "geo:51.5008,0.1247;foo=bar;u=1",
+        ] {
+            assert!(invalid.parse::<GeoUri>().is_err(), "{} parsed", invalid);
+        }
+    }
+
+    #[test]
+    fn parse_non_ascii() {
+        for invalid in &["ge€:1,2", "€", "géo:51.5008,0.1247", "geo:51.5008,0.1247€"] {
+            assert!(invalid.parse::<GeoUri>().is_err(), "{} parsed", invalid);
+        }
+    }
+
+    #[test]
+    fn construct() {
+        let geo_uri = GeoUri::new(51.5008, -0.1247)
+            .unwrap()
+            .with_altitude(12.0)
+            .unwrap()
+            .with_uncertainty(35.0)
+            .unwrap();
+
+        assert_eq!(geo_uri.to_string(), "geo:51.5008,-0.1247,12;u=35");
+        assert!(GeoUri::new(-90.5, 0.0).is_err());
+        assert!(GeoUri::new(0.0, 0.0)
+            .unwrap()
+            .with_uncertainty(-3.0)
+            .is_err());
+    }
+
+    #[test]
+    fn serde_roundtrip() {
+        let geo_uri = from_json_value::<GeoUri>(json!("geo:37.786971,-122.399677;u=35")).unwrap();
+
+        assert_eq!(geo_uri.latitude(), 37.786971);
+        assert_eq!(
+            to_json_value(&geo_uri).unwrap(),
+            json!("geo:37.786971,-122.399677;u=35")
+        );
+        assert!(from_json_value::<GeoUri>(json!("geo:37.786971")).is_err());
+    }
+}
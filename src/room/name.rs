@@ -260,7 +260,7 @@ mod tests {
     #[test]
     fn name_fails_validation_when_too_long() {
         // "XXXX .." 256 times
-        let long_string: String = String::from_iter(std::iter::repeat('X').take(256));
+        let long_string: String = String::from_iter(std::iter::repeat_n('X', 256));
         assert_eq!(long_string.len(), 256);
 
         let long_content_json = json!({ "name": &long_string });
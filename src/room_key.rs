@@ -1,10 +1,14 @@
 //! Types for the *m.room_key* event.
+//!
+//! The *m.room_key.withheld* event lives in the `withheld` module.
 
 use ruma_events_macros::ruma_event;
 use ruma_identifiers::RoomId;
 
 use super::Algorithm;
 
+pub mod withheld;
+
 ruma_event! {
     /// This event type is used to exchange keys for end-to-end encryption.
     ///
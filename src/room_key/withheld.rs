@@ -0,0 +1,164 @@
+//! Types for the *m.room_key.withheld* event.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use ruma_events_macros::ruma_event;
+use ruma_identifiers::RoomId;
+use serde::{Deserialize, Serialize};
+
+use crate::Algorithm;
+
+ruma_event! {
+    /// This event type is used to indicate that the sender is not sharing room keys with the
+    /// recipient.
+    ///
+    /// It is sent as an unencrypted to-device event.
+    WithheldEvent {
+        kind: Event,
+        event_type: "m.room_key.withheld",
+        content: {
+            /// The encryption algorithm the withheld key is used with.
+            ///
+            /// Must be `m.megolm.v1.aes-sha2`.
+            pub algorithm: Algorithm,
+
+            /// The room for the withheld key.
+            ///
+            /// Required unless `code` is `m.no_olm`.
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub room_id: Option<RoomId>,
+
+            /// The ID of the session with the withheld key.
+            ///
+            /// Required unless `code` is `m.no_olm`.
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub session_id: Option<String>,
+
+            /// The Curve25519 key of the device that is withholding the key.
+            pub sender_key: String,
+
+            /// Why the key is withheld.
+            pub code: WithheldCode,
+
+            /// A human-readable reason for why the key is withheld.
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub reason: Option<String>,
+        },
+    }
+}
+
+/// The reason a room key is withheld.
+///
+/// Custom codes should use the Java package naming convention.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum WithheldCode {
+    /// The recipient's device is blacklisted by the sender.
+    Blacklisted,
+
+    /// The recipient's device is not verified and the sender only shares keys with verified
+    /// devices.
+    Unverified,
+
+    /// The recipient is not allowed to receive the key, e.g. because they were not in the room
+    /// when the message was sent.
+    Unauthorised,
+
+    /// The sender doesn't have the requested key, sent in reply to a key request.
+    Unavailable,
+
+    /// The sender couldn't establish an Olm session with the recipient's device.
+    NoOlm,
+
+    /// Any code that is not part of the specification.
+    Custom(String),
+}
+
+impl Display for WithheldCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let withheld_code_str = match *self {
+            WithheldCode::Blacklisted => "m.blacklisted",
+            WithheldCode::Unverified => "m.unverified",
+            WithheldCode::Unauthorised => "m.unauthorised",
+            WithheldCode::Unavailable => "m.unavailable",
+            WithheldCode::NoOlm => "m.no_olm",
+            WithheldCode::Custom(ref withheld_code) => withheld_code,
+        };
+
+        write!(f, "{}", withheld_code_str)
+    }
+}
+
+impl<T> From<T> for WithheldCode
+where
+    T: Into<String> + AsRef<str>,
+{
+    fn from(s: T) -> WithheldCode {
+        match s.as_ref() {
+            "m.blacklisted" => WithheldCode::Blacklisted,
+            "m.unverified" => WithheldCode::Unverified,
+            "m.unauthorised" => WithheldCode::Unauthorised,
+            "m.unavailable" => WithheldCode::Unavailable,
+            "m.no_olm" => WithheldCode::NoOlm,
+            _ => WithheldCode::Custom(s.into()),
+        }
+    }
+}
+
+impl From<WithheldCode> for String {
+    fn from(withheld_code: WithheldCode) -> String {
+        withheld_code.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use ruma_identifiers::RoomId;
+    use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
+
+    use super::{WithheldCode, WithheldEvent, WithheldEventContent};
+    use crate::Algorithm;
+
+    #[test]
+    fn serialization() {
+        let ev = WithheldEvent {
+            content: WithheldEventContent {
+                algorithm: Algorithm::MegolmV1AesSha2,
+                room_id: Some(RoomId::try_from("!testroomid:example.org").unwrap()),
+                session_id: Some("SessId".into()),
+                sender_key: "SenderKey".into(),
+                code: WithheldCode::Unverified,
+                reason: Some("Device not verified".into()),
+            },
+        };
+
+        assert_eq!(
+            to_json_value(ev).unwrap(),
+            json!({
+                "type": "m.room_key.withheld",
+                "content": {
+                    "algorithm": "m.megolm.v1.aes-sha2",
+                    "room_id": "!testroomid:example.org",
+                    "session_id": "SessId",
+                    "sender_key": "SenderKey",
+                    "code": "m.unverified",
+                    "reason": "Device not verified",
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn custom_code() {
+        assert_eq!(
+            from_json_value::<WithheldCode>(json!("org.example.code")).unwrap(),
+            WithheldCode::Custom("org.example.code".into())
+        );
+        assert_eq!(
+            to_json_value(WithheldCode::Custom("org.example.code".into())).unwrap(),
+            json!("org.example.code")
+        );
+    }
+}
@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 
 use super::Algorithm;
 
+pub mod sharing;
+
 ruma_event! {
     /// This event type is used to request keys for end-to-end encryption.
     ///
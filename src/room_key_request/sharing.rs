@@ -0,0 +1,372 @@
+//! A policy for answering requests for room keys.
+//!
+//! Devices request the megolm sessions they are missing with *m.room_key_request* events and
+//! cancel them once they received the session from another device. `RoomKeyRequests` tracks these
+//! requests and decides which of them to answer with an *m.forwarded_room_key* event.
+//!
+//! Following the specification, sessions are only shared with verified devices of the own user.
+
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use ruma_identifiers::{DeviceId, UserId};
+
+use super::{Action, RequestedKeyInfo};
+use crate::{
+    forwarded_room_key::{ExportedRoomKey, ForwardedRoomKeyEventContent},
+    room_key::withheld::WithheldCode,
+    to_device::ToDeviceRoomKeyRequest,
+};
+
+/// A pending request for a room key.
+#[derive(Clone, Debug)]
+pub struct RoomKeyRequest {
+    /// The user that sent the request.
+    pub sender: UserId,
+
+    /// The ID of the device requesting the key.
+    pub requesting_device_id: DeviceId,
+
+    /// The ID of the request.
+    pub request_id: String,
+
+    /// Information about the requested key.
+    pub info: RequestedKeyInfo,
+}
+
+/// The reason a room key request is not answered with the requested key.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum KeyShareRefusal {
+    /// There is no pending request with the given ID, e.g. because it was cancelled.
+    UnknownRequest,
+
+    /// The requesting device belongs to another user.
+    NotOwnDevice,
+
+    /// The requesting device is not verified.
+    UnverifiedDevice,
+
+    /// None of the known sessions matches the request.
+    UnknownSession,
+}
+
+impl KeyShareRefusal {
+    /// The code to send in an *m.room_key.withheld* event to tell the requesting device why it
+    /// didn't receive the key, if any.
+    ///
+    /// Returns `None` for `UnknownRequest`, since there is no request to respond to.
+    pub fn withheld_code(self) -> Option<WithheldCode> {
+        match self {
+            KeyShareRefusal::UnknownRequest => None,
+            KeyShareRefusal::NotOwnDevice => Some(WithheldCode::Unauthorised),
+            KeyShareRefusal::UnverifiedDevice => Some(WithheldCode::Unverified),
+            KeyShareRefusal::UnknownSession => Some(WithheldCode::Unavailable),
+        }
+    }
+}
+
+impl Display for KeyShareRefusal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyShareRefusal::UnknownRequest => write!(f, "unknown or cancelled key request"),
+            KeyShareRefusal::NotOwnDevice => write!(f, "requesting device belongs to another user"),
+            KeyShareRefusal::UnverifiedDevice => write!(f, "requesting device is not verified"),
+            KeyShareRefusal::UnknownSession => write!(f, "requested session is unknown"),
+        }
+    }
+}
+
+impl Error for KeyShareRefusal {}
+
+/// The pending room key requests of other devices, indexed by the requesting user and device and
+/// the request ID.
+#[derive(Clone, Debug)]
+pub struct RoomKeyRequests {
+    own_user_id: UserId,
+    own_device_id: DeviceId,
+    requests: BTreeMap<(UserId, DeviceId, String), RoomKeyRequest>,
+}
+
+impl RoomKeyRequests {
+    /// Creates an empty set of room key requests for the device with the given user ID and device
+    /// ID.
+    pub fn new(own_user_id: UserId, own_device_id: DeviceId) -> Self {
+        Self {
+            own_user_id,
+            own_device_id,
+            requests: BTreeMap::new(),
+        }
+    }
+
+    /// The pending request with the given ID by the given device, if any.
+    pub fn get(
+        &self,
+        sender: &UserId,
+        requesting_device_id: &DeviceId,
+        request_id: &str,
+    ) -> Option<&RoomKeyRequest> {
+        self.requests.get(&(
+            sender.clone(),
+            requesting_device_id.clone(),
+            request_id.to_string(),
+        ))
+    }
+
+    /// An iterator over all pending requests.
+    pub fn pending(&self) -> impl Iterator<Item = &RoomKeyRequest> {
+        self.requests.values()
+    }
+
+    /// Tracks an *m.room_key_request* event.
+    ///
+    /// A request replaces any pending request with the same ID by the same device, a cancellation
+    /// removes it. Returns the request if the event is a new request, and `None` if it is a
+    /// cancellation, a request without a body or a request sent by this device itself.
+    ///
+    /// Requests by other users are not tracked; `KeyShareRefusal::NotOwnDevice` is returned for
+    /// them.
+    pub fn receive(
+        &mut self,
+        event: &ToDeviceRoomKeyRequest,
+    ) -> Result<Option<&RoomKeyRequest>, KeyShareRefusal> {
+        let content = &event.content;
+
+        if event.sender != self.own_user_id {
+            return Err(KeyShareRefusal::NotOwnDevice);
+        }
+
+        if content.requesting_device_id == self.own_device_id {
+            return Ok(None);
+        }
+
+        let key = (
+            event.sender.clone(),
+            content.requesting_device_id.clone(),
+            content.request_id.clone(),
+        );
+
+        match (content.action, &content.body) {
+            (Action::Request, Some(info)) => {
+                let request = RoomKeyRequest {
+                    sender: event.sender.clone(),
+                    requesting_device_id: content.requesting_device_id.clone(),
+                    request_id: content.request_id.clone(),
+                    info: info.clone(),
+                };
+
+                self.requests.insert(key.clone(), request);
+                Ok(self.requests.get(&key))
+            }
+            (Action::Request, None) => Ok(None),
+            (Action::CancelRequest, _) => {
+                self.requests.remove(&key);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Decides whether to answer a pending request, and returns the content of the
+    /// *m.forwarded_room_key* event to send to the requesting device if so.
+    ///
+    /// `device_verified` tells whether the requesting device is verified, and `sessions` are the
+    /// megolm sessions known to this device. The request is removed from the pending requests
+    /// unless it is refused because the requesting device is not verified, so that it can be
+    /// answered once the device is verified.
+    ///
+    /// The `forwarding_curve25519_key_chain` of the exported session is forwarded unchanged.
+    pub fn answer<'a, I>(
+        &mut self,
+        sender: &UserId,
+        requesting_device_id: &DeviceId,
+        request_id: &str,
+        device_verified: bool,
+        sessions: I,
+    ) -> Result<ForwardedRoomKeyEventContent, KeyShareRefusal>
+    where
+        I: IntoIterator<Item = &'a ExportedRoomKey>,
+    {
+        let key = (
+            sender.clone(),
+            requesting_device_id.clone(),
+            request_id.to_string(),
+        );
+        if !self.requests.contains_key(&key) {
+            return Err(KeyShareRefusal::UnknownRequest);
+        }
+
+        if !device_verified {
+            return Err(KeyShareRefusal::UnverifiedDevice);
+        }
+
+        let request = self.requests.remove(&key).expect("request is pending");
+        sessions
+            .into_iter()
+            .find(|session| matches_request(session, &request.info))
+            .and_then(ExportedRoomKey::to_forwarded_room_key)
+            .ok_or(KeyShareRefusal::UnknownSession)
+    }
+}
+
+fn matches_request(session: &ExportedRoomKey, info: &RequestedKeyInfo) -> bool {
+    session.algorithm == info.algorithm
+        && session.room_id == info.room_id
+        && session.sender_key == info.sender_key
+        && session.session_id == info.session_id
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use maplit::btreemap;
+    use matches::assert_matches;
+    use ruma_identifiers::{RoomId, UserId};
+
+    use super::{KeyShareRefusal, RoomKeyRequests};
+    use crate::{
+        forwarded_room_key::ExportedRoomKey,
+        room_key::withheld::WithheldCode,
+        room_key_request::{Action, RequestedKeyInfo, RoomKeyRequestEventContent},
+        to_device::ToDeviceRoomKeyRequest,
+        Algorithm,
+    };
+
+    fn alice() -> UserId {
+        UserId::try_from("@alice:example.org").unwrap()
+    }
+
+    fn session() -> ExportedRoomKey {
+        ExportedRoomKey {
+            algorithm: Algorithm::MegolmV1AesSha2,
+            forwarding_curve25519_key_chain: vec!["forwarder_key".to_string()],
+            room_id: RoomId::try_from("!Cuyf34gef24t:localhost").unwrap(),
+            sender_key: "RF3s+E7RkTQTGF2d8Deol0FkQvgII2aJDf3/Jp5mxVU".to_string(),
+            sender_claimed_keys: btreemap! {
+                "ed25519".to_string() => "aj40p+aw64yPIdsxoog8Jhlu9i+jZPiL7BNtcLyh/UE".to_string(),
+            },
+            session_id: "X3lUlvLELLYxeTx4yOVu6UDpasGEVO0Jbu+QFnm0cKQ".to_string(),
+            session_key: "AgAAAADxKHa9uFxcXzwYoNueL5Xqi69IkD4sni8Llf".to_string(),
+        }
+    }
+
+    fn request(sender: UserId, action: Action, session_id: &str) -> ToDeviceRoomKeyRequest {
+        ToDeviceRoomKeyRequest {
+            sender,
+            content: RoomKeyRequestEventContent {
+                action,
+                body: Some(RequestedKeyInfo {
+                    algorithm: Algorithm::MegolmV1AesSha2,
+                    room_id: RoomId::try_from("!Cuyf34gef24t:localhost").unwrap(),
+                    sender_key: "RF3s+E7RkTQTGF2d8Deol0FkQvgII2aJDf3/Jp5mxVU".to_string(),
+                    session_id: session_id.to_string(),
+                }),
+                requesting_device_id: "JLAFKJWSCS".to_string(),
+                request_id: "1495474790150.19".to_string(),
+            },
+        }
+    }
+
+    fn requests() -> RoomKeyRequests {
+        RoomKeyRequests::new(alice(), "OWNDEVICE".to_string())
+    }
+
+    #[test]
+    fn share_with_own_verified_device() {
+        let mut requests = requests();
+        let session = session();
+        assert!(requests
+            .receive(&request(alice(), Action::Request, &session.session_id))
+            .unwrap()
+            .is_some());
+
+        let content = requests
+            .answer(
+                &alice(),
+                &"JLAFKJWSCS".to_string(),
+                "1495474790150.19",
+                true,
+                vec![&session],
+            )
+            .unwrap();
+        assert_eq!(content.session_key, session.session_key);
+        assert_eq!(
+            content.forwarding_curve25519_key_chain,
+            vec!["forwarder_key"]
+        );
+        assert_eq!(requests.pending().count(), 0);
+    }
+
+    #[test]
+    fn refuse_requests() {
+        let mut requests = requests();
+        let session = session();
+        let device_id = "JLAFKJWSCS".to_string();
+
+        requests
+            .receive(&request(alice(), Action::Request, &session.session_id))
+            .unwrap();
+        assert_matches!(
+            requests.answer(&alice(), &device_id, "1495474790150.19", false, &[]),
+            Err(KeyShareRefusal::UnverifiedDevice)
+        );
+        // Unverified devices can be answered later.
+        assert!(requests
+            .get(&alice(), &device_id, "1495474790150.19")
+            .is_some());
+        assert_matches!(
+            requests.answer(&alice(), &device_id, "1495474790150.19", true, &[]),
+            Err(KeyShareRefusal::UnknownSession)
+        );
+
+        let bob = UserId::try_from("@bob:example.org").unwrap();
+        let refusal = requests
+            .receive(&request(bob.clone(), Action::Request, &session.session_id))
+            .unwrap_err();
+        assert_eq!(refusal, KeyShareRefusal::NotOwnDevice);
+        assert_eq!(refusal.withheld_code(), Some(WithheldCode::Unauthorised));
+        assert!(requests.get(&bob, &device_id, "1495474790150.19").is_none());
+    }
+
+    #[test]
+    fn ignore_own_requests() {
+        let mut requests = requests();
+        let mut own_request = request(alice(), Action::Request, "X3lUlvLELLYxeTx4yOVu6UDpasGEVO0");
+        own_request.content.requesting_device_id = "OWNDEVICE".to_string();
+
+        assert!(requests.receive(&own_request).unwrap().is_none());
+        assert_eq!(requests.pending().count(), 0);
+    }
+
+    #[test]
+    fn cancelled_request() {
+        let mut requests = requests();
+        let session = session();
+
+        requests
+            .receive(&request(alice(), Action::Request, &session.session_id))
+            .unwrap();
+        assert!(requests
+            .receive(&request(
+                alice(),
+                Action::CancelRequest,
+                &session.session_id
+            ))
+            .unwrap()
+            .is_none());
+
+        assert_matches!(
+            requests.answer(
+                &alice(),
+                &"JLAFKJWSCS".to_string(),
+                "1495474790150.19",
+                true,
+                &[session]
+            ),
+            Err(KeyShareRefusal::UnknownRequest)
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Types for the *m.secret.request* event.
+
+use ruma_events_macros::ruma_event;
+use ruma_identifiers::DeviceId;
+use serde::{Deserialize, Serialize};
+
+ruma_event! {
+    /// Requests a secret from another device.
+    ///
+    /// It is sent as an unencrypted to-device event.
+    RequestEvent {
+        kind: Event,
+        event_type: "m.secret.request",
+        content: {
+            /// Whether this is a new secret request or a cancellation of a previous request.
+            #[serde(flatten)]
+            pub action: RequestAction,
+
+            /// ID of the device requesting the secret.
+            pub requesting_device_id: DeviceId,
+
+            /// A random string uniquely identifying the request for a secret.
+            ///
+            /// If the secret is requested multiple times, it should be reused. It should also
+            /// reused in order to cancel a request.
+            pub request_id: String,
+        },
+    }
+}
+
+/// A new secret request or a cancellation of a previous request.
+///
+/// Serialized as the `action` field of the content, along with the `name` of the requested secret
+/// for new requests.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(tag = "action")]
+pub enum RequestAction {
+    /// Request a secret.
+    #[serde(rename = "request")]
+    Request {
+        /// The name of the secret that is being requested.
+        name: String,
+    },
+
+    /// Cancel a request for a secret.
+    #[serde(rename = "request_cancellation")]
+    RequestCancellation,
+}
+
+#[cfg(test)]
+mod tests {
+    use matches::assert_matches;
+    use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
+
+    use super::{RequestAction, RequestEventContent};
+    use crate::EventJson;
+
+    #[test]
+    fn serialization() {
+        let content = RequestEventContent {
+            action: RequestAction::Request {
+                name: "m.cross_signing.self_signing".to_string(),
+            },
+            requesting_device_id: "ABCDEFG".to_string(),
+            request_id: "randomly_generated_id_9573".to_string(),
+        };
+
+        assert_eq!(
+            to_json_value(&content).unwrap(),
+            json!({
+                "action": "request",
+                "name": "m.cross_signing.self_signing",
+                "requesting_device_id": "ABCDEFG",
+                "request_id": "randomly_generated_id_9573"
+            })
+        );
+    }
+
+    #[test]
+    fn cancellation_deserialization() {
+        let json_data = json!({
+            "action": "request_cancellation",
+            "requesting_device_id": "ABCDEFG",
+            "request_id": "randomly_generated_id_9573"
+        });
+
+        assert_matches!(
+            from_json_value::<EventJson<RequestEventContent>>(json_data)
+                .unwrap()
+                .deserialize()
+                .unwrap(),
+            RequestEventContent {
+                action: RequestAction::RequestCancellation,
+                requesting_device_id,
+                request_id,
+            } if requesting_device_id == "ABCDEFG" && request_id == "randomly_generated_id_9573"
+        );
+    }
+
+    #[test]
+    fn request_without_name() {
+        let json_data = json!({
+            "action": "request",
+            "requesting_device_id": "ABCDEFG",
+            "request_id": "randomly_generated_id_9573"
+        });
+
+        assert!(from_json_value::<EventJson<RequestEventContent>>(json_data)
+            .unwrap()
+            .deserialize()
+            .is_err());
+    }
+}
@@ -0,0 +1,20 @@
+//! Types for the *m.secret.send* event.
+
+use ruma_events_macros::ruma_event;
+
+ruma_event! {
+    /// Sends a secret to another device in response to an *m.secret.request* event.
+    ///
+    /// It must be encrypted as an *m.room.encrypted* event, then sent as a to-device event.
+    SendEvent {
+        kind: Event,
+        event_type: "m.secret.send",
+        content: {
+            /// The ID of the request that this is a response to.
+            pub request_id: String,
+
+            /// The contents of the secret.
+            pub secret: String,
+        },
+    }
+}
@@ -0,0 +1,141 @@
+//! Modules for events in the *m.secret_storage* namespace.
+//!
+//! This module also contains the encrypted payload of secrets stored in the user's account data.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::FromRaw;
+
+#[cfg(feature = "secret-storage")]
+mod crypto;
+
+pub mod default_key;
+pub mod key;
+
+#[cfg(feature = "secret-storage")]
+pub use self::crypto::{SecretStorageError, SecretStorageKey, MAX_PASSPHRASE_ITERATIONS};
+
+/// An algorithm for encrypting secrets in secret storage.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum SecretEncryptionAlgorithm {
+    /// AES-256 in CTR mode for encryption with HMAC-SHA-256 for authentication.
+    AesHmacSha2,
+
+    /// Any algorithm that is not part of the specification.
+    Custom(String),
+}
+
+/// The content of an account data event storing an encrypted secret, e.g. *m.cross_signing.master*.
+///
+/// The secret is encrypted once for every key it is stored with, keyed by the ID of the key.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SecretEventContent {
+    /// The encrypted secret, keyed by the ID of the key used to encrypt it.
+    pub encrypted: BTreeMap<String, AesHmacSha2EncryptedData>,
+}
+
+/// A secret encrypted with the *m.secret_storage.v1.aes-hmac-sha2* algorithm.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct AesHmacSha2EncryptedData {
+    /// The 16-byte initialization vector, encoded as base64.
+    pub iv: String,
+
+    /// The AES-CTR-encrypted secret, encoded as base64.
+    pub ciphertext: String,
+
+    /// The MAC of the ciphertext, encoded as base64.
+    pub mac: String,
+}
+
+impl FromRaw for SecretEventContent {
+    type Raw = Self;
+
+    fn from_raw(raw: Self) -> Self {
+        raw
+    }
+}
+
+impl Display for SecretEncryptionAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let algorithm_str = match *self {
+            SecretEncryptionAlgorithm::AesHmacSha2 => "m.secret_storage.v1.aes-hmac-sha2",
+            SecretEncryptionAlgorithm::Custom(ref algorithm) => algorithm,
+        };
+
+        write!(f, "{}", algorithm_str)
+    }
+}
+
+impl<T> From<T> for SecretEncryptionAlgorithm
+where
+    T: Into<String> + AsRef<str>,
+{
+    fn from(s: T) -> SecretEncryptionAlgorithm {
+        match s.as_ref() {
+            "m.secret_storage.v1.aes-hmac-sha2" => SecretEncryptionAlgorithm::AesHmacSha2,
+            _ => SecretEncryptionAlgorithm::Custom(s.into()),
+        }
+    }
+}
+
+impl From<SecretEncryptionAlgorithm> for String {
+    fn from(algorithm: SecretEncryptionAlgorithm) -> String {
+        algorithm.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maplit::btreemap;
+    use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
+
+    use super::{AesHmacSha2EncryptedData, SecretEncryptionAlgorithm, SecretEventContent};
+
+    #[test]
+    fn algorithm_serde() {
+        assert_eq!(
+            to_json_value(SecretEncryptionAlgorithm::AesHmacSha2).unwrap(),
+            json!("m.secret_storage.v1.aes-hmac-sha2")
+        );
+        assert_eq!(
+            from_json_value::<SecretEncryptionAlgorithm>(json!("org.example.algorithm")).unwrap(),
+            SecretEncryptionAlgorithm::Custom("org.example.algorithm".to_string())
+        );
+    }
+
+    #[test]
+    fn secret_content_serde() {
+        let content = SecretEventContent {
+            encrypted: btreemap! {
+                "my_key".to_string() => AesHmacSha2EncryptedData {
+                    iv: "YWJjZGVmZ2hpamtsbW5vcA".to_string(),
+                    ciphertext: "dGhpc2lzZGVmaW5pdGVseWNpcGhlcnRleHQ".to_string(),
+                    mac: "aWRvbnRrbm93d2hhdGFtYWNsb29rc2xpa2U".to_string(),
+                },
+            },
+        };
+        let json_data = json!({
+            "encrypted": {
+                "my_key": {
+                    "iv": "YWJjZGVmZ2hpamtsbW5vcA",
+                    "ciphertext": "dGhpc2lzZGVmaW5pdGVseWNpcGhlcnRleHQ",
+                    "mac": "aWRvbnRrbm93d2hhdGFtYWNsb29rc2xpa2U"
+                }
+            }
+        });
+
+        assert_eq!(to_json_value(&content).unwrap(), json_data);
+        assert_eq!(
+            from_json_value::<SecretEventContent>(json_data)
+                .unwrap()
+                .encrypted["my_key"],
+            content.encrypted["my_key"]
+        );
+    }
+}
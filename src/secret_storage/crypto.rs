@@ -0,0 +1,447 @@
+//! Derivation of secret storage keys and encryption of secrets with the
+//! *m.secret_storage.v1.aes-hmac-sha2* algorithm.
+
+use std::{
+    convert::TryFrom,
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+};
+
+use aes::Aes256;
+use ctr::{
+    cipher::{KeyIvInit, StreamCipher},
+    Ctr128BE,
+};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use js_int::UInt;
+use pbkdf2::pbkdf2_hmac;
+use rand::{distributions::Alphanumeric, thread_rng, Rng, RngCore};
+use sha2::{Sha256, Sha512};
+
+use super::{
+    key::{KeyDerivationAlgorithm, KeyEventContent, PassPhrase},
+    AesHmacSha2EncryptedData, SecretEncryptionAlgorithm,
+};
+use crate::util::{decode_base64, encode_base64};
+
+type Aes256Ctr = Ctr128BE<Aes256>;
+
+/// The size of a secret storage key in bytes.
+const KEY_SIZE: usize = 32;
+
+/// The size of an AES-CTR initialization vector in bytes.
+const IV_SIZE: usize = 16;
+
+/// The prefix of a decoded recovery key.
+const RECOVERY_KEY_PREFIX: [u8; 2] = [0x8b, 0x01];
+
+/// The length of a generated passphrase salt.
+const SALT_LENGTH: usize = 32;
+
+/// The maximum number of PBKDF2 iterations accepted when deriving a key from a passphrase.
+///
+/// Clients use a few hundred thousand iterations; the limit keeps a key description from making
+/// the derivation take arbitrarily long.
+pub const MAX_PASSPHRASE_ITERATIONS: u32 = 10_000_000;
+
+/// An error encountered while deriving a secret storage key or decrypting a secret.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SecretStorageError {
+    /// The key description uses an algorithm other than *m.secret_storage.v1.aes-hmac-sha2*, or
+    /// the passphrase info uses an algorithm other than *m.pbkdf2*.
+    UnsupportedAlgorithm,
+
+    /// The passphrase info asks for a key size other than 256 bits or more than
+    /// `MAX_PASSPHRASE_ITERATIONS` iterations.
+    InvalidPassPhrase,
+
+    /// The recovery key is not valid base58, has the wrong prefix, length, or parity.
+    InvalidRecoveryKey,
+
+    /// The initialization vector, ciphertext or MAC is not valid base64 or has the wrong length.
+    InvalidEncoding,
+
+    /// The MAC of the ciphertext doesn't match, i.e. the key or the secret name is wrong.
+    MacMismatch,
+
+    /// The decrypted secret is not valid UTF-8.
+    InvalidSecret,
+}
+
+impl Display for SecretStorageError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SecretStorageError::UnsupportedAlgorithm => write!(f, "unsupported algorithm"),
+            SecretStorageError::InvalidPassPhrase => write!(f, "invalid passphrase info"),
+            SecretStorageError::InvalidRecoveryKey => write!(f, "invalid recovery key"),
+            SecretStorageError::InvalidEncoding => write!(f, "invalid encrypted data encoding"),
+            SecretStorageError::MacMismatch => write!(f, "MAC of the encrypted data mismatch"),
+            SecretStorageError::InvalidSecret => write!(f, "decrypted secret is not UTF-8"),
+        }
+    }
+}
+
+impl Error for SecretStorageError {}
+
+/// A key used to encrypt secrets with the *m.secret_storage.v1.aes-hmac-sha2* algorithm.
+#[derive(Clone)]
+pub struct SecretStorageKey {
+    key: [u8; KEY_SIZE],
+}
+
+impl SecretStorageKey {
+    /// Generates a new random key.
+    pub fn generate() -> Self {
+        let mut key = [0; KEY_SIZE];
+        thread_rng().fill_bytes(&mut key);
+
+        Self { key }
+    }
+
+    /// Creates a key from its raw bytes.
+    pub fn from_bytes(key: [u8; KEY_SIZE]) -> Self {
+        Self { key }
+    }
+
+    /// The raw bytes of the key.
+    pub fn as_bytes(&self) -> &[u8; KEY_SIZE] {
+        &self.key
+    }
+
+    /// Derives a key from a passphrase, with a new random salt.
+    ///
+    /// Returns the key and the passphrase info to store in its key description, or
+    /// `SecretStorageError::InvalidPassPhrase` if `iterations` is greater than
+    /// `MAX_PASSPHRASE_ITERATIONS`.
+    pub fn with_passphrase(
+        passphrase: &str,
+        iterations: u32,
+    ) -> Result<(Self, PassPhrase), SecretStorageError> {
+        let salt: String = thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(SALT_LENGTH)
+            .map(char::from)
+            .collect();
+
+        let info = PassPhrase {
+            algorithm: KeyDerivationAlgorithm::Pbkdf2,
+            salt,
+            iterations: UInt::from(iterations),
+            bits: UInt::from(256_u32),
+        };
+        let key = Self::from_passphrase(passphrase, &info)?;
+
+        Ok((key, info))
+    }
+
+    /// Derives a key from a passphrase, using the parameters from a key description.
+    pub fn from_passphrase(
+        passphrase: &str,
+        info: &PassPhrase,
+    ) -> Result<Self, SecretStorageError> {
+        if info.algorithm != KeyDerivationAlgorithm::Pbkdf2 {
+            return Err(SecretStorageError::UnsupportedAlgorithm);
+        }
+
+        if info.bits != UInt::from(256_u32) {
+            return Err(SecretStorageError::InvalidPassPhrase);
+        }
+
+        let iterations = match u32::try_from(u64::from(info.iterations)) {
+            Ok(iterations) if iterations <= MAX_PASSPHRASE_ITERATIONS => iterations,
+            _ => return Err(SecretStorageError::InvalidPassPhrase),
+        };
+
+        let mut key = [0; KEY_SIZE];
+        pbkdf2_hmac::<Sha512>(
+            passphrase.as_bytes(),
+            info.salt.as_bytes(),
+            iterations,
+            &mut key,
+        );
+
+        Ok(Self { key })
+    }
+
+    /// Decodes a recovery key, as entered by the user.
+    ///
+    /// Whitespace in the recovery key is ignored.
+    pub fn from_recovery_key(recovery_key: &str) -> Result<Self, SecretStorageError> {
+        let recovery_key: String = recovery_key.split_whitespace().collect();
+        let bytes = bs58::decode(recovery_key)
+            .into_vec()
+            .map_err(|_| SecretStorageError::InvalidRecoveryKey)?;
+
+        if bytes.len() != RECOVERY_KEY_PREFIX.len() + KEY_SIZE + 1
+            || !bytes.starts_with(&RECOVERY_KEY_PREFIX)
+            || bytes.iter().fold(0, |parity, byte| parity ^ byte) != 0
+        {
+            return Err(SecretStorageError::InvalidRecoveryKey);
+        }
+
+        let mut key = [0; KEY_SIZE];
+        key.copy_from_slice(&bytes[RECOVERY_KEY_PREFIX.len()..bytes.len() - 1]);
+
+        Ok(Self { key })
+    }
+
+    /// Encodes the key as a recovery key to show to the user.
+    ///
+    /// The recovery key is split into groups of four characters separated by spaces.
+    pub fn to_recovery_key(&self) -> String {
+        let mut bytes = Vec::with_capacity(RECOVERY_KEY_PREFIX.len() + KEY_SIZE + 1);
+        bytes.extend_from_slice(&RECOVERY_KEY_PREFIX);
+        bytes.extend_from_slice(&self.key);
+        bytes.push(bytes.iter().fold(0, |parity, byte| parity ^ byte));
+
+        let encoded = bs58::encode(bytes).into_string();
+        encoded
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| std::str::from_utf8(chunk).expect("base58 is ASCII"))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Creates the content of the *m.secret_storage.key.[key ID]* event describing this key.
+    ///
+    /// The description contains an initialization vector and MAC that allow checking whether a
+    /// key entered by the user is correct with `check`.
+    pub fn key_description(
+        &self,
+        name: Option<String>,
+        passphrase: Option<PassPhrase>,
+    ) -> KeyEventContent {
+        let check = self.encrypt_bytes("", &[0; KEY_SIZE]);
+
+        KeyEventContent {
+            name,
+            algorithm: SecretEncryptionAlgorithm::AesHmacSha2,
+            passphrase,
+            iv: Some(check.iv),
+            mac: Some(check.mac),
+        }
+    }
+
+    /// Checks whether this is the key described by the given key description.
+    ///
+    /// Returns `false` if the description doesn't use the *m.secret_storage.v1.aes-hmac-sha2*
+    /// algorithm or doesn't contain the information to check the key.
+    pub fn check(&self, description: &KeyEventContent) -> bool {
+        if description.algorithm != SecretEncryptionAlgorithm::AesHmacSha2 {
+            return false;
+        }
+
+        let (iv, mac) = match (&description.iv, &description.mac) {
+            (Some(iv), Some(mac)) => (iv, mac),
+            _ => return false,
+        };
+        let (iv, mac) = match (decode_iv(iv), decode_base64(mac)) {
+            (Ok(iv), Ok(mac)) => (iv, mac),
+            _ => return false,
+        };
+
+        let (aes_key, mac_key) = self.derive_keys("");
+        let mut ciphertext = [0; KEY_SIZE];
+        Aes256Ctr::new(&aes_key.into(), &iv.into()).apply_keystream(&mut ciphertext);
+
+        hmac_sha256(&mac_key, &ciphertext)
+            .verify_slice(&mac)
+            .is_ok()
+    }
+
+    /// Encrypts a secret to store it in the account data event with the given name, e.g.
+    /// *m.cross_signing.master*.
+    pub fn encrypt(&self, name: &str, secret: &str) -> AesHmacSha2EncryptedData {
+        self.encrypt_bytes(name, secret.as_bytes())
+    }
+
+    /// Decrypts a secret stored in the account data event with the given name.
+    pub fn decrypt(
+        &self,
+        name: &str,
+        data: &AesHmacSha2EncryptedData,
+    ) -> Result<String, SecretStorageError> {
+        let iv = decode_iv(&data.iv)?;
+        let mut plaintext =
+            decode_base64(&data.ciphertext).map_err(|_| SecretStorageError::InvalidEncoding)?;
+        let mac = decode_base64(&data.mac).map_err(|_| SecretStorageError::InvalidEncoding)?;
+
+        let (aes_key, mac_key) = self.derive_keys(name);
+        hmac_sha256(&mac_key, &plaintext)
+            .verify_slice(&mac)
+            .map_err(|_| SecretStorageError::MacMismatch)?;
+
+        Aes256Ctr::new(&aes_key.into(), &iv.into()).apply_keystream(&mut plaintext);
+
+        String::from_utf8(plaintext).map_err(|_| SecretStorageError::InvalidSecret)
+    }
+
+    fn encrypt_bytes(&self, name: &str, plaintext: &[u8]) -> AesHmacSha2EncryptedData {
+        let mut iv = [0; IV_SIZE];
+        thread_rng().fill_bytes(&mut iv);
+        // Clear bit 63 of the counter so that it can't overflow into the nonce.
+        iv[8] &= 0x7f;
+
+        let (aes_key, mac_key) = self.derive_keys(name);
+        let mut ciphertext = plaintext.to_vec();
+        Aes256Ctr::new(&aes_key.into(), &iv.into()).apply_keystream(&mut ciphertext);
+        let mac = hmac_sha256(&mac_key, &ciphertext).finalize().into_bytes();
+
+        AesHmacSha2EncryptedData {
+            iv: encode_base64(iv),
+            ciphertext: encode_base64(ciphertext),
+            mac: encode_base64(mac),
+        }
+    }
+
+    /// Derives the AES and HMAC keys used to encrypt the secret with the given name.
+    fn derive_keys(&self, name: &str) -> ([u8; 32], [u8; 32]) {
+        let mut okm = [0; 64];
+        Hkdf::<Sha256>::new(Some(&[0; 32]), &self.key)
+            .expand(name.as_bytes(), &mut okm)
+            .expect("64 bytes is a valid length for HKDF-SHA-256");
+
+        let mut aes_key = [0; 32];
+        let mut mac_key = [0; 32];
+        aes_key.copy_from_slice(&okm[..32]);
+        mac_key.copy_from_slice(&okm[32..]);
+
+        (aes_key, mac_key)
+    }
+}
+
+impl Debug for SecretStorageKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretStorageKey").finish()
+    }
+}
+
+fn decode_iv(iv: &str) -> Result<[u8; IV_SIZE], SecretStorageError> {
+    let bytes = decode_base64(iv).map_err(|_| SecretStorageError::InvalidEncoding)?;
+    if bytes.len() != IV_SIZE {
+        return Err(SecretStorageError::InvalidEncoding);
+    }
+
+    let mut iv = [0; IV_SIZE];
+    iv.copy_from_slice(&bytes);
+
+    Ok(iv)
+}
+
+fn hmac_sha256(key: &[u8], input: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(input);
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use js_int::UInt;
+
+    use super::{SecretStorageError, SecretStorageKey, MAX_PASSPHRASE_ITERATIONS};
+    use crate::secret_storage::key::{KeyDerivationAlgorithm, PassPhrase};
+
+    #[test]
+    fn encrypt_decrypt_roundtrip() {
+        let key = SecretStorageKey::generate();
+        let encrypted = key.encrypt("m.cross_signing.master", "my secret");
+
+        assert_eq!(
+            key.decrypt("m.cross_signing.master", &encrypted).unwrap(),
+            "my secret"
+        );
+        assert!(matches!(
+            key.decrypt("m.cross_signing.self_signing", &encrypted),
+            Err(SecretStorageError::MacMismatch)
+        ));
+        assert!(matches!(
+            SecretStorageKey::generate().decrypt("m.cross_signing.master", &encrypted),
+            Err(SecretStorageError::MacMismatch)
+        ));
+    }
+
+    #[test]
+    fn key_check() {
+        let key = SecretStorageKey::generate();
+        let description = key.key_description(Some("Default key".to_string()), None);
+
+        assert!(key.check(&description));
+        assert!(!SecretStorageKey::generate().check(&description));
+    }
+
+    #[test]
+    fn passphrase() {
+        let info = PassPhrase {
+            algorithm: KeyDerivationAlgorithm::Pbkdf2,
+            salt: "salt".to_string(),
+            iterations: UInt::from(1_u32),
+            bits: UInt::from(256_u32),
+        };
+        let key = SecretStorageKey::from_passphrase("password", &info).unwrap();
+
+        // First 32 bytes of PBKDF2-HMAC-SHA-512("password", "salt", 1).
+        assert_eq!(
+            key.as_bytes(),
+            &[
+                0x86, 0x7f, 0x70, 0xcf, 0x1a, 0xde, 0x02, 0xcf, 0xf3, 0x75, 0x25, 0x99, 0xa3, 0xa5,
+                0x3d, 0xc4, 0xaf, 0x34, 0xc7, 0xa6, 0x69, 0x81, 0x5a, 0xe5, 0xd5, 0x13, 0x55, 0x4e,
+                0x1c, 0x8c, 0xf2, 0x52,
+            ]
+        );
+
+        let (key, info) = SecretStorageKey::with_passphrase("password", 10).unwrap();
+        assert_eq!(
+            SecretStorageKey::from_passphrase("password", &info)
+                .unwrap()
+                .as_bytes(),
+            key.as_bytes()
+        );
+    }
+
+    #[test]
+    fn too_many_passphrase_iterations() {
+        let info = PassPhrase {
+            algorithm: KeyDerivationAlgorithm::Pbkdf2,
+            salt: "salt".to_string(),
+            iterations: UInt::from(MAX_PASSPHRASE_ITERATIONS + 1),
+            bits: UInt::from(256_u32),
+        };
+
+        assert!(matches!(
+            SecretStorageKey::from_passphrase("password", &info),
+            Err(SecretStorageError::InvalidPassPhrase)
+        ));
+        assert!(matches!(
+            SecretStorageKey::with_passphrase("password", u32::MAX),
+            Err(SecretStorageError::InvalidPassPhrase)
+        ));
+    }
+
+    #[test]
+    fn recovery_key() {
+        let key = SecretStorageKey::from_bytes([0; 32]);
+        let recovery_key = key.to_recovery_key();
+
+        assert_eq!(
+            recovery_key,
+            "EsSz ygLv VP1b xF1C v7kE eBQx MxDP buG5 w25T L3b6 hfyG Kkrd"
+        );
+        assert_eq!(
+            SecretStorageKey::from_recovery_key(&recovery_key)
+                .unwrap()
+                .as_bytes(),
+            &[0; 32]
+        );
+
+        let mut wrong_parity = recovery_key.clone();
+        wrong_parity.pop();
+        wrong_parity.push('q');
+        assert!(matches!(
+            SecretStorageKey::from_recovery_key(&wrong_parity),
+            Err(SecretStorageError::InvalidRecoveryKey)
+        ));
+    }
+}
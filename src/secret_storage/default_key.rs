@@ -0,0 +1,19 @@
+//! Types for the *m.secret_storage.default_key* event.
+
+use ruma_events_macros::ruma_event;
+
+ruma_event! {
+    /// The ID of the default key for storing secrets.
+    ///
+    /// This is stored in the user's account data.
+    DefaultKeyEvent {
+        kind: Event,
+        event_type: "m.secret_storage.default_key",
+        content: {
+            /// The ID of the default key.
+            ///
+            /// Its description is stored in the *m.secret_storage.key.[key ID]* event.
+            pub key: String,
+        },
+    }
+}
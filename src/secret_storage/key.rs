@@ -0,0 +1,333 @@
+//! Types for the *m.secret_storage.key.[key ID]* events.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use js_int::UInt;
+use serde::{
+    de::Error as _, ser::SerializeStruct as _, Deserialize, Deserializer, Serialize, Serializer,
+};
+use serde_json::Value as JsonValue;
+
+use super::SecretEncryptionAlgorithm;
+use crate::{util::get_field, Event, EventType, FromRaw};
+
+/// The prefix of the type of the events describing secret storage keys.
+const EVENT_TYPE_PREFIX: &str = "m.secret_storage.key.";
+
+/// The description of a key for storing secrets.
+///
+/// This is stored in the user's account data, with the ID of the key as the suffix of the event
+/// type.
+#[derive(Clone, Debug)]
+pub struct KeyEvent {
+    /// The ID of the key.
+    pub key_id: String,
+
+    /// The event's content.
+    pub content: KeyEventContent,
+}
+
+/// The payload of an *m.secret_storage.key.[key ID]* event.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct KeyEventContent {
+    /// The name of the key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// The encryption algorithm the key is used with.
+    pub algorithm: SecretEncryptionAlgorithm,
+
+    /// Information on how to derive the key from a passphrase, if it was derived from one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passphrase: Option<PassPhrase>,
+
+    /// The initialization vector used to check the key, encoded as base64.
+    ///
+    /// Used with the *m.secret_storage.v1.aes-hmac-sha2* algorithm.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iv: Option<String>,
+
+    /// The MAC of 32 zero bytes encrypted with the key, encoded as base64.
+    ///
+    /// Used with the *m.secret_storage.v1.aes-hmac-sha2* algorithm to check that a key entered by
+    /// the user is correct.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac: Option<String>,
+}
+
+/// Information on how to derive a key from a passphrase.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PassPhrase {
+    /// The algorithm used to derive the key.
+    pub algorithm: KeyDerivationAlgorithm,
+
+    /// The salt used in the key derivation.
+    pub salt: String,
+
+    /// The number of iterations used in the key derivation.
+    pub iterations: UInt,
+
+    /// The number of bits of the derived key.
+    ///
+    /// Defaults to 256.
+    #[serde(default = "default_bits", skip_serializing_if = "is_default_bits")]
+    pub bits: UInt,
+}
+
+/// An algorithm for deriving a key from a passphrase.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub enum KeyDerivationAlgorithm {
+    /// PBKDF2 with SHA-512 as the hash function.
+    Pbkdf2,
+
+    /// Any algorithm that is not part of the specification.
+    Custom(String),
+}
+
+impl KeyEvent {
+    /// The type of the event, e.g. `m.secret_storage.key.abc`.
+    pub fn event_type_str(&self) -> String {
+        format!("{}{}", EVENT_TYPE_PREFIX, self.key_id)
+    }
+}
+
+impl Event for KeyEvent {
+    /// The type of this event's `content` field.
+    type Content = KeyEventContent;
+
+    /// The event's content.
+    fn content(&self) -> &Self::Content {
+        &self.content
+    }
+
+    /// The type of the event.
+    ///
+    /// Since the event type contains the key ID, this is always `EventType::Custom`.
+    fn event_type(&self) -> EventType {
+        EventType::Custom(self.event_type_str())
+    }
+}
+
+impl FromRaw for KeyEvent {
+    type Raw = Self;
+
+    fn from_raw(raw: Self) -> Self {
+        raw
+    }
+}
+
+impl FromRaw for KeyEventContent {
+    type Raw = Self;
+
+    fn from_raw(raw: Self) -> Self {
+        raw
+    }
+}
+
+impl Serialize for KeyEvent {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("KeyEvent", 2)?;
+
+        state.serialize_field("content", &self.content)?;
+        state.serialize_field("type", &self.event_type_str())?;
+
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for KeyEvent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = JsonValue::deserialize(deserializer)?;
+        let event_type: String = get_field(&value, "type")?;
+
+        if !is_key_event_type(&event_type) {
+            return Err(D::Error::custom(format!(
+                "expected an event type starting with `{}`",
+                EVENT_TYPE_PREFIX
+            )));
+        }
+        let key_id = event_type[EVENT_TYPE_PREFIX.len()..].to_string();
+
+        Ok(Self {
+            key_id,
+            content: get_field(&value, "content")?,
+        })
+    }
+}
+
+/// Whether the given event type is the type of an *m.secret_storage.key.[key ID]* event.
+pub(crate) fn is_key_event_type(event_type: &str) -> bool {
+    event_type.len() > EVENT_TYPE_PREFIX.len() && event_type.starts_with(EVENT_TYPE_PREFIX)
+}
+
+impl Display for KeyDerivationAlgorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let algorithm_str = match *self {
+            KeyDerivationAlgorithm::Pbkdf2 => "m.pbkdf2",
+            KeyDerivationAlgorithm::Custom(ref algorithm) => algorithm,
+        };
+
+        write!(f, "{}", algorithm_str)
+    }
+}
+
+impl<T> From<T> for KeyDerivationAlgorithm
+where
+    T: Into<String> + AsRef<str>,
+{
+    fn from(s: T) -> KeyDerivationAlgorithm {
+        match s.as_ref() {
+            "m.pbkdf2" => KeyDerivationAlgorithm::Pbkdf2,
+            _ => KeyDerivationAlgorithm::Custom(s.into()),
+        }
+    }
+}
+
+impl From<KeyDerivationAlgorithm> for String {
+    fn from(algorithm: KeyDerivationAlgorithm) -> String {
+        algorithm.to_string()
+    }
+}
+
+fn default_bits() -> UInt {
+    UInt::from(256_u32)
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_default_bits(bits: &UInt) -> bool {
+    *bits == default_bits()
+}
+
+#[cfg(test)]
+mod tests {
+    use js_int::UInt;
+    use matches::assert_matches;
+    use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
+
+    use super::{KeyDerivationAlgorithm, KeyEvent, KeyEventContent, PassPhrase};
+    use crate::{
+        collections::only, secret_storage::SecretEncryptionAlgorithm, Event, EventJson, EventType,
+    };
+
+    #[test]
+    fn serialization() {
+        let event = KeyEvent {
+            key_id: "my_key".to_string(),
+            content: KeyEventContent {
+                name: Some("Recovery key".to_string()),
+                algorithm: SecretEncryptionAlgorithm::AesHmacSha2,
+                passphrase: Some(PassPhrase {
+                    algorithm: KeyDerivationAlgorithm::Pbkdf2,
+                    salt: "MmMsAlty".to_string(),
+                    iterations: UInt::from(100_000_u32),
+                    bits: UInt::from(256_u32),
+                }),
+                iv: Some("gH2iNpiETFhApvW6/FFEJQ".to_string()),
+                mac: Some("9Lw12m5SKDipNghdQXKjgpfdj1/K7HFI2brO+UWAGoM".to_string()),
+            },
+        };
+
+        assert_eq!(
+            event.event_type(),
+            EventType::Custom("m.secret_storage.key.my_key".to_string())
+        );
+        assert_eq!(
+            to_json_value(&event).unwrap(),
+            json!({
+                "content": {
+                    "name": "Recovery key",
+                    "algorithm": "m.secret_storage.v1.aes-hmac-sha2",
+                    "passphrase": {
+                        "algorithm": "m.pbkdf2",
+                        "salt": "MmMsAlty",
+                        "iterations": 100_000
+                    },
+                    "iv": "gH2iNpiETFhApvW6/FFEJQ",
+                    "mac": "9Lw12m5SKDipNghdQXKjgpfdj1/K7HFI2brO+UWAGoM"
+                },
+                "type": "m.secret_storage.key.my_key"
+            })
+        );
+    }
+
+    #[test]
+    fn deserialization() {
+        let json_data = json!({
+            "content": {
+                "algorithm": "m.secret_storage.v1.aes-hmac-sha2",
+                "passphrase": {
+                    "algorithm": "m.pbkdf2",
+                    "salt": "MmMsAlty",
+                    "iterations": 100_000,
+                    "bits": 512
+                }
+            },
+            "type": "m.secret_storage.key.my_key"
+        });
+
+        assert_matches!(
+            from_json_value::<EventJson<KeyEvent>>(json_data)
+                .unwrap()
+                .deserialize()
+                .unwrap(),
+            KeyEvent {
+                key_id,
+                content: KeyEventContent {
+                    name: None,
+                    algorithm: SecretEncryptionAlgorithm::AesHmacSha2,
+                    passphrase: Some(PassPhrase {
+                        algorithm: KeyDerivationAlgorithm::Pbkdf2,
+                        salt,
+                        iterations,
+                        bits,
+                    }),
+                    iv: None,
+                    mac: None,
+                },
+            } if key_id == "my_key"
+                && salt == "MmMsAlty"
+                && iterations == UInt::from(100_000_u32)
+                && bits == UInt::from(512_u32)
+        );
+    }
+
+    #[test]
+    fn deserialization_of_wrong_event_type() {
+        let json_data = json!({
+            "content": {
+                "algorithm": "m.secret_storage.v1.aes-hmac-sha2"
+            },
+            "type": "m.secret_storage.default_key"
+        });
+
+        assert!(from_json_value::<EventJson<KeyEvent>>(json_data)
+            .unwrap()
+            .deserialize()
+            .is_err());
+    }
+
+    #[test]
+    fn deserialization_in_collection() {
+        let json_data = json!({
+            "content": {
+                "algorithm": "m.secret_storage.v1.aes-hmac-sha2"
+            },
+            "type": "m.secret_storage.key.my_key"
+        });
+
+        assert_matches!(
+            from_json_value::<EventJson<only::Event>>(json_data)
+                .unwrap()
+                .deserialize()
+                .unwrap(),
+            only::Event::SecretStorageKey(KeyEvent { key_id, .. }) if key_id == "my_key"
+        );
+    }
+}
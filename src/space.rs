@@ -0,0 +1,4 @@
+//! Modules for events in the *m.space* namespace.
+
+pub mod child;
+pub mod parent;
@@ -0,0 +1,119 @@
+//! Types for the *m.space.child* event.
+
+use ruma_events_macros::ruma_event;
+use ruma_identifiers::RoomId;
+
+ruma_event! {
+    /// Adds a room to a space. The state key is the ID of the child room.
+    ///
+    /// A child without `via` servers is not part of the space anymore.
+    SpaceChildEvent {
+        kind: StateEvent,
+        event_type: "m.space.child",
+        state_key_type: RoomId,
+        content: {
+            /// Servers to try to join the child room through.
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub via: Option<Vec<String>>,
+
+            /// A string used to order the children of the space lexicographically.
+            ///
+            /// Orders which are longer than 50 characters or contain characters outside of the
+            /// printable ASCII range are ignored, see `SpaceChildEventContent::valid_order`.
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub order: Option<String>,
+
+            /// Whether the child room is suggested to members of the space.
+            #[serde(default, skip_serializing_if = "ruma_serde::is_default")]
+            pub suggested: bool,
+        },
+    }
+}
+
+impl SpaceChildEventContent {
+    /// Whether the child room is part of the space, i.e. whether `via` is set and not empty.
+    pub fn is_valid(&self) -> bool {
+        match &self.via {
+            Some(via) => !via.is_empty(),
+            None => false,
+        }
+    }
+
+    /// The `order` of the child room, if it is valid.
+    pub fn valid_order(&self) -> Option<&str> {
+        self.order.as_deref().filter(|order| {
+            order.len() <= 50 && order.bytes().all(|byte| (0x20..=0x7e).contains(&byte))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use matches::assert_matches;
+    use ruma_identifiers::RoomId;
+    use serde_json::{from_value as from_json_value, json, to_value as to_json_value};
+
+    use super::{SpaceChildEvent, SpaceChildEventContent};
+    use crate::{EventJson, StateEvent};
+
+    #[test]
+    fn serialization() {
+        let content = SpaceChildEventContent {
+            via: Some(vec!["example.org".to_string()]),
+            order: Some("lobby".to_string()),
+            suggested: true,
+        };
+        let json = json!({
+            "via": ["example.org"],
+            "order": "lobby",
+            "suggested": true
+        });
+
+        assert_eq!(to_json_value(&content).unwrap(), json);
+    }
+
+    #[test]
+    fn deserialization() {
+        let json = json!({
+            "content": {
+                "via": ["example.org"],
+                "order": "\u{1f3e0}"
+            },
+            "event_id": "$h29iv0s8:example.com",
+            "origin_server_ts": 1,
+            "room_id": "!space:example.com",
+            "sender": "@carl:example.com",
+            "state_key": "!child:example.org",
+            "type": "m.space.child"
+        });
+
+        let event = from_json_value::<EventJson<SpaceChildEvent>>(json)
+            .unwrap()
+            .deserialize()
+            .unwrap();
+        assert_eq!(
+            event.state_key,
+            RoomId::try_from("!child:example.org").unwrap()
+        );
+        assert_eq!(event.state_key(), "!child:example.org");
+        assert!(event.content.is_valid());
+        assert!(!event.content.suggested);
+        assert_eq!(event.content.valid_order(), None);
+
+        assert_matches!(
+            from_json_value::<EventJson<SpaceChildEvent>>(json!({
+                "content": {},
+                "event_id": "$h29iv0s8:example.com",
+                "origin_server_ts": 1,
+                "sender": "@carl:example.com",
+                "state_key": "not a room ID",
+                "type": "m.space.child"
+            }))
+            .unwrap()
+            .deserialize(),
+            Err(_)
+        );
+    }
+}
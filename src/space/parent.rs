@@ -0,0 +1,22 @@
+//! Types for the *m.space.parent* event.
+
+use ruma_events_macros::ruma_event;
+use ruma_identifiers::RoomId;
+
+ruma_event! {
+    /// Declares a space a room belongs to. The state key is the ID of the parent space.
+    SpaceParentEvent {
+        kind: StateEvent,
+        event_type: "m.space.parent",
+        state_key_type: RoomId,
+        content: {
+            /// Servers to try to join the parent space through.
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub via: Option<Vec<String>>,
+
+            /// Whether the parent space is the main space of the room.
+            #[serde(default, skip_serializing_if = "ruma_serde::is_default")]
+            pub canonical: bool,
+        },
+    }
+}
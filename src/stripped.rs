@@ -10,6 +10,7 @@ use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 use serde_json::Value as JsonValue;
 
 use crate::{
+    call::{group::GroupCallEventContent, member::CallMemberEventContent},
     room::{
         aliases::AliasesEventContent, avatar::AvatarEventContent,
         canonical_alias::CanonicalAliasEventContent, create::CreateEventContent,
@@ -27,6 +28,12 @@ use crate::{
 #[serde(untagged)]
 #[allow(clippy::large_enum_variant)]
 pub enum AnyStrippedStateEvent {
+    /// A stripped-down version of the *m.call* event.
+    Call(StrippedCall),
+
+    /// A stripped-down version of the *m.call.member* event.
+    CallMember(StrippedCallMember),
+
     /// A stripped-down version of the *m.room.aliases* event.
     RoomAliases(StrippedRoomAliases),
 
@@ -82,6 +89,12 @@ pub struct StrippedStateEvent<C> {
     pub sender: UserId,
 }
 
+/// A stripped-down version of the *m.call* event.
+pub type StrippedCall = StrippedStateEvent<GroupCallEventContent>;
+
+/// A stripped-down version of the *m.call.member* event.
+pub type StrippedCallMember = StrippedStateEvent<CallMemberEventContent>;
+
 /// A stripped-down version of the *m.room.aliases* event.
 pub type StrippedRoomAliases = StrippedStateEvent<AliasesEventContent>;
 
@@ -127,6 +140,8 @@ impl TryFromRaw for AnyStrippedStateEvent {
         use raw::StrippedState::*;
 
         match raw {
+            Call(c) => conv(AnyStrippedStateEvent::Call, c),
+            CallMember(c) => conv(AnyStrippedStateEvent::CallMember, c),
             RoomAliases(c) => conv(AnyStrippedStateEvent::RoomAliases, c),
             RoomAvatar(c) => conv(AnyStrippedStateEvent::RoomAvatar, c),
             RoomCanonicalAlias(c) => conv(AnyStrippedStateEvent::RoomCanonicalAlias, c),
@@ -186,6 +201,7 @@ mod raw {
 
     use super::StrippedStateEvent;
     use crate::{
+        call::{group::raw::GroupCallEventContent, member::raw::CallMemberEventContent},
         room::{
             aliases::raw::AliasesEventContent, avatar::raw::AvatarEventContent,
             canonical_alias::raw::CanonicalAliasEventContent, create::raw::CreateEventContent,
@@ -198,6 +214,12 @@ mod raw {
         util::get_field,
     };
 
+    /// A stripped-down version of the *m.call* event.
+    pub type StrippedCall = StrippedStateEvent<GroupCallEventContent>;
+
+    /// A stripped-down version of the *m.call.member* event.
+    pub type StrippedCallMember = StrippedStateEvent<CallMemberEventContent>;
+
     /// A stripped-down version of the *m.room.aliases* event.
     pub type StrippedRoomAliases = StrippedStateEvent<AliasesEventContent>;
 
@@ -236,8 +258,14 @@ mod raw {
 
     /// A stripped-down version of a state event that is included along with some other events.
     #[derive(Clone, Debug)]
-    #[allow(clippy::large_enum_variant)]
+    #[allow(clippy::enum_variant_names, clippy::large_enum_variant)]
     pub enum StrippedState {
+        /// A stripped-down version of the *m.call* event.
+        Call(StrippedCall),
+
+        /// A stripped-down version of the *m.call.member* event.
+        CallMember(StrippedCallMember),
+
         /// A stripped-down version of the *m.room.aliases* event.
         RoomAliases(StrippedRoomAliases),
 
@@ -288,6 +316,8 @@ mod raw {
             let event_type = get_field(&value, "type")?;
 
             match event_type {
+                Call => from_value(value, StrippedState::Call),
+                CallMember => from_value(value, StrippedState::CallMember),
                 RoomAliases => from_value(value, StrippedState::RoomAliases),
                 RoomAvatar => from_value(value, StrippedState::RoomAvatar),
                 RoomCanonicalAlias => from_value(value, StrippedState::RoomCanonicalAlias),
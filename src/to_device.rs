@@ -12,12 +12,14 @@ use crate::{
     dummy::DummyEventContent,
     forwarded_room_key::ForwardedRoomKeyEventContent,
     key::verification::{
-        accept::AcceptEventContent, cancel::CancelEventContent, key::KeyEventContent,
-        mac::MacEventContent, request::RequestEventContent, start::StartEventContent,
+        accept::AcceptEventContent, cancel::CancelEventContent, done::DoneEventContent,
+        key::KeyEventContent, mac::MacEventContent, ready::ReadyEventContent,
+        request::RequestEventContent, start::StartEventContent,
     },
     room::encrypted::EncryptedEventContent,
-    room_key::RoomKeyEventContent,
+    room_key::{withheld::WithheldEventContent, RoomKeyEventContent},
     room_key_request::RoomKeyRequestEventContent,
+    secret::{request::RequestEventContent as SecretRequestEventContent, send::SendEventContent},
     util::get_field,
     TryFromRaw,
 };
@@ -37,6 +39,8 @@ pub enum AnyToDeviceEvent {
     ForwardedRoomKey(ToDeviceForwardedRoomKey),
     /// To-device version of the *m.room_key_request* event.
     RoomKeyRequest(ToDeviceRoomKeyRequest),
+    /// To-device version of the *m.room_key.withheld* event.
+    RoomKeyWithheld(ToDeviceRoomKeyWithheld),
     /// To-device version of the *m.key.verification.start* event.
     KeyVerificationStart(ToDeviceVerificationStart),
     /// To-device version of the *m.key.verification.accept* event.
@@ -49,6 +53,16 @@ pub enum AnyToDeviceEvent {
     KeyVerificationCancel(ToDeviceVerificationCancel),
     /// To-device version of the *m.key.verification.request* event.
     KeyVerificationRequest(ToDeviceVerificationRequest),
+    /// To-device version of the *m.key.verification.ready* event.
+    KeyVerificationReady(ToDeviceVerificationReady),
+    /// To-device version of the *m.key.verification.done* event.
+    KeyVerificationDone(ToDeviceVerificationDone),
+    /// To-device version of the *m.secret.request* event.
+    SecretRequest(ToDeviceSecretRequest),
+    /// To-device version of the *m.secret.send* event.
+    SecretSend(ToDeviceSecretSend),
+    /// A to-device event of a type not covered by the other variants.
+    Custom(ToDeviceCustom),
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -75,6 +89,9 @@ pub type ToDeviceForwardedRoomKey = ToDeviceEvent<ForwardedRoomKeyEventContent>;
 /// To-device version of the *m.room_key_request* event.
 pub type ToDeviceRoomKeyRequest = ToDeviceEvent<RoomKeyRequestEventContent>;
 
+/// To-device version of the *m.room_key.withheld* event.
+pub type ToDeviceRoomKeyWithheld = ToDeviceEvent<WithheldEventContent>;
+
 /// To-device version of the *m.key.verification.start* event.
 pub type ToDeviceVerificationStart = ToDeviceEvent<StartEventContent>;
 
@@ -93,6 +110,33 @@ pub type ToDeviceVerificationCancel = ToDeviceEvent<CancelEventContent>;
 /// To-device version of the *m.key.verification.request* event.
 pub type ToDeviceVerificationRequest = ToDeviceEvent<RequestEventContent>;
 
+/// To-device version of the *m.key.verification.ready* event.
+pub type ToDeviceVerificationReady = ToDeviceEvent<ReadyEventContent>;
+
+/// To-device version of the *m.key.verification.done* event.
+pub type ToDeviceVerificationDone = ToDeviceEvent<DoneEventContent>;
+
+/// To-device version of the *m.secret.request* event.
+pub type ToDeviceSecretRequest = ToDeviceEvent<SecretRequestEventContent>;
+
+/// To-device version of the *m.secret.send* event.
+///
+/// This event is only sent encrypted, so it only appears after decrypting an *m.room.encrypted*
+/// event.
+pub type ToDeviceSecretSend = ToDeviceEvent<SendEventContent>;
+
+/// To-device version of an event not covered by the Matrix specification or this crate.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ToDeviceCustom {
+    /// The unique identifier for the user who sent this event.
+    pub sender: UserId,
+    /// The custom type of the event.
+    #[serde(rename = "type")]
+    pub event_type: String,
+    /// Data specific to the event type.
+    pub content: JsonValue,
+}
+
 impl TryFromRaw for AnyToDeviceEvent {
     type Raw = raw::AnyToDeviceEvent;
     type Err = String;
@@ -107,12 +151,18 @@ impl TryFromRaw for AnyToDeviceEvent {
             RoomEncrypted(c) => conv(AnyToDeviceEvent::RoomEncrypted, c),
             ForwardedRoomKey(c) => conv(AnyToDeviceEvent::ForwardedRoomKey, c),
             RoomKeyRequest(c) => conv(AnyToDeviceEvent::RoomKeyRequest, c),
+            RoomKeyWithheld(c) => conv(AnyToDeviceEvent::RoomKeyWithheld, c),
             KeyVerificationStart(c) => conv(AnyToDeviceEvent::KeyVerificationStart, c),
             KeyVerificationAccept(c) => conv(AnyToDeviceEvent::KeyVerificationAccept, c),
             KeyVerificationKey(c) => conv(AnyToDeviceEvent::KeyVerificationKey, c),
             KeyVerificationMac(c) => conv(AnyToDeviceEvent::KeyVerificationMac, c),
             KeyVerificationCancel(c) => conv(AnyToDeviceEvent::KeyVerificationCancel, c),
             KeyVerificationRequest(c) => conv(AnyToDeviceEvent::KeyVerificationRequest, c),
+            KeyVerificationReady(c) => conv(AnyToDeviceEvent::KeyVerificationReady, c),
+            KeyVerificationDone(c) => conv(AnyToDeviceEvent::KeyVerificationDone, c),
+            SecretRequest(c) => conv(AnyToDeviceEvent::SecretRequest, c),
+            SecretSend(c) => conv(AnyToDeviceEvent::SecretSend, c),
+            Custom(c) => Ok(AnyToDeviceEvent::Custom(c)),
         }
     }
 }
@@ -155,18 +205,23 @@ mod raw {
     use serde::{Deserialize, Deserializer};
     use serde_json::Value as JsonValue;
 
-    use super::ToDeviceEvent;
+    use super::{ToDeviceCustom, ToDeviceEvent};
     use crate::{
         dummy::DummyEventContent,
         forwarded_room_key::raw::ForwardedRoomKeyEventContent,
         key::verification::{
             accept::raw::AcceptEventContent, cancel::raw::CancelEventContent,
-            key::raw::KeyEventContent, mac::raw::MacEventContent,
-            request::raw::RequestEventContent, start::raw::StartEventContent,
+            done::raw::DoneEventContent, key::raw::KeyEventContent, mac::raw::MacEventContent,
+            ready::raw::ReadyEventContent, request::raw::RequestEventContent,
+            start::raw::StartEventContent,
         },
         room::encrypted::raw::EncryptedEventContent,
-        room_key::raw::RoomKeyEventContent,
+        room_key::{raw::RoomKeyEventContent, withheld::raw::WithheldEventContent},
         room_key_request::raw::RoomKeyRequestEventContent,
+        secret::{
+            request::raw::RequestEventContent as SecretRequestEventContent,
+            send::raw::SendEventContent,
+        },
         util::get_field,
     };
 
@@ -180,6 +235,8 @@ mod raw {
     pub type ToDeviceForwardedRoomKey = ToDeviceEvent<ForwardedRoomKeyEventContent>;
     /// To-device version of the *m.room_key_request* event.
     pub type ToDeviceRoomKeyRequest = ToDeviceEvent<RoomKeyRequestEventContent>;
+    /// To-device version of the *m.room_key.withheld* event.
+    pub type ToDeviceRoomKeyWithheld = ToDeviceEvent<WithheldEventContent>;
     /// To-device version of the *m.key.verification.start* event.
     pub type ToDeviceVerificationStart = ToDeviceEvent<StartEventContent>;
     /// To-device version of the *m.key.verification.accept* event.
@@ -192,6 +249,14 @@ mod raw {
     pub type ToDeviceVerificationCancel = ToDeviceEvent<CancelEventContent>;
     /// To-device version of the *m.key.verification.request* event.
     pub type ToDeviceVerificationRequest = ToDeviceEvent<RequestEventContent>;
+    /// To-device version of the *m.key.verification.ready* event.
+    pub type ToDeviceVerificationReady = ToDeviceEvent<ReadyEventContent>;
+    /// To-device version of the *m.key.verification.done* event.
+    pub type ToDeviceVerificationDone = ToDeviceEvent<DoneEventContent>;
+    /// To-device version of the *m.secret.request* event.
+    pub type ToDeviceSecretRequest = ToDeviceEvent<SecretRequestEventContent>;
+    /// To-device version of the *m.secret.send* event.
+    pub type ToDeviceSecretSend = ToDeviceEvent<SendEventContent>;
 
     /// A stripped-down version of a state event that is included along with some other events.
     #[derive(Clone, Debug)]
@@ -207,6 +272,8 @@ mod raw {
         ForwardedRoomKey(ToDeviceForwardedRoomKey),
         /// To-device version of the *m.room_key_request* event.
         RoomKeyRequest(ToDeviceRoomKeyRequest),
+        /// To-device version of the *m.room_key.withheld* event.
+        RoomKeyWithheld(ToDeviceRoomKeyWithheld),
         /// To-device version of the *m.key.verification.start* event.
         KeyVerificationStart(ToDeviceVerificationStart),
         /// To-device version of the *m.key.verification.accept* event.
@@ -219,6 +286,16 @@ mod raw {
         KeyVerificationCancel(ToDeviceVerificationCancel),
         /// To-device version of the *m.key.verification.request* event.
         KeyVerificationRequest(ToDeviceVerificationRequest),
+        /// To-device version of the *m.key.verification.ready* event.
+        KeyVerificationReady(ToDeviceVerificationReady),
+        /// To-device version of the *m.key.verification.done* event.
+        KeyVerificationDone(ToDeviceVerificationDone),
+        /// To-device version of the *m.secret.request* event.
+        SecretRequest(ToDeviceSecretRequest),
+        /// To-device version of the *m.secret.send* event.
+        SecretSend(ToDeviceSecretSend),
+        /// A to-device event of a type not covered by the other variants.
+        Custom(ToDeviceCustom),
     }
 
     impl<'de> Deserialize<'de> for AnyToDeviceEvent {
@@ -227,7 +304,6 @@ mod raw {
             D: Deserializer<'de>,
         {
             use crate::{util::try_variant_from_value as from_value, EventType::*};
-            use serde::de::Error as _;
 
             // TODO: Optimize, what should be optimized here? Can we expand this
             // comment?
@@ -240,6 +316,7 @@ mod raw {
                 RoomEncrypted => from_value(value, AnyToDeviceEvent::RoomEncrypted),
                 ForwardedRoomKey => from_value(value, AnyToDeviceEvent::ForwardedRoomKey),
                 RoomKeyRequest => from_value(value, AnyToDeviceEvent::RoomKeyRequest),
+                RoomKeyWithheld => from_value(value, AnyToDeviceEvent::RoomKeyWithheld),
                 KeyVerificationStart => from_value(value, AnyToDeviceEvent::KeyVerificationStart),
                 KeyVerificationAccept => from_value(value, AnyToDeviceEvent::KeyVerificationAccept),
                 KeyVerificationKey => from_value(value, AnyToDeviceEvent::KeyVerificationKey),
@@ -248,7 +325,11 @@ mod raw {
                 KeyVerificationRequest => {
                     from_value(value, AnyToDeviceEvent::KeyVerificationRequest)
                 }
-                _ => Err(D::Error::custom("unknown to-device event")),
+                KeyVerificationReady => from_value(value, AnyToDeviceEvent::KeyVerificationReady),
+                KeyVerificationDone => from_value(value, AnyToDeviceEvent::KeyVerificationDone),
+                SecretRequest => from_value(value, AnyToDeviceEvent::SecretRequest),
+                SecretSend => from_value(value, AnyToDeviceEvent::SecretSend),
+                _ => from_value(value, AnyToDeviceEvent::Custom),
             }
         }
     }
@@ -270,8 +351,10 @@ mod tests {
             cancel::CancelCode, start::StartEventContent, HashAlgorithm, KeyAgreementProtocol,
             MessageAuthenticationCode, ShortAuthenticationString, VerificationMethod,
         },
-        room::encrypted::EncryptedEventContent,
+        room::encrypted::{Curve25519Key, EncryptedEventContent, OlmMessageType},
+        room_key::withheld::WithheldCode,
         room_key_request::Action,
+        secret::request::RequestAction,
         Algorithm, Empty, EventJson,
     };
 
@@ -346,11 +429,11 @@ mod tests {
             "content": {
                 "sender_key": "test_sender_key",
                 "ciphertext": {
-                    "sender_key_0": {
+                    "3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI": {
                         "body": "ciphertext0",
                         "type": 0
                     },
-                    "sender_key_1": {
+                    "lEuiRJBit0IG6nUf5pUzWTUEsRVVe/HJkoKuEww9ULI": {
                         "body": "ciphertext1",
                         "type": 1
                     }
@@ -370,10 +453,18 @@ mod tests {
 
         assert_eq!(content.sender_key, "test_sender_key");
         assert_eq!(content.ciphertext.len(), 2);
-        assert_eq!(content.ciphertext["sender_key_0"].body, "ciphertext0");
-        assert_eq!(content.ciphertext["sender_key_0"].message_type, 0u16.into());
-        assert_eq!(content.ciphertext["sender_key_1"].body, "ciphertext1");
-        assert_eq!(content.ciphertext["sender_key_1"].message_type, 1u16.into());
+        let key_0 = Curve25519Key::try_from("3C5BFWi2Y8MaVvjM8M22DBmh24PmgR0nPvJOIArzgyI").unwrap();
+        let key_1 = Curve25519Key::try_from("lEuiRJBit0IG6nUf5pUzWTUEsRVVe/HJkoKuEww9ULI").unwrap();
+        assert_eq!(content.ciphertext[&key_0].body, "ciphertext0");
+        assert_eq!(
+            content.ciphertext[&key_0].message_type,
+            OlmMessageType::PreKey
+        );
+        assert_eq!(content.ciphertext[&key_1].body, "ciphertext1");
+        assert_eq!(
+            content.ciphertext[&key_1].message_type,
+            OlmMessageType::Normal
+        );
     }
 
     #[test]
@@ -466,6 +557,48 @@ mod tests {
         assert_eq!(event.content.requesting_device_id, "RJYKSTBOIE");
     }
 
+    #[test]
+    fn room_key_withheld() {
+        let source = json!({
+            "sender": "@alice:example.org",
+            "content": {
+                "algorithm": "m.megolm.v1.aes-sha2",
+                "room_id": "!test:localhost",
+                "session_id": "X3lUlvLELLYxeTx4yOVu6UDpasGEVO0Jbu+QFnm0cKQ",
+                "sender_key": "RF3s+E7RkTQTGF2d8Deol0FkQvgII2aJDf3/Jp5mxVU",
+                "code": "m.unverified",
+                "reason": "Device not verified"
+            },
+            "type": "m.room_key.withheld"
+        });
+
+        let event = deserialize! {source, AnyToDeviceEvent::RoomKeyWithheld};
+        assert_eq!(event.content.code, WithheldCode::Unverified);
+        assert_eq!(
+            event.content.room_id,
+            Some(RoomId::try_from("!test:localhost").unwrap())
+        );
+        assert_eq!(event.content.reason.as_deref(), Some("Device not verified"));
+    }
+
+    #[test]
+    fn room_key_withheld_no_olm() {
+        let source = json!({
+            "sender": "@alice:example.org",
+            "content": {
+                "algorithm": "m.megolm.v1.aes-sha2",
+                "sender_key": "RF3s+E7RkTQTGF2d8Deol0FkQvgII2aJDf3/Jp5mxVU",
+                "code": "m.no_olm"
+            },
+            "type": "m.room_key.withheld"
+        });
+
+        let event = deserialize! {source, AnyToDeviceEvent::RoomKeyWithheld};
+        assert_eq!(event.content.code, WithheldCode::NoOlm);
+        assert!(event.content.room_id.is_none());
+        assert!(event.content.session_id.is_none());
+    }
+
     #[test]
     fn key_verification_start() {
         let source = json!({
@@ -493,7 +626,10 @@ mod tests {
 
         let event = deserialize! {source, AnyToDeviceEvent::KeyVerificationStart};
 
-        let StartEventContent::MSasV1(content) = event.content;
+        let content = match event.content {
+            StartEventContent::MSasV1(content) => content,
+            _ => panic!("expected the m.sas.v1 method"),
+        };
 
         assert_eq!(content.from_device, "AliceDevice1");
         assert_eq!(content.hashes, &[HashAlgorithm::Sha256]);
@@ -647,4 +783,93 @@ mod tests {
             UNIX_EPOCH + Duration::from_millis(1_559_598_944_869)
         );
     }
+
+    #[test]
+    fn key_verification_ready() {
+        let source = json!({
+            "content": {
+                "from_device": "BobDevice1",
+                "methods": [
+                    "m.sas.v1"
+                ],
+                "transaction_id": "S0meUniqueAndOpaqueString"
+            },
+            "type": "m.key.verification.ready",
+            "sender": "@alice:example.org"
+        });
+
+        let event = deserialize! {source, AnyToDeviceEvent::KeyVerificationReady};
+        assert_eq!(event.content.transaction_id, "S0meUniqueAndOpaqueString");
+        assert_eq!(event.content.from_device, "BobDevice1");
+        assert_eq!(event.content.methods, &[VerificationMethod::MSasV1]);
+    }
+
+    #[test]
+    fn key_verification_done() {
+        let source = json!({
+            "content": {
+                "transaction_id": "S0meUniqueAndOpaqueString"
+            },
+            "type": "m.key.verification.done",
+            "sender": "@alice:example.org"
+        });
+
+        let event = deserialize! {source, AnyToDeviceEvent::KeyVerificationDone};
+        assert_eq!(event.content.transaction_id, "S0meUniqueAndOpaqueString");
+    }
+
+    #[test]
+    fn secret_request() {
+        let source = json!({
+            "content": {
+                "action": "request",
+                "name": "m.megolm_backup.v1",
+                "requesting_device_id": "ABCDEFG",
+                "request_id": "randomly_generated_id_9573"
+            },
+            "type": "m.secret.request",
+            "sender": "@alice:example.org"
+        });
+
+        let event = deserialize! {source, AnyToDeviceEvent::SecretRequest};
+        assert_eq!(
+            event.content.action,
+            RequestAction::Request {
+                name: "m.megolm_backup.v1".to_string()
+            }
+        );
+        assert_eq!(event.content.requesting_device_id, "ABCDEFG");
+        assert_eq!(event.content.request_id, "randomly_generated_id_9573");
+    }
+
+    #[test]
+    fn secret_send() {
+        let source = json!({
+            "content": {
+                "request_id": "randomly_generated_id_9573",
+                "secret": "ThisIsASecretDon'tTellAnyone"
+            },
+            "type": "m.secret.send",
+            "sender": "@alice:example.org"
+        });
+
+        let event = deserialize! {source, AnyToDeviceEvent::SecretSend};
+        assert_eq!(event.content.request_id, "randomly_generated_id_9573");
+        assert_eq!(event.content.secret, "ThisIsASecretDon'tTellAnyone");
+    }
+
+    #[test]
+    fn custom() {
+        let source = json!({
+            "content": {
+                "foo": "bar"
+            },
+            "type": "org.example.custom",
+            "sender": "@alice:example.org"
+        });
+
+        let event = deserialize! {source, AnyToDeviceEvent::Custom};
+        assert_eq!(event.event_type, "org.example.custom");
+        assert_eq!(event.content, json!({ "foo": "bar" }));
+    }
 }
@@ -22,6 +22,26 @@ where
         .map_err(serde_json_error_to_generic_de_error)
 }
 
+/// Deserializes an event whose type is used both in rooms and as a to-device event, e.g. a key
+/// verification event, into `in_room_variant` if it has an `event_id` and into
+/// `to_device_variant` otherwise.
+pub fn try_in_room_or_to_device_variant_from_value<R, T, U, E>(
+    value: JsonValue,
+    in_room_variant: fn(R) -> U,
+    to_device_variant: fn(T) -> U,
+) -> Result<U, E>
+where
+    R: DeserializeOwned,
+    T: DeserializeOwned,
+    E: serde::de::Error,
+{
+    if value.get("event_id").is_some() {
+        try_variant_from_value(value, in_room_variant)
+    } else {
+        try_variant_from_value(value, to_device_variant)
+    }
+}
+
 pub fn serde_json_error_to_generic_de_error<E: serde::de::Error>(error: serde_json::Error) -> E {
     E::custom(error.to_string())
 }
@@ -39,3 +59,95 @@ where
     )
     .map_err(serde_json_error_to_generic_de_error)
 }
+
+#[cfg(any(
+    feature = "cross-signing",
+    feature = "encrypted-attachments",
+    feature = "key-export",
+    feature = "sas",
+    feature = "secret-storage"
+))]
+mod base64_engines {
+    use base64::{
+        alphabet,
+        engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig},
+    };
+
+    const CONFIG: GeneralPurposeConfig = GeneralPurposeConfig::new()
+        .with_encode_padding(false)
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent);
+
+    /// Unpadded standard base64, which also accepts padded input.
+    pub const STANDARD: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, CONFIG);
+
+    /// Unpadded URL-safe base64, which also accepts padded input.
+    #[cfg(feature = "encrypted-attachments")]
+    pub const URL_SAFE: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, CONFIG);
+}
+
+/// Encodes the given bytes as unpadded base64, the encoding used throughout the Matrix
+/// end-to-end encryption specification.
+#[cfg(any(
+    feature = "cross-signing",
+    feature = "encrypted-attachments",
+    feature = "sas",
+    feature = "secret-storage"
+))]
+pub(crate) fn encode_base64(input: impl AsRef<[u8]>) -> String {
+    use base64::Engine as _;
+
+    base64_engines::STANDARD.encode(input)
+}
+
+/// Decodes base64, with or without padding.
+#[cfg(any(
+    feature = "cross-signing",
+    feature = "encrypted-attachments",
+    feature = "key-export",
+    feature = "sas",
+    feature = "secret-storage"
+))]
+pub(crate) fn decode_base64(input: impl AsRef<[u8]>) -> Result<Vec<u8>, base64::DecodeError> {
+    use base64::Engine as _;
+
+    base64_engines::STANDARD.decode(input)
+}
+
+/// Encodes the given bytes as unpadded URL-safe base64.
+#[cfg(feature = "encrypted-attachments")]
+pub(crate) fn encode_base64_url(input: impl AsRef<[u8]>) -> String {
+    use base64::Engine as _;
+
+    base64_engines::URL_SAFE.encode(input)
+}
+
+/// Decodes URL-safe base64, with or without padding.
+#[cfg(feature = "encrypted-attachments")]
+pub(crate) fn decode_base64_url(input: impl AsRef<[u8]>) -> Result<Vec<u8>, base64::DecodeError> {
+    use base64::Engine as _;
+
+    base64_engines::URL_SAFE.decode(input)
+}
+
+/// Serializes the given value as [canonical JSON](https://matrix.org/docs/spec/appendices#canonical-json).
+#[cfg(any(feature = "cross-signing", feature = "sas"))]
+pub(crate) fn to_canonical_json_string<T>(value: &T) -> Result<String, serde_json::Error>
+where
+    T: serde::Serialize,
+{
+    fn sort_keys(value: JsonValue) -> JsonValue {
+        match value {
+            JsonValue::Object(map) => {
+                let sorted: std::collections::BTreeMap<_, _> =
+                    map.into_iter().map(|(k, v)| (k, sort_keys(v))).collect();
+                JsonValue::Object(sorted.into_iter().collect())
+            }
+            JsonValue::Array(values) => {
+                JsonValue::Array(values.into_iter().map(sort_keys).collect())
+            }
+            value => value,
+        }
+    }
+
+    serde_json::to_string(&sort_keys(serde_json::to_value(value)?))
+}